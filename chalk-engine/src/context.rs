@@ -8,6 +8,7 @@
 use crate::CompleteAnswer;
 use chalk_ir::interner::Interner;
 use chalk_ir::Substitution;
+use chalk_solve::AmbiguityCause;
 use std::fmt::Debug;
 
 pub enum AnswerResult<I: Interner> {
@@ -17,8 +18,9 @@ pub enum AnswerResult<I: Interner> {
     /// No answer could be returned because there are no more solutions.
     NoMoreSolutions,
 
-    /// No answer could be returned because the goal has floundered.
-    Floundered,
+    /// No answer could be returned because the goal has floundered
+    /// (for the given reason).
+    Floundered(AmbiguityCause<I>),
 
     // No answer could be returned *yet*, because we exceeded our
     // quantum (`should_continue` returned false).
@@ -59,7 +61,7 @@ impl<I: Interner> Debug for AnswerResult<I> {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             AnswerResult::Answer(answer) => write!(fmt, "{:?}", answer),
-            AnswerResult::Floundered => write!(fmt, "Floundered"),
+            AnswerResult::Floundered(cause) => write!(fmt, "Floundered({})", cause),
             AnswerResult::NoMoreSolutions => write!(fmt, "None"),
             AnswerResult::QuantumExceeded => write!(fmt, "QuantumExceeded"),
         }
@@ -7,6 +7,7 @@ use crate::{TableIndex, TimeStamp};
 
 use chalk_ir::interner::Interner;
 use chalk_ir::{Goal, InEnvironment, Substitution, UCanonical};
+use chalk_solve::{AmbiguityCause, SolverStats};
 use tracing::debug;
 
 pub(crate) struct Forest<I: Interner> {
@@ -17,6 +18,20 @@ pub(crate) struct Forest<I: Interner> {
     /// This effectively gives us way to track what depth
     /// and loop a table or strand was last followed.
     pub(crate) clock: TimeStamp,
+
+    /// Whether strands and answers record how they were derived, so
+    /// that proof trees can be rebuilt for answers.
+    pub(crate) record_proofs: bool,
+
+    /// Statistics about the current (or most recent) query.
+    pub(crate) stats: SolverStats,
+
+    /// The number of strand steps the current query may still take, if it
+    /// was given a fuel budget.
+    pub(crate) fuel: Option<usize>,
+
+    /// Set once the current query stops because it ran out of fuel.
+    pub(crate) fuel_exhausted: bool,
 }
 
 impl<I: Interner> Forest<I> {
@@ -24,6 +39,10 @@ impl<I: Interner> Forest<I> {
         Forest {
             tables: Tables::new(),
             clock: TimeStamp::default(),
+            record_proofs: false,
+            stats: SolverStats::default(),
+            fuel: None,
+            fuel_exhausted: false,
         }
     }
 
@@ -79,7 +98,11 @@ impl<'me, I: Interner> AnswerStream<I> for ForestSolver<'me, I> {
                     self.answer.increment();
                 }
                 Err(RootSearchFail::Floundered) => {
-                    return AnswerResult::Floundered;
+                    let cause = self.forest.tables[self.table]
+                        .floundered_cause()
+                        .cloned()
+                        .unwrap_or(AmbiguityCause::Floundered);
+                    return AnswerResult::Floundered(cause);
                 }
 
                 Err(RootSearchFail::NoMoreSolutions) => {
@@ -87,6 +110,10 @@ impl<'me, I: Interner> AnswerStream<I> for ForestSolver<'me, I> {
                 }
 
                 Err(RootSearchFail::QuantumExceeded) => {
+                    if self.forest.fuel == Some(0) {
+                        self.forest.fuel_exhausted = true;
+                        return AnswerResult::QuantumExceeded;
+                    }
                     if !should_continue() {
                         return AnswerResult::QuantumExceeded;
                     }
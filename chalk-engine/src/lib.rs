@@ -63,12 +63,14 @@ use chalk_ir::{
     AnswerSubst, Canonical, ConstrainedSubst, Constraint, DebruijnIndex, Goal, InEnvironment,
     Substitution,
 };
+use chalk_solve::AmbiguityCause;
 
 pub mod context;
 mod derived;
 pub mod forest;
 mod logic;
 mod normalize_deep;
+mod proof;
 mod simplify;
 pub mod slg;
 pub mod solve;
@@ -90,11 +92,11 @@ pub struct ExClause<I: Interner> {
     /// would yield A.
     pub subst: Substitution<I>,
 
-    /// True if any subgoals were depended upon negatively and
-    /// were not fully evaluated, or if we encountered a `CannotProve`
-    /// goal. (In the full SLG algorithm, we would use delayed literals here,
-    /// but we don't bother, as we don't need that support.)
-    pub ambiguous: bool,
+    /// Set (to the reason why) if any subgoals were depended upon
+    /// negatively and were not fully evaluated, or if we encountered a
+    /// `CannotProve` goal. (In the full SLG algorithm, we would use delayed
+    /// literals here, but we don't bother, as we don't need that support.)
+    pub ambiguity: Option<AmbiguityCause<I>>,
 
     /// Region constraints we have accumulated.
     pub constraints: Vec<InEnvironment<Constraint<I>>>,
@@ -128,6 +130,17 @@ pub struct TimeStamp {
     clock: u64,
 }
 
+impl<I: Interner> ExClause<I> {
+    /// Marks the clause as ambiguous because of `cause`, on top of whatever
+    /// may have made it ambiguous before.
+    pub(crate) fn mark_ambiguous(&mut self, cause: AmbiguityCause<I>) {
+        self.ambiguity = Some(match self.ambiguity.take() {
+            Some(previous) => previous.and(cause),
+            None => cause,
+        });
+    }
+}
+
 impl TimeStamp {
     const MAX: TimeStamp = TimeStamp {
         clock: ::std::u64::MAX,
@@ -176,6 +189,10 @@ pub struct FlounderedSubgoal<I: Interner> {
     /// Current value of the strand's clock at the time of
     /// floundering.
     pub floundered_time: TimeStamp,
+
+    /// Why the literal floundered: usually `Floundered`, but also
+    /// `Truncation` for literals that were too large.
+    pub cause: AmbiguityCause<I>,
 }
 
 /// An "answer" in the on-demand solver corresponds to a fully solved
@@ -189,10 +206,13 @@ pub struct Answer<I: Interner> {
     /// proven, but not by chalk).
     pub subst: Canonical<AnswerSubst<I>>,
 
-    /// If this flag is set, then the answer could be neither proven
+    /// If this is set, then the answer could be neither proven
     /// nor disproven. This could be the size of the answer exceeded
     /// `max_size` or because of a negative loop (e.g., `P :- not { P }`).
-    pub ambiguous: bool,
+    pub ambiguity: Option<AmbiguityCause<I>>,
+
+    /// How the answer was derived, if derivations are being recorded.
+    pub(crate) derivation: Option<proof::Derivation<I>>,
 }
 
 #[derive(Clone, Debug)]
@@ -202,10 +222,10 @@ pub struct CompleteAnswer<I: Interner> {
     /// proven, but not by chalk).
     pub subst: Canonical<ConstrainedSubst<I>>,
 
-    /// If this flag is set, then the answer could be neither proven
+    /// If this is set, then the answer could be neither proven
     /// nor disproven. This could be the size of the answer exceeded
     /// `max_size` or because of a negative loop (e.g., `P :- not { P }`).
-    pub ambiguous: bool,
+    pub ambiguity: Option<AmbiguityCause<I>>,
 }
 
 /// Either `A` or `~A`, where `A` is a `Env |- Goal`.
@@ -1,4 +1,5 @@
 use crate::forest::Forest;
+use crate::proof::{Derivation, SubgoalDerivation};
 use crate::slg::{
     ResolventOps, SlgContext, SlgContextOps, TruncateOps, TruncatingInferenceTable, UnificationOps,
 };
@@ -17,6 +18,8 @@ use chalk_ir::{
 };
 use chalk_solve::clauses::program_clauses_for_goal;
 use chalk_solve::coinductive_goal::IsCoinductive;
+use chalk_solve::proof::ProofStep;
+use chalk_solve::{AmbiguityCause, SolverStats};
 use tracing::{debug, debug_span, info, instrument};
 
 type RootSearchResult<T> = Result<T, RootSearchFail>;
@@ -107,7 +110,7 @@ impl<I: Interner> Forest<I> {
                             constraints: answer.subst.value.constraints.clone(),
                         },
                     },
-                    ambiguous: answer.ambiguous,
+                    ambiguity: answer.ambiguity.clone(),
                 })
             }
             Err(err) => Err(err),
@@ -145,6 +148,7 @@ impl<I: Interner> Forest<I> {
             ex_clause,
             selected_subgoal,
             last_pursued_time,
+            derivation,
         } = strand;
         Forest::canonicalize_strand_from(
             context,
@@ -152,6 +156,7 @@ impl<I: Interner> Forest<I> {
             ex_clause,
             selected_subgoal,
             last_pursued_time,
+            derivation,
         )
     }
 
@@ -161,6 +166,7 @@ impl<I: Interner> Forest<I> {
         ex_clause: ExClause<I>,
         selected_subgoal: Option<SelectedSubgoal>,
         last_pursued_time: TimeStamp,
+        derivation: Option<Derivation<I>>,
     ) -> CanonicalStrand<I> {
         let canonical_ex_clause =
             infer.canonicalize_ex_clause(context.program().interner(), ex_clause);
@@ -168,9 +174,23 @@ impl<I: Interner> Forest<I> {
             canonical_ex_clause,
             selected_subgoal,
             last_pursued_time,
+            derivation,
         }
     }
 
+    /// Converts a negative subgoal into the u-canonical form of
+    /// `not { subgoal }`, as recorded in derivations. Unlike the table
+    /// for the subgoal, this is not inverted.
+    fn canonicalize_negated_subgoal(
+        context: &SlgContextOps<I>,
+        infer: &mut TruncatingInferenceTable<I>,
+        subgoal: InEnvironment<Goal<I>>,
+    ) -> UCanonical<InEnvironment<Goal<I>>> {
+        let interner = context.program().interner();
+        let negated = subgoal.map(|goal| GoalData::Not(goal).intern(interner));
+        infer.fully_canonicalize_goal(interner, negated).0
+    }
+
     /// Given a subgoal, converts the literal into u-canonical form
     /// and searches for an existing table. If one is found, it is
     /// returned, but otherwise a new table is created (and populated
@@ -190,14 +210,14 @@ impl<I: Interner> Forest<I> {
         context: &SlgContextOps<I>,
         infer: &mut TruncatingInferenceTable<I>,
         subgoal: &Literal<I>,
-    ) -> Option<(TableIndex, UniverseMap)> {
+    ) -> Result<(TableIndex, UniverseMap), AmbiguityCause<I>> {
         // Subgoal abstraction:
         let (ucanonical_subgoal, universe_map) = match subgoal {
             Literal::Positive(subgoal) => {
-                Forest::abstract_positive_literal(context, infer, subgoal.clone())?
+                self.abstract_positive_literal(context, infer, subgoal.clone())?
             }
             Literal::Negative(subgoal) => {
-                Forest::abstract_negative_literal(context, infer, subgoal.clone())?
+                self.abstract_negative_literal(context, infer, subgoal.clone())?
             }
         };
 
@@ -205,7 +225,7 @@ impl<I: Interner> Forest<I> {
 
         let table = self.get_or_create_table_for_ucanonical_goal(context, ucanonical_subgoal);
 
-        Some((table, universe_map))
+        Ok((table, universe_map))
     }
 
     /// Given a u-canonical goal, searches for an existing table. If
@@ -223,15 +243,24 @@ impl<I: Interner> Forest<I> {
     ) -> TableIndex {
         if let Some(table) = self.tables.index_of(&goal) {
             debug!(?table, "found existing table");
+            self.stats.cache_hits += 1;
             return table;
         }
+        self.stats.cache_misses += 1;
+        self.stats.tables_created += 1;
 
         info!(
             table = ?self.tables.next_index(),
             "creating new table with goal = {:#?}",
             goal,
         );
-        let table = Self::build_table(context, self.tables.next_index(), goal);
+        let table = Self::build_table(
+            context,
+            self.tables.next_index(),
+            goal,
+            self.record_proofs,
+            &mut self.stats,
+        );
         self.tables.insert(table)
     }
 
@@ -246,10 +275,15 @@ impl<I: Interner> Forest<I> {
     /// In terms of the NFTD paper, this corresponds to the *Program
     /// Clause Resolution* step being applied eagerly, as many times
     /// as possible.
+    ///
+    /// If `record_proofs` is set, each strand starts out recording
+    /// the clause (or simplification) it was created from.
     fn build_table(
         context: &SlgContextOps<I>,
         table_idx: TableIndex,
         goal: UCanonical<InEnvironment<Goal<I>>>,
+        record_proofs: bool,
+        stats: &mut SolverStats,
     ) -> Table<I> {
         let coinductive = goal.is_coinductive(context.program());
         let mut table = Table::new(goal.clone(), coinductive);
@@ -280,6 +314,7 @@ impl<I: Interner> Forest<I> {
 
                 match clauses {
                     Ok(clauses) => {
+                        stats.record_program_clauses(domain_goal, clauses.len());
                         for clause in clauses {
                             info!("program clause = {:#?}", clause);
                             let mut infer = infer.clone();
@@ -297,6 +332,11 @@ impl<I: Interner> Forest<I> {
                                     ex_clause: resolvent,
                                     selected_subgoal: None,
                                     last_pursued_time: TimeStamp::default(),
+                                    derivation: if record_proofs {
+                                        Some(Derivation::new(ProofStep::Clause(clause)))
+                                    } else {
+                                        None
+                                    },
                                 };
                                 let canonical_strand = Self::canonicalize_strand(context, strand);
                                 table.enqueue_strand(canonical_strand);
@@ -309,7 +349,7 @@ impl<I: Interner> Forest<I> {
                             "Marking table {:?} as floundered! (failed to create program clauses)",
                             table_idx
                         );
-                        table.mark_floundered();
+                        table.mark_floundered(AmbiguityCause::Floundered);
                     }
                 }
             }
@@ -340,12 +380,19 @@ impl<I: Interner> Forest<I> {
                             ex_clause,
                             selected_subgoal: None,
                             last_pursued_time: TimeStamp::default(),
+                            derivation: if record_proofs {
+                                Some(Derivation::new(ProofStep::Simplification))
+                            } else {
+                                None
+                            },
                         };
                         let canonical_strand = Self::canonicalize_strand(context, strand);
                         table.enqueue_strand(canonical_strand);
                     }
                     FallibleOrFloundered::NoSolution => {}
-                    FallibleOrFloundered::Floundered => table.mark_floundered(),
+                    FallibleOrFloundered::Floundered => {
+                        table.mark_floundered(AmbiguityCause::Floundered)
+                    }
                 }
             }
         }
@@ -358,31 +405,34 @@ impl<I: Interner> Forest<I> {
     /// used to pick a table. Typically, this abstraction has no
     /// effect, and hence we are simply returning the canonical form
     /// of `subgoal`; but if the subgoal is getting too big, we return
-    /// `None`, which causes the subgoal to flounder.
+    /// `Err(Truncation)`, which causes the subgoal to flounder.
     fn abstract_positive_literal(
+        &mut self,
         context: &SlgContextOps<I>,
         infer: &mut TruncatingInferenceTable<I>,
         subgoal: InEnvironment<Goal<I>>,
-    ) -> Option<(UCanonical<InEnvironment<Goal<I>>>, UniverseMap)> {
+    ) -> Result<(UCanonical<InEnvironment<Goal<I>>>, UniverseMap), AmbiguityCause<I>> {
         if infer.goal_needs_truncation(context.program().interner(), &subgoal) {
-            None
+            self.stats.truncations += 1;
+            Err(AmbiguityCause::Truncation)
         } else {
-            Some(infer.fully_canonicalize_goal(context.program().interner(), subgoal))
+            Ok(infer.fully_canonicalize_goal(context.program().interner(), subgoal))
         }
     }
 
     /// Given a selected negative subgoal, the subgoal is "inverted"
     /// (see `InferenceTable<I, C>::invert`) and then potentially truncated
     /// (see `abstract_positive_literal`). The result subgoal is
-    /// canonicalized. In some cases, this may return `None` and hence
+    /// canonicalized. In some cases, this may return `Err` and hence
     /// fail to yield a useful result, for example if free existential
     /// variables appear in `subgoal` (in which case the execution is
     /// said to "flounder").
     fn abstract_negative_literal(
+        &mut self,
         context: &SlgContextOps<I>,
         infer: &mut TruncatingInferenceTable<I>,
         subgoal: InEnvironment<Goal<I>>,
-    ) -> Option<(UCanonical<InEnvironment<Goal<I>>>, UniverseMap)> {
+    ) -> Result<(UCanonical<InEnvironment<Goal<I>>>, UniverseMap), AmbiguityCause<I>> {
         // First, we have to check that the selected negative literal
         // is ground, and invert any universally quantified variables.
         //
@@ -420,12 +470,15 @@ impl<I: Interner> Forest<I> {
         // could instead generate an (imprecise) result). As you can
         // see a bit later, we also diverge in some other aspects that
         // affect completeness when it comes to subgoal abstraction.
-        let inverted_subgoal = infer.invert_goal(context.program().interner(), subgoal)?;
+        let inverted_subgoal = infer
+            .invert_goal(context.program().interner(), subgoal)
+            .ok_or(AmbiguityCause::Floundered)?;
 
         if infer.goal_needs_truncation(context.program().interner(), &inverted_subgoal) {
-            None
+            self.stats.truncations += 1;
+            Err(AmbiguityCause::Truncation)
         } else {
-            Some(infer.fully_canonicalize_goal(context.program().interner(), inverted_subgoal))
+            Ok(infer.fully_canonicalize_goal(context.program().interner(), inverted_subgoal))
         }
     }
 }
@@ -487,6 +540,13 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
         self.stack
             .push(initial_table, Minimums::MAX, self.forest.increment_clock());
         loop {
+            // Out of fuel: stop before the next strand step. Dropping the
+            // solve state puts the active strands back into their tables.
+            if self.forest.fuel == Some(0) {
+                self.forest.fuel_exhausted = true;
+                return Err(RootSearchFail::QuantumExceeded);
+            }
+
             let clock = self.stack.top().clock;
             // If we had an active strand, continue to pursue it
             let table = self.stack.top().table;
@@ -509,7 +569,8 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                             strand.canonical_ex_clause.clone(),
                         );
                         let time_eligble = strand.last_pursued_time < clock;
-                        let mode_eligble = match (table_answer_mode, ex_clause.ambiguous) {
+                        let mode_eligble = match (table_answer_mode, ex_clause.ambiguity.is_some())
+                        {
                             (AnswerMode::Complete, false) => true,
                             (AnswerMode::Complete, true) => false,
                             (AnswerMode::Ambiguous, _) => true,
@@ -521,6 +582,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                             canonical_ex_clause,
                             selected_subgoal,
                             last_pursued_time,
+                            derivation,
                         } = canonical_strand;
 
                         let (infer, _, ex_clause) =
@@ -535,12 +597,17 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                             ex_clause,
                             selected_subgoal,
                             last_pursued_time,
+                            derivation,
                         }
                     })
             });
             match next_strand {
                 Some(mut strand) => {
                     debug!("starting next strand = {:#?}", strand);
+                    self.forest.stats.strands_pursued += 1;
+                    if let Some(fuel) = &mut self.forest.fuel {
+                        *fuel = fuel.saturating_sub(1);
+                    }
 
                     strand.last_pursued_time = clock;
                     match self.select_subgoal(&mut strand) {
@@ -582,16 +649,16 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
 
         // If this answer is ambiguous and we don't want ambiguous answers
         // yet, then we act like this is a floundered subgoal.
-        let ambiguous = {
+        let ambiguity = {
             let selected_subgoal = strand.selected_subgoal.as_ref().unwrap();
             let answer = self.forest.answer(
                 selected_subgoal.subgoal_table,
                 selected_subgoal.answer_index,
             );
-            answer.ambiguous
+            answer.ambiguity.clone()
         };
         if let AnswerMode::Complete = self.forest.tables[self.stack.top().table].answer_mode {
-            if ambiguous {
+            if let Some(cause) = ambiguity {
                 // FIXME: we could try to be a little bit smarter here. This can
                 // really be split into cases:
                 // 1) Cases where no amount of solving will cause this ambiguity to change.
@@ -608,7 +675,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 // The selected subgoal returned an ambiguous answer, but we don't want that.
                 // So, we treat this subgoal as floundered.
                 let selected_subgoal = strand.selected_subgoal.take().unwrap();
-                self.flounder_subgoal(&mut strand.ex_clause, selected_subgoal.subgoal_index);
+                self.flounder_subgoal(&mut strand.ex_clause, selected_subgoal.subgoal_index, cause);
                 return Ok(());
             }
         }
@@ -636,6 +703,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     ex_clause: strand.ex_clause.clone(),
                     selected_subgoal: Some(next_subgoal),
                     last_pursued_time: strand.last_pursued_time,
+                    derivation: strand.derivation.clone(),
                 };
                 let table = self.stack.top().table;
                 let canonical_next_strand = Forest::canonicalize_strand(self.context, next_strand);
@@ -680,15 +748,22 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                             ex_clause,
                             selected_subgoal: _,
                             last_pursued_time: _,
+                            derivation,
                         } = strand;
 
+                        if let Some(derivation) = derivation {
+                            derivation.push(SubgoalDerivation::Answer(subgoal_table, answer_index));
+                        }
+
                         // If the answer had was ambiguous, we have to
                         // ensure that `ex_clause` is also ambiguous. This is
                         // the SLG FACTOR operation, though NFTD just makes it
                         // part of computing the SLG resolvent.
-                        if self.forest.answer(subgoal_table, answer_index).ambiguous {
+                        if let Some(cause) =
+                            &self.forest.answer(subgoal_table, answer_index).ambiguity
+                        {
                             debug!("Marking Strand as ambiguous because answer to (positive) subgoal was ambiguous");
-                            ex_clause.ambiguous = true;
+                            ex_clause.mark_ambiguous(cause.clone());
                         }
 
                         // Increment the answer time for the `ex_clause`. Floundered
@@ -713,7 +788,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     }
                 }
             }
-            Literal::Negative(_) => {
+            Literal::Negative(subgoal) => {
                 let SelectedSubgoal {
                     subgoal_index: _,
                     subgoal_table,
@@ -734,19 +809,22 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     panic!("Negative subgoal had delayed_subgoals");
                 }
 
-                if !answer.ambiguous {
-                    // We want to disproval the subgoal, but we
-                    // have an unconditional answer for the subgoal,
-                    // therefore we have failed to disprove it.
-                    info!("found unconditional answer to neg literal -> NoSolution");
+                let cause = match &answer.ambiguity {
+                    Some(cause) => cause.clone(),
+                    None => {
+                        // We want to disproval the subgoal, but we
+                        // have an unconditional answer for the subgoal,
+                        // therefore we have failed to disprove it.
+                        info!("found unconditional answer to neg literal -> NoSolution");
 
-                    // This strand as no solution. By returning an Err,
-                    // the caller should discard this `Strand`.
+                        // This strand as no solution. By returning an Err,
+                        // the caller should discard this `Strand`.
 
-                    // Now we want to propogate back to the up with `QuantumExceeded`
-                    self.unwind_stack();
-                    return Err(RootSearchFail::QuantumExceeded);
-                }
+                        // Now we want to propogate back to the up with `QuantumExceeded`
+                        self.unwind_stack();
+                        return Err(RootSearchFail::QuantumExceeded);
+                    }
+                };
 
                 // Otherwise, the answer is ambiguous. We can keep going,
                 // but we have to mark our strand, too, as ambiguous.
@@ -755,7 +833,19 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 // have an unconditional answer for the subgoal,
                 // therefore we have failed to disprove it.
                 debug!(?strand, "Marking Strand as ambiguous because answer to (negative) subgoal was ambiguous");
-                strand.ex_clause.ambiguous = true;
+                strand.ex_clause.mark_ambiguous(cause);
+                if let Some(derivation) = &mut strand.derivation {
+                    let negated = Forest::canonicalize_negated_subgoal(
+                        self.context,
+                        &mut strand.infer,
+                        subgoal,
+                    );
+                    derivation.push(SubgoalDerivation::AmbiguousNegation(
+                        negated,
+                        subgoal_table,
+                        answer_index,
+                    ));
+                }
 
                 // Strand is ambigious.
                 return Ok(());
@@ -786,7 +876,11 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 // floundered list, along with the time that it
                 // floundered. We'll try to solve some other subgoals
                 // and maybe come back to it.
-                self.flounder_subgoal(&mut strand.ex_clause, selected_subgoal.subgoal_index);
+                let cause = self.forest.tables[selected_subgoal.subgoal_table]
+                    .floundered_cause()
+                    .cloned()
+                    .unwrap_or(AmbiguityCause::Floundered);
+                self.flounder_subgoal(&mut strand.ex_clause, selected_subgoal.subgoal_index, cause);
 
                 false
             }
@@ -840,6 +934,9 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 );
 
                 strand.ex_clause.delayed_subgoals.push(subgoal);
+                if let Some(derivation) = &mut strand.derivation {
+                    derivation.push(SubgoalDerivation::Delayed(selected_subgoal.subgoal_table));
+                }
 
                 self.stack.top().active_strand = Some(strand);
                 Ok(())
@@ -990,7 +1087,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
     /// it yet, we just enqueue it again to pick it up later. Otherwise, we
     /// add the answer from the strand onto the table.
     fn on_no_remaining_subgoals(&mut self, strand: Strand<I>) -> NoRemainingSubgoalsResult {
-        let ambiguous = strand.ex_clause.ambiguous;
+        let ambiguous = strand.ex_clause.ambiguity.is_some();
         if let AnswerMode::Complete = self.forest.tables[self.stack.top().table].answer_mode {
             if ambiguous {
                 // The strand can only return an ambiguous answer, but we don't
@@ -1103,7 +1200,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
             infer: table,
             ex_clause: ExClause {
                 subst,
-                ambiguous: answer.ambiguous,
+                ambiguity: answer.ambiguity.clone(),
                 constraints: constraints
                     .as_slice(self.context.program().interner())
                     .to_vec(),
@@ -1114,6 +1211,9 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
             },
             selected_subgoal: None,
             last_pursued_time: TimeStamp::default(),
+            // The refinement only confirms the delayed subgoals, which
+            // the derivation of the answer already closes with cycles.
+            derivation: answer.derivation.clone().map(Derivation::refinement),
         };
 
         Some(Forest::canonicalize_strand(self.context, strand))
@@ -1162,10 +1262,22 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     // There is no solution for this strand. But, this
                     // is what we want, so can remove this subgoal and
                     // keep going.
-                    caller_strand
+                    let subgoal = caller_strand
                         .ex_clause
                         .subgoals
                         .remove(caller_selected_subgoal.subgoal_index);
+                    if let Some(derivation) = &mut caller_strand.derivation {
+                        let subgoal = match subgoal {
+                            Literal::Negative(subgoal) => subgoal,
+                            Literal::Positive(_) => unreachable!(),
+                        };
+                        let negated = Forest::canonicalize_negated_subgoal(
+                            self.context,
+                            &mut caller_strand.infer,
+                            subgoal,
+                        );
+                        derivation.push(SubgoalDerivation::Negation(negated, table));
+                    }
 
                     // This strand is still active, so continue
                     Ok(())
@@ -1279,6 +1391,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 canonical_ex_clause,
                 selected_subgoal,
                 last_pursued_time: _,
+                derivation: _,
             } = strand;
             let selected_subgoal = selected_subgoal.unwrap_or_else(|| {
                 panic!(
@@ -1309,7 +1422,14 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                         // to get helpful information from this strand still, but it
                         // will *always* be ambiguous, so mark it as so.
                         assert!(!strand.ex_clause.floundered_subgoals.is_empty());
-                        strand.ex_clause.ambiguous = true;
+                        let cause = strand
+                            .ex_clause
+                            .floundered_subgoals
+                            .iter()
+                            .map(|floundered| floundered.cause.clone())
+                            .reduce(AmbiguityCause::and)
+                            .unwrap();
+                        strand.ex_clause.mark_ambiguous(cause);
                         return SubGoalSelection::NotSelected;
                     }
 
@@ -1324,7 +1444,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     &mut strand.infer,
                     &strand.ex_clause.subgoals[subgoal_index],
                 ) {
-                    Some((subgoal_table, universe_map)) => {
+                    Ok((subgoal_table, universe_map)) => {
                         strand.selected_subgoal = Some(SelectedSubgoal {
                             subgoal_index,
                             subgoal_table,
@@ -1333,11 +1453,11 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                         });
                     }
 
-                    None => {
+                    Err(cause) => {
                         // If we failed to create a table for the subgoal,
                         // that is because we have a floundered negative
-                        // literal.
-                        self.flounder_subgoal(&mut strand.ex_clause, subgoal_index);
+                        // literal, or the subgoal was too large.
+                        self.flounder_subgoal(&mut strand.ex_clause, subgoal_index, cause);
                     }
                 }
             }
@@ -1376,7 +1496,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 ExClause {
                     subst,
                     constraints,
-                    ambiguous,
+                    ambiguity,
                     subgoals,
                     delayed_subgoals,
                     answer_time: _,
@@ -1384,13 +1504,14 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 },
             selected_subgoal: _,
             last_pursued_time: _,
+            mut derivation,
         } = strand;
         // If there are subgoals left, they should be followed
         assert!(subgoals.is_empty());
         // We can still try to get an ambiguous answer if there are floundered subgoals
         let floundered = !floundered_subgoals.is_empty();
         // So let's make sure that it *really* is an ambiguous answer (this should be set previously)
-        assert!(!floundered || ambiguous);
+        assert!(!floundered || ambiguity.is_some());
 
         // FIXME: If there are floundered subgoals, we *could* potentially
         // actually check if the partial answers to any of these subgoals
@@ -1416,7 +1537,8 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
         // down to "it works as we expect for the current tests". And, we likely don't
         // even *need* the added complexity just for potentially more answers.
         if infer.answer_needs_truncation(self.context.program().interner(), &subst) {
-            self.forest.tables[table].mark_floundered();
+            self.forest.stats.truncations += 1;
+            self.forest.tables[table].mark_floundered(AmbiguityCause::Truncation);
             return None;
         }
 
@@ -1442,7 +1564,23 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
         );
         debug!(?table, ?subst, ?floundered, "found answer");
 
-        let answer = Answer { subst, ambiguous };
+        if let Some(derivation) = &mut derivation {
+            for floundered_subgoal in floundered_subgoals {
+                let goal = match floundered_subgoal.floundered_literal {
+                    Literal::Positive(goal) | Literal::Negative(goal) => goal,
+                };
+                let (goal, _) =
+                    infer.fully_canonicalize_goal(self.context.program().interner(), goal);
+                derivation.push(SubgoalDerivation::Floundered(goal));
+            }
+        }
+
+        let ambiguous = ambiguity.is_some();
+        let answer = Answer {
+            subst,
+            ambiguity,
+            derivation,
+        };
 
         // A "trivial" answer is one that is 'just true for all cases'
         // -- in other words, it gives no information back to the
@@ -1514,6 +1652,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
         };
 
         if let Some(answer_index) = self.forest.tables[table].push_answer(answer) {
+            self.forest.stats.answers_produced += 1;
             // See above, if we have a *complete* and trivial answer, we don't
             // want to follow any more strands
             if !ambiguous && is_trivial_answer {
@@ -1546,7 +1685,12 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
     /// Removes the subgoal at `subgoal_index` from the strand's
     /// subgoal list and adds it to the strand's floundered subgoal
     /// list.
-    fn flounder_subgoal(&self, ex_clause: &mut ExClause<I>, subgoal_index: usize) {
+    fn flounder_subgoal(
+        &self,
+        ex_clause: &mut ExClause<I>,
+        subgoal_index: usize,
+        cause: AmbiguityCause<I>,
+    ) {
         let _s = debug_span!(
             "flounder_subgoal",
             answer_time = ?ex_clause.answer_time,
@@ -1559,6 +1703,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
         ex_clause.floundered_subgoals.push(FlounderedSubgoal {
             floundered_literal,
             floundered_time,
+            cause,
         });
         debug!(?ex_clause);
     }
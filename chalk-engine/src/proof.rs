@@ -0,0 +1,238 @@
+//! Recording of derivations, so that the proof tree of an answer can be
+//! rebuilt once it has been found. Derivations are only recorded when the
+//! forest was asked to (see `SLGSolver::record_proofs`).
+
+use crate::forest::Forest;
+use crate::table::AnswerIndex;
+use crate::{Answer, TableIndex};
+
+use chalk_ir::interner::Interner;
+use chalk_ir::{Canonical, ConstrainedSubst, Constraints, Goal, InEnvironment, UCanonical};
+use chalk_solve::proof::{ProofStep, ProofTree};
+use chalk_solve::{AmbiguityCause, Guidance, Solution};
+
+/// How a strand (and eventually the answer it produces) was derived: the
+/// step that created the strand, and how each of its subgoals was dealt
+/// with, in the order they were completed.
+#[derive(Clone, Debug)]
+pub(crate) struct Derivation<I: Interner> {
+    /// Either `ProofStep::Clause`, for strands created from a program
+    /// clause, or `ProofStep::Simplification`, for strands created by
+    /// simplifying a non-domain goal.
+    pub(crate) step: ProofStep<I>,
+
+    pub(crate) subgoals: Vec<SubgoalDerivation<I>>,
+
+    /// Whether this is the derivation of a strand that refines an answer
+    /// with delayed subgoals. Such a strand only re-proves subgoals that
+    /// the derivation of the answer already closes with a cycle, so
+    /// nothing more is recorded for it.
+    refining: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum SubgoalDerivation<I: Interner> {
+    /// A positive subgoal, proven by the given answer of its table.
+    Answer(TableIndex, AnswerIndex),
+
+    /// A positive subgoal that was delayed because it formed a
+    /// coinductive cycle with the given table.
+    Delayed(TableIndex),
+
+    /// A negative subgoal, given as `not { G }`, whose (inverted) table
+    /// turned out to have no answers.
+    Negation(UCanonical<InEnvironment<Goal<I>>>, TableIndex),
+
+    /// A negative subgoal, given as `not { G }`, whose (inverted) table
+    /// only had the given, ambiguous, answer.
+    AmbiguousNegation(UCanonical<InEnvironment<Goal<I>>>, TableIndex, AnswerIndex),
+
+    /// A subgoal that floundered, leaving the answer ambiguous.
+    Floundered(UCanonical<InEnvironment<Goal<I>>>),
+}
+
+impl<I: Interner> Derivation<I> {
+    pub(crate) fn new(step: ProofStep<I>) -> Self {
+        Derivation {
+            step,
+            subgoals: vec![],
+            refining: false,
+        }
+    }
+
+    pub(crate) fn refinement(self) -> Self {
+        Derivation {
+            refining: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn push(&mut self, subgoal: SubgoalDerivation<I>) {
+        if !self.refining {
+            self.subgoals.push(subgoal);
+        }
+    }
+}
+
+impl<I: Interner> Forest<I> {
+    /// Rebuilds the proof trees of the answers found so far for `goal`, in
+    /// the order in which they are returned by the forest. Answers that
+    /// still have delayed subgoals are skipped, just like they are when
+    /// solving.
+    pub(crate) fn root_answer_proofs(
+        &self,
+        interner: &I,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Vec<ProofTree<I>> {
+        let table = match self.tables.index_of(goal) {
+            Some(table) => table,
+            None => return vec![],
+        };
+        let mut proofs = vec![];
+        let mut answer_index = AnswerIndex::ZERO;
+        while let Some(answer) = self.tables[table].answer(answer_index) {
+            if answer.subst.value.delayed_subgoals.is_empty() {
+                proofs.push(self.answer_proof(interner, table, answer_index));
+            }
+            answer_index.increment();
+        }
+        proofs
+    }
+
+    /// Rebuilds the proof tree of the given answer out of the recorded
+    /// derivations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the answer was found while derivations were not being
+    /// recorded.
+    pub(crate) fn answer_proof(
+        &self,
+        interner: &I,
+        table: TableIndex,
+        answer_index: AnswerIndex,
+    ) -> ProofTree<I> {
+        let table_goal = &self.tables[table].table_goal;
+        let answer = match self.tables[table].answer(answer_index) {
+            Some(answer) => answer,
+            None => {
+                // The table floundered after this answer was used, which
+                // discards its answers.
+                let cause = self.tables[table]
+                    .floundered_cause()
+                    .cloned()
+                    .unwrap_or(AmbiguityCause::Floundered);
+                return ProofTree {
+                    goal: table_goal.clone(),
+                    solution: Some(Solution::Ambig(Guidance::Unknown, cause)),
+                    step: ProofStep::Floundered,
+                    subgoals: vec![],
+                };
+            }
+        };
+        let derivation = answer
+            .derivation
+            .as_ref()
+            .expect("answer was found without recording its derivation");
+
+        let subgoals = derivation
+            .subgoals
+            .iter()
+            .map(|subgoal| match subgoal {
+                SubgoalDerivation::Answer(table, answer) => {
+                    self.answer_proof(interner, *table, *answer)
+                }
+                SubgoalDerivation::Delayed(table) => {
+                    let goal = &self.tables[*table].table_goal;
+                    ProofTree {
+                        goal: goal.clone(),
+                        solution: Some(trivial_solution(interner, goal)),
+                        step: ProofStep::Cycle,
+                        subgoals: vec![],
+                    }
+                }
+                SubgoalDerivation::Negation(negated, table) => {
+                    let goal = &self.tables[*table].table_goal;
+                    ProofTree {
+                        goal: negated.clone(),
+                        solution: Some(trivial_solution(interner, negated)),
+                        step: ProofStep::Negation,
+                        subgoals: vec![ProofTree {
+                            goal: goal.clone(),
+                            solution: None,
+                            step: ProofStep::Alternatives,
+                            subgoals: vec![],
+                        }],
+                    }
+                }
+                SubgoalDerivation::AmbiguousNegation(negated, table, answer) => ProofTree {
+                    goal: negated.clone(),
+                    solution: Some(Solution::Ambig(
+                        Guidance::Unknown,
+                        self.tables[*table]
+                            .answer(*answer)
+                            .and_then(|answer| answer.ambiguity.clone())
+                            .unwrap_or(AmbiguityCause::Ambiguity),
+                    )),
+                    step: ProofStep::Negation,
+                    subgoals: vec![self.answer_proof(interner, *table, *answer)],
+                },
+                SubgoalDerivation::Floundered(goal) => ProofTree {
+                    goal: goal.clone(),
+                    solution: Some(Solution::Ambig(
+                        Guidance::Unknown,
+                        AmbiguityCause::Floundered,
+                    )),
+                    step: ProofStep::Floundered,
+                    subgoals: vec![],
+                },
+            })
+            .collect();
+
+        ProofTree {
+            goal: table_goal.clone(),
+            solution: Some(answer_solution(interner, answer)),
+            step: derivation.step.clone(),
+            subgoals,
+        }
+    }
+}
+
+/// The solution given by a single answer.
+fn answer_solution<I: Interner>(interner: &I, answer: &Answer<I>) -> Solution<I> {
+    let subst = Canonical {
+        binders: answer.subst.binders.clone(),
+        value: ConstrainedSubst {
+            subst: answer.subst.value.subst.clone(),
+            constraints: answer.subst.value.constraints.clone(),
+        },
+    };
+    match &answer.ambiguity {
+        None => Solution::Unique(subst),
+        Some(cause) if subst.value.subst.is_identity_subst(interner) => {
+            Solution::Ambig(Guidance::Unknown, cause.clone())
+        }
+        Some(cause) => Solution::Ambig(
+            Guidance::Definite(Canonical {
+                binders: subst.binders,
+                value: subst.value.subst,
+            }),
+            cause.clone(),
+        ),
+    }
+}
+
+/// The solution of a goal that holds without constraining any of its
+/// variables.
+fn trivial_solution<I: Interner>(
+    interner: &I,
+    goal: &UCanonical<InEnvironment<Goal<I>>>,
+) -> Solution<I> {
+    Solution::Unique(Canonical {
+        value: ConstrainedSubst {
+            subst: goal.trivial_substitution(interner),
+            constraints: Constraints::empty(interner),
+        },
+        binders: goal.canonical.binders.clone(),
+    })
+}
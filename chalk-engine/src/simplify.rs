@@ -8,6 +8,7 @@ use chalk_ir::{
     Environment, FallibleOrFloundered, Goal, GoalData, InEnvironment, QuantifierKind, Substitution,
     Variance,
 };
+use chalk_solve::AmbiguityCause;
 use tracing::debug;
 
 impl<I: Interner> Forest<I> {
@@ -23,7 +24,7 @@ impl<I: Interner> Forest<I> {
     ) -> FallibleOrFloundered<ExClause<I>> {
         let mut ex_clause = ExClause {
             subst,
-            ambiguous: false,
+            ambiguity: None,
             constraints: vec![],
             subgoals: vec![],
             delayed_subgoals: vec![],
@@ -111,7 +112,7 @@ impl<I: Interner> Forest<I> {
                 }
                 GoalData::CannotProve => {
                     debug!("Marking Strand as ambiguous because of a `CannotProve` subgoal");
-                    ex_clause.ambiguous = true;
+                    ex_clause.mark_ambiguous(AmbiguityCause::Ambiguity);
                 }
             }
         }
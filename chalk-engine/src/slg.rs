@@ -567,8 +567,25 @@ impl<I: Interner> MayInvalidate<'_, I> {
                 !c1.const_eq(new_ty, c2, interner)
             }
 
-            // Only variants left are placeholder = concrete, which always fails
-            (ConstValue::Placeholder(_), _) | (ConstValue::Concrete(_), _) => true,
+            (ConstValue::Projection(p1), ConstValue::Projection(p2)) => self
+                .aggregate_name_and_substs(
+                    &p1.associated_const_id,
+                    &p1.substitution,
+                    &p2.associated_const_id,
+                    &p2.substitution,
+                ),
+
+            (ConstValue::Expr(e1), ConstValue::Expr(e2)) => {
+                e1.op != e2.op
+                    || self.aggregate_consts(&e1.lhs, &e2.lhs)
+                    || self.aggregate_consts(&e1.rhs, &e2.rhs)
+            }
+
+            // Only variants left are mismatched kinds of consts, which always fail
+            (ConstValue::Placeholder(_), _)
+            | (ConstValue::Concrete(_), _)
+            | (ConstValue::Projection(_), _)
+            | (ConstValue::Expr(_), _) => true,
         }
     }
 
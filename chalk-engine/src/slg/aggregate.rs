@@ -7,7 +7,7 @@ use chalk_ir::interner::Interner;
 use chalk_ir::*;
 use chalk_solve::ext::*;
 use chalk_solve::infer::InferenceTable;
-use chalk_solve::solve::{Guidance, Solution};
+use chalk_solve::solve::{AmbiguityCause, Guidance, Solution};
 
 use std::fmt::Debug;
 
@@ -31,35 +31,43 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
         should_continue: impl std::ops::Fn() -> bool,
     ) -> Option<Solution<I>> {
         let interner = self.program.interner();
-        let CompleteAnswer { subst, ambiguous } = match answers.next_answer(|| should_continue()) {
+        let CompleteAnswer { subst, ambiguity } = match answers.next_answer(|| should_continue()) {
             AnswerResult::NoMoreSolutions => {
                 // No answers at all
                 return None;
             }
             AnswerResult::Answer(answer) => answer,
-            AnswerResult::Floundered => CompleteAnswer {
+            AnswerResult::Floundered(cause) => CompleteAnswer {
                 subst: self.identity_constrained_subst(root_goal),
-                ambiguous: true,
+                ambiguity: Some(cause),
             },
             AnswerResult::QuantumExceeded => {
-                return Some(Solution::Ambig(Guidance::Unknown));
+                return Some(Solution::Ambig(
+                    Guidance::Unknown,
+                    AmbiguityCause::Ambiguity,
+                ));
             }
         };
 
         // Exactly 1 unconditional answer?
         let next_answer = answers.peek_answer(|| should_continue());
         if next_answer.is_quantum_exceeded() {
+            let cause = ambiguity.unwrap_or(AmbiguityCause::Ambiguity);
             if subst.value.subst.is_identity_subst(interner) {
-                return Some(Solution::Ambig(Guidance::Unknown));
+                return Some(Solution::Ambig(Guidance::Unknown, cause));
             } else {
-                return Some(Solution::Ambig(Guidance::Suggested(
-                    subst.map(interner, |cs| cs.subst),
-                )));
+                return Some(Solution::Ambig(
+                    Guidance::Suggested(subst.map(interner, |cs| cs.subst)),
+                    cause,
+                ));
             }
         }
-        if next_answer.is_no_more_solutions() && !ambiguous {
-            return Some(Solution::Unique(subst));
-        }
+        let mut ambiguity = match ambiguity {
+            None if next_answer.is_no_more_solutions() => {
+                return Some(Solution::Unique(subst));
+            }
+            ambiguity => ambiguity,
+        };
 
         // Otherwise, we either have >1 answer, or else we have
         // ambiguity.  Either way, we are only going to be giving back
@@ -95,11 +103,11 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
                 }
             }
 
-            let new_subst = match answers.next_answer(|| should_continue()) {
-                AnswerResult::Answer(answer1) => answer1.subst,
-                AnswerResult::Floundered => {
+            let (new_subst, new_ambiguity) = match answers.next_answer(|| should_continue()) {
+                AnswerResult::Answer(answer1) => (answer1.subst, answer1.ambiguity),
+                AnswerResult::Floundered(cause) => {
                     // FIXME: this doesn't trigger for any current tests
-                    self.identity_constrained_subst(root_goal)
+                    (self.identity_constrained_subst(root_goal), Some(cause))
                 }
                 AnswerResult::NoMoreSolutions => {
                     break Guidance::Definite(subst);
@@ -108,6 +116,12 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
                     break Guidance::Suggested(subst);
                 }
             };
+            // Having several answers is itself ambiguity; we only blame
+            // something more specific if every answer was ambiguous.
+            ambiguity = match (ambiguity, new_ambiguity) {
+                (Some(cause), Some(new_cause)) => Some(cause.or(new_cause)),
+                _ => Some(AmbiguityCause::Ambiguity),
+            };
             subst = merge_into_guidance(interner, &root_goal.canonical, subst, &new_subst);
             num_answers += 1;
         };
@@ -118,7 +132,10 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
                 "Not enough answers for solution."
             );
         }
-        Some(Solution::Ambig(guidance))
+        Some(Solution::Ambig(
+            guidance,
+            ambiguity.unwrap_or(AmbiguityCause::Ambiguity),
+        ))
     }
 }
 
@@ -548,9 +565,50 @@ impl<I: Interner> AntiUnifier<'_, '_, I> {
                 }
             }
 
-            (ConstValue::Placeholder(_), _) | (_, ConstValue::Placeholder(_)) => {
-                self.new_const_variable(ty)
+            (ConstValue::Projection(proj1), ConstValue::Projection(proj2)) => {
+                let ProjectionConst {
+                    associated_const_id: name1,
+                    substitution: substitution1,
+                } = proj1;
+                let ProjectionConst {
+                    associated_const_id: name2,
+                    substitution: substitution2,
+                } = proj2;
+
+                self.aggregate_name_and_substs(name1, substitution1, name2, substitution2)
+                    .map(|(&associated_const_id, substitution)| {
+                        ConstData {
+                            ty: ty.clone(),
+                            value: ConstValue::Projection(ProjectionConst {
+                                associated_const_id,
+                                substitution,
+                            }),
+                        }
+                        .intern(interner)
+                    })
+                    .unwrap_or_else(|| self.new_const_variable(ty))
             }
+
+            (ConstValue::Expr(expr1), ConstValue::Expr(expr2)) if expr1.op == expr2.op => {
+                let lhs = self.aggregate_consts(&expr1.lhs, &expr2.lhs);
+                let rhs = self.aggregate_consts(&expr1.rhs, &expr2.rhs);
+                ConstData {
+                    ty,
+                    value: ConstValue::Expr(ConstExpr {
+                        op: expr1.op,
+                        lhs,
+                        rhs,
+                    }),
+                }
+                .intern(interner)
+            }
+
+            (ConstValue::Placeholder(_), _)
+            | (_, ConstValue::Placeholder(_))
+            | (ConstValue::Projection(_), _)
+            | (_, ConstValue::Projection(_))
+            | (ConstValue::Expr(_), _)
+            | (_, ConstValue::Expr(_)) => self.new_const_variable(ty),
         }
     }
 
@@ -99,7 +99,7 @@ impl<I: Interner> ResolventOps<I> for TruncatingInferenceTable<I> {
         // Final X-clause that we will return.
         let mut ex_clause = ExClause {
             subst: subst.clone(),
-            ambiguous: false,
+            ambiguity: None,
             constraints: vec![],
             subgoals: vec![],
             delayed_subgoals: vec![],
@@ -679,6 +679,14 @@ impl<'i, I: Interner> Zipper<'i, I> for AnswerSubstitutor<'i, I> {
                 Ok(())
             }
 
+            (ConstValue::Projection(answer), ConstValue::Projection(pending)) => {
+                Zip::zip_with(self, variance, answer, pending)
+            }
+
+            (ConstValue::Expr(answer), ConstValue::Expr(pending)) => {
+                Zip::zip_with(self, variance, answer, pending)
+            }
+
             (ConstValue::InferenceVar(_), _) | (_, ConstValue::InferenceVar(_)) => panic!(
                 "unexpected inference var in answer `{:?}` or pending goal `{:?}`",
                 answer, pending,
@@ -686,7 +694,9 @@ impl<'i, I: Interner> Zipper<'i, I> for AnswerSubstitutor<'i, I> {
 
             (ConstValue::BoundVar(_), _)
             | (ConstValue::Placeholder(_), _)
-            | (ConstValue::Concrete(_), _) => panic!(
+            | (ConstValue::Concrete(_), _)
+            | (ConstValue::Projection(_), _)
+            | (ConstValue::Expr(_), _) => panic!(
                 "structural mismatch between answer `{:?}` and pending goal `{:?}`",
                 answer, pending,
             ),
@@ -4,9 +4,13 @@ use crate::slg::aggregate::AggregateOps;
 use crate::slg::SlgContextOps;
 use chalk_ir::interner::Interner;
 use chalk_ir::{Canonical, ConstrainedSubst, Goal, InEnvironment, UCanonical};
-use chalk_solve::{RustIrDatabase, Solution, Solver, SubstitutionResult};
+use chalk_solve::proof::ProofTree;
+use chalk_solve::{
+    FuelExhausted, RustIrDatabase, Solution, Solver, SolverStats, SubstitutionResult,
+};
 
 use std::fmt;
+use std::time::Instant;
 
 pub struct SLGSolver<I: Interner> {
     pub(crate) forest: Forest<I>,
@@ -22,6 +26,45 @@ impl<I: Interner> SLGSolver<I> {
             expected_answers,
         }
     }
+
+    /// Makes the solver record how each answer is derived, so that
+    /// [`answer_proofs`](Self::answer_proofs) can rebuild their proof
+    /// trees. Since answers found up to now were not recorded, they are
+    /// discarded.
+    pub fn record_proofs(&mut self) {
+        if !self.forest.record_proofs {
+            self.forest = Forest::new();
+            self.forest.record_proofs = true;
+        }
+    }
+
+    /// Rebuilds the proof trees of the answers found so far for `goal`, in
+    /// the order in which `solve_multiple` returns them. The solution
+    /// returned by `solve` is aggregated from (a prefix of) these answers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`record_proofs`](Self::record_proofs) was not called
+    /// before solving `goal`.
+    pub fn answer_proofs(
+        &self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Vec<ProofTree<I>> {
+        assert!(self.forest.record_proofs, "proofs are not being recorded");
+        self.forest.root_answer_proofs(program.interner(), goal)
+    }
+
+    /// Runs `query`, collecting statistics about it from scratch. Tables
+    /// left over from earlier queries are still reused, and count as cache
+    /// hits.
+    fn measure<R>(&mut self, query: impl FnOnce(&mut Self) -> R) -> R {
+        self.forest.stats = SolverStats::default();
+        let start = Instant::now();
+        let result = query(self);
+        self.forest.stats.wall_time = start.elapsed();
+        result
+    }
 }
 
 impl<I: Interner> fmt::Debug for SLGSolver<I> {
@@ -36,8 +79,10 @@ impl<I: Interner> Solver<I> for SLGSolver<I> {
         program: &dyn RustIrDatabase<I>,
         goal: &UCanonical<InEnvironment<Goal<I>>>,
     ) -> Option<Solution<I>> {
-        let ops = SlgContextOps::new(program, self.max_size, self.expected_answers);
-        ops.make_solution(goal, self.forest.iter_answers(&ops, goal), || true)
+        self.measure(|solver| {
+            let ops = SlgContextOps::new(program, solver.max_size, solver.expected_answers);
+            ops.make_solution(goal, solver.forest.iter_answers(&ops, goal), || true)
+        })
     }
 
     fn solve_limited(
@@ -46,8 +91,36 @@ impl<I: Interner> Solver<I> for SLGSolver<I> {
         goal: &UCanonical<InEnvironment<Goal<I>>>,
         should_continue: &dyn std::ops::Fn() -> bool,
     ) -> Option<Solution<I>> {
-        let ops = SlgContextOps::new(program, self.max_size, self.expected_answers);
-        ops.make_solution(goal, self.forest.iter_answers(&ops, goal), should_continue)
+        self.measure(|solver| {
+            let ops = SlgContextOps::new(program, solver.max_size, solver.expected_answers);
+            ops.make_solution(
+                goal,
+                solver.forest.iter_answers(&ops, goal),
+                should_continue,
+            )
+        })
+    }
+
+    fn solve_with_fuel(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+        fuel: usize,
+    ) -> Result<Option<Solution<I>>, FuelExhausted> {
+        self.measure(|solver| {
+            solver.forest.fuel = Some(fuel);
+            solver.forest.fuel_exhausted = false;
+            let ops = SlgContextOps::new(program, solver.max_size, solver.expected_answers);
+            let solution = ops.make_solution(goal, solver.forest.iter_answers(&ops, goal), || true);
+            solver.forest.fuel = None;
+            // The work done so far stays in the forest, so asking again
+            // picks up where this query left off.
+            if solver.forest.fuel_exhausted {
+                Err(FuelExhausted)
+            } else {
+                Ok(solution)
+            }
+        })
     }
 
     fn solve_multiple(
@@ -56,34 +129,40 @@ impl<I: Interner> Solver<I> for SLGSolver<I> {
         goal: &UCanonical<InEnvironment<Goal<I>>>,
         f: &mut dyn FnMut(SubstitutionResult<Canonical<ConstrainedSubst<I>>>, bool) -> bool,
     ) -> bool {
-        let ops = SlgContextOps::new(program, self.max_size, self.expected_answers);
-        let mut answers = self.forest.iter_answers(&ops, goal);
-        loop {
-            let subst = match answers.next_answer(|| true) {
-                AnswerResult::Answer(answer) => {
-                    if !answer.ambiguous {
-                        SubstitutionResult::Definite(answer.subst)
-                    } else if answer
-                        .subst
-                        .value
-                        .subst
-                        .is_identity_subst(ops.program().interner())
-                    {
-                        SubstitutionResult::Floundered
-                    } else {
-                        SubstitutionResult::Ambiguous(answer.subst)
+        self.measure(|solver| {
+            let ops = SlgContextOps::new(program, solver.max_size, solver.expected_answers);
+            let mut answers = solver.forest.iter_answers(&ops, goal);
+            loop {
+                let subst = match answers.next_answer(|| true) {
+                    AnswerResult::Answer(answer) => {
+                        if answer.ambiguity.is_none() {
+                            SubstitutionResult::Definite(answer.subst)
+                        } else if answer
+                            .subst
+                            .value
+                            .subst
+                            .is_identity_subst(ops.program().interner())
+                        {
+                            SubstitutionResult::Floundered
+                        } else {
+                            SubstitutionResult::Ambiguous(answer.subst)
+                        }
                     }
-                }
-                AnswerResult::Floundered => SubstitutionResult::Floundered,
-                AnswerResult::NoMoreSolutions => {
-                    return true;
-                }
-                AnswerResult::QuantumExceeded => continue,
-            };
+                    AnswerResult::Floundered(_) => SubstitutionResult::Floundered,
+                    AnswerResult::NoMoreSolutions => {
+                        return true;
+                    }
+                    AnswerResult::QuantumExceeded => continue,
+                };
 
-            if !f(subst, !answers.peek_answer(|| true).is_no_more_solutions()) {
-                return false;
+                if !f(subst, !answers.peek_answer(|| true).is_no_more_solutions()) {
+                    return false;
+                }
             }
-        }
+        })
+    }
+
+    fn stats(&self) -> Option<&SolverStats> {
+        Some(&self.forest.stats)
     }
 }
@@ -1,3 +1,4 @@
+use crate::proof::Derivation;
 use crate::slg::TruncatingInferenceTable;
 use crate::table::AnswerIndex;
 use crate::{ExClause, TableIndex, TimeStamp};
@@ -14,6 +15,10 @@ pub(crate) struct CanonicalStrand<I: Interner> {
     pub(crate) selected_subgoal: Option<SelectedSubgoal>,
 
     pub(crate) last_pursued_time: TimeStamp,
+
+    /// How the strand was derived so far, if derivations are being
+    /// recorded.
+    pub(crate) derivation: Option<Derivation<I>>,
 }
 
 pub(crate) struct Strand<I: Interner> {
@@ -25,6 +30,10 @@ pub(crate) struct Strand<I: Interner> {
     pub(crate) selected_subgoal: Option<SelectedSubgoal>,
 
     pub(crate) last_pursued_time: TimeStamp,
+
+    /// How the strand was derived so far, if derivations are being
+    /// recorded.
+    pub(crate) derivation: Option<Derivation<I>>,
 }
 
 #[derive(Clone, Debug)]
@@ -8,6 +8,7 @@ use std::mem;
 
 use chalk_ir::interner::Interner;
 use chalk_ir::{AnswerSubst, Canonical, Goal, InEnvironment, UCanonical};
+use chalk_solve::AmbiguityCause;
 use tracing::{debug, info, instrument};
 
 #[derive(Debug)]
@@ -20,9 +21,10 @@ pub(crate) struct Table<I: Interner> {
     /// or less. This is true for auto traits.
     pub(crate) coinductive_goal: bool,
 
-    /// True if this table is floundered, meaning that it doesn't have
-    /// enough types specified for us to solve.
-    floundered: bool,
+    /// Set if this table is floundered, meaning that it doesn't have
+    /// enough types specified for us to solve (or its goals were too
+    /// large to solve precisely).
+    floundered: Option<AmbiguityCause<I>>,
 
     /// Stores the answers that we have found thus far. When we get a request
     /// for an answer N, we will first check this vector.
@@ -62,7 +64,7 @@ impl<I: Interner> Table<I> {
             table_goal,
             coinductive_goal,
             answers: Vec::new(),
-            floundered: false,
+            floundered: None,
             answers_hash: FxHashMap::default(),
             strands: VecDeque::new(),
             answer_mode: AnswerMode::Complete,
@@ -100,17 +102,22 @@ impl<I: Interner> Table<I> {
         }
     }
 
-    /// Mark the table as floundered -- this also discards all pre-existing answers,
-    /// as they are no longer relevant.
-    pub(crate) fn mark_floundered(&mut self) {
-        self.floundered = true;
+    /// Mark the table as floundered (because of `cause`) -- this also discards all
+    /// pre-existing answers, as they are no longer relevant.
+    pub(crate) fn mark_floundered(&mut self, cause: AmbiguityCause<I>) {
+        self.floundered = Some(cause);
         self.strands = Default::default();
         self.answers = Default::default();
     }
 
     /// Returns true if the table is floundered.
     pub(crate) fn is_floundered(&self) -> bool {
-        self.floundered
+        self.floundered.is_some()
+    }
+
+    /// Why the table floundered, if it did.
+    pub(crate) fn floundered_cause(&self) -> Option<&AmbiguityCause<I>> {
+        self.floundered.as_ref()
     }
 
     /// Adds `answer` to our list of answers, unless it is already present.
@@ -125,7 +132,7 @@ impl<I: Interner> Table<I> {
     /// existing answer replaced.
     #[instrument(level = "debug", skip(self))]
     pub(super) fn push_answer(&mut self, answer: Answer<I>) -> Option<AnswerIndex> {
-        assert!(!self.floundered);
+        assert!(!self.is_floundered());
         debug!(
             "pre-existing entry: {:?}",
             self.answers_hash.get(&answer.subst)
@@ -133,13 +140,13 @@ impl<I: Interner> Table<I> {
 
         let added = match self.answers_hash.entry(answer.subst.clone()) {
             Entry::Vacant(entry) => {
-                entry.insert(answer.ambiguous);
+                entry.insert(answer.ambiguity.is_some());
                 true
             }
 
             Entry::Occupied(entry) => {
                 let was_ambiguous = entry.get();
-                if *was_ambiguous && !answer.ambiguous {
+                if *was_ambiguous && answer.ambiguity.is_none() {
                     panic!("New answer was not ambiguous whereas previous answer was.");
                 }
                 false
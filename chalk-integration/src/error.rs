@@ -1,5 +1,5 @@
 use crate::interner::ChalkIr;
-use chalk_parse::ast::{Identifier, Kind};
+use chalk_parse::ast::{ConstValue, Identifier, Kind};
 use chalk_solve::coherence::CoherenceError;
 use chalk_solve::wf::WfError;
 use string_cache::DefaultAtom as Atom;
@@ -66,6 +66,17 @@ pub enum RustIrError {
     InvalidFundamentalTypesParameters(Identifier),
     NegativeImplAssociatedValues(Identifier),
     MissingAssociatedType(Identifier),
+    MissingAssociatedConst(Identifier),
+    AssociatedConstParameters(Identifier),
+    IncorrectAssociatedConstType(Identifier),
+    InvalidConstValue {
+        value: ConstValue,
+        ty: chalk_ir::Ty<ChalkIr>,
+    },
+    IncorrectConstType {
+        expected: chalk_ir::Ty<ChalkIr>,
+        actual: chalk_ir::Ty<ChalkIr>,
+    },
     IncorrectNumberOfVarianceParameters {
         identifier: Identifier,
         expected: usize,
@@ -98,6 +109,9 @@ pub enum RustIrError {
     },
     CannotApplyTypeParameter(Identifier),
     InvalidExternAbi(Atom),
+    DefaultedParameterNotTrailing(Identifier),
+    CyclicParameterDefault(Atom),
+    ParameterDefaultReferencesSelf(Identifier),
 }
 
 impl std::fmt::Display for RustIrError {
@@ -142,6 +156,25 @@ impl std::fmt::Display for RustIrError {
             RustIrError::MissingAssociatedType(name) => {
                 write!(f, "no associated type `{}` defined in trait", name)
             }
+            RustIrError::MissingAssociatedConst(name) => {
+                write!(f, "no associated const `{}` defined in trait", name)
+            }
+            RustIrError::AssociatedConstParameters(name) => {
+                write!(f, "associated const `{}` cannot have parameters", name)
+            }
+            RustIrError::IncorrectAssociatedConstType(name) => write!(
+                f,
+                "value of associated const `{}` does not have the declared type",
+                name
+            ),
+            RustIrError::InvalidConstValue { value, ty } => {
+                write!(f, "`{}` is not a valid value of type `{:?}`", value, ty)
+            }
+            RustIrError::IncorrectConstType { expected, actual } => write!(
+                f,
+                "incorrect const type: expected `{:?}`, found `{:?}`",
+                expected, actual
+            ),
             RustIrError::IncorrectNumberOfVarianceParameters {
                 identifier,
                 expected,
@@ -200,6 +233,21 @@ impl std::fmt::Display for RustIrError {
                 write!(f, "cannot apply type parameter `{}`", name)
             }
             RustIrError::InvalidExternAbi(abi) => write!(f, "invalid extern ABI `{}`", abi),
+            RustIrError::DefaultedParameterNotTrailing(name) => write!(
+                f,
+                "parameters with a default must come last in the parameters of `{}`",
+                name
+            ),
+            RustIrError::CyclicParameterDefault(name) => write!(
+                f,
+                "cycle detected when computing the parameter defaults of `{}`",
+                name
+            ),
+            RustIrError::ParameterDefaultReferencesSelf(name) => write!(
+                f,
+                "the parameters of trait `{}` whose defaults refer to `Self` must be specified explicitly here",
+                name
+            ),
         }
     }
 }
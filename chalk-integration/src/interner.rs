@@ -1,10 +1,11 @@
 use crate::tls;
 use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::{
-    AdtId, AliasTy, AssocTypeId, CanonicalVarKind, CanonicalVarKinds, ConstData, Constraint,
-    Constraints, FnDefId, Goals, InEnvironment, Lifetime, OpaqueTy, OpaqueTyId,
-    ProgramClauseImplication, ProgramClauses, ProjectionTy, QuantifiedWhereClauses,
-    SeparatorTraitRef, Substitution, TraitId, Ty, TyData, VariableKind, VariableKinds, Variances,
+    AdtId, AliasTy, AssocConstId, AssocTypeId, CanonicalVarKind, CanonicalVarKinds, ConstBinOp,
+    ConstData, ConstEvalError, Constraint, Constraints, FnDefId, Goals, InEnvironment, Lifetime,
+    OpaqueTy, OpaqueTyId, ProgramClauseImplication, ProgramClauses, ProjectionConst, ProjectionTy,
+    QuantifiedWhereClauses, SeparatorTraitRef, Substitution, TraitId, Ty, TyData, VariableKind,
+    VariableKinds, Variances,
 };
 use chalk_ir::{
     GenericArg, GenericArgData, Goal, GoalData, LifetimeData, ProgramClause, ProgramClauseData,
@@ -47,6 +48,88 @@ impl Debug for ChalkFnAbi {
     }
 }
 
+/// A concrete const value, along with the scalar type it has.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChalkConstValue {
+    Bool(bool),
+    Char(char),
+    Int(chalk_ir::IntTy, i128),
+    Uint(chalk_ir::UintTy, u128),
+}
+
+impl ChalkConstValue {
+    /// Creates a value of the signed integer type `ty`, if it is in range.
+    pub fn int(ty: chalk_ir::IntTy, value: i128) -> Option<Self> {
+        let (min, max) = match ty {
+            chalk_ir::IntTy::I8 => (i8::MIN.into(), i8::MAX.into()),
+            chalk_ir::IntTy::I16 => (i16::MIN.into(), i16::MAX.into()),
+            chalk_ir::IntTy::I32 => (i32::MIN.into(), i32::MAX.into()),
+            chalk_ir::IntTy::I64 | chalk_ir::IntTy::Isize => (i64::MIN.into(), i64::MAX.into()),
+            chalk_ir::IntTy::I128 => (i128::MIN, i128::MAX),
+        };
+        if value < min || value > max {
+            None
+        } else {
+            Some(ChalkConstValue::Int(ty, value))
+        }
+    }
+
+    /// Creates a value of the unsigned integer type `ty`, if it is in range.
+    pub fn uint(ty: chalk_ir::UintTy, value: u128) -> Option<Self> {
+        let max = match ty {
+            chalk_ir::UintTy::U8 => u8::MAX.into(),
+            chalk_ir::UintTy::U16 => u16::MAX.into(),
+            chalk_ir::UintTy::U32 => u32::MAX.into(),
+            chalk_ir::UintTy::U64 | chalk_ir::UintTy::Usize => u64::MAX.into(),
+            chalk_ir::UintTy::U128 => u128::MAX,
+        };
+        if value > max {
+            None
+        } else {
+            Some(ChalkConstValue::Uint(ty, value))
+        }
+    }
+
+    /// Evaluates `self op rhs`, which is only defined for integers of the
+    /// same type and when the result is in range.
+    pub fn eval_bin_op(self, op: ConstBinOp, rhs: ChalkConstValue) -> Option<Self> {
+        match (self, rhs) {
+            (ChalkConstValue::Int(ty, a), ChalkConstValue::Int(rhs_ty, b)) if ty == rhs_ty => {
+                let value = match op {
+                    ConstBinOp::Add => a.checked_add(b),
+                    ConstBinOp::Sub => a.checked_sub(b),
+                    ConstBinOp::Mul => a.checked_mul(b),
+                    ConstBinOp::Div => a.checked_div(b),
+                    ConstBinOp::Rem => a.checked_rem(b),
+                }?;
+                ChalkConstValue::int(ty, value)
+            }
+            (ChalkConstValue::Uint(ty, a), ChalkConstValue::Uint(rhs_ty, b)) if ty == rhs_ty => {
+                let value = match op {
+                    ConstBinOp::Add => a.checked_add(b),
+                    ConstBinOp::Sub => a.checked_sub(b),
+                    ConstBinOp::Mul => a.checked_mul(b),
+                    ConstBinOp::Div => a.checked_div(b),
+                    ConstBinOp::Rem => a.checked_rem(b),
+                }?;
+                ChalkConstValue::uint(ty, value)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Debug for ChalkConstValue {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChalkConstValue::Bool(b) => write!(fmt, "{}", b),
+            ChalkConstValue::Char(c) => write!(fmt, "{:?}", c),
+            ChalkConstValue::Int(_, i) => write!(fmt, "{}", i),
+            ChalkConstValue::Uint(_, u) => write!(fmt, "{}", u),
+        }
+    }
+}
+
 /// The default "interner" and the only interner used by chalk
 /// itself. In this interner, no interning actually occurs.
 #[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
@@ -56,7 +139,7 @@ impl Interner for ChalkIr {
     type InternedType = Arc<TyData<ChalkIr>>;
     type InternedLifetime = LifetimeData<ChalkIr>;
     type InternedConst = Arc<ConstData<ChalkIr>>;
-    type InternedConcreteConst = u32;
+    type InternedConcreteConst = ChalkConstValue;
     type InternedGenericArg = GenericArgData<ChalkIr>;
     type InternedGoal = Arc<GoalData<ChalkIr>>;
     type InternedGoals = Vec<Goal<ChalkIr>>;
@@ -94,6 +177,13 @@ impl Interner for ChalkIr {
         tls::with_current_program(|prog| Some(prog?.debug_assoc_type_id(id, fmt)))
     }
 
+    fn debug_assoc_const_id(
+        id: AssocConstId<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        tls::with_current_program(|prog| Some(prog?.debug_assoc_const_id(id, fmt)))
+    }
+
     fn debug_opaque_ty_id(
         id: OpaqueTyId<ChalkIr>,
         fmt: &mut fmt::Formatter<'_>,
@@ -116,6 +206,13 @@ impl Interner for ChalkIr {
         tls::with_current_program(|prog| Some(prog?.debug_projection_ty(proj, fmt)))
     }
 
+    fn debug_projection_const(
+        proj: &ProjectionConst<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        tls::with_current_program(|prog| Some(prog?.debug_projection_const(proj, fmt)))
+    }
+
     fn debug_opaque_ty(
         opaque_ty: &OpaqueTy<ChalkIr>,
         fmt: &mut fmt::Formatter<'_>,
@@ -257,10 +354,25 @@ impl Interner for ChalkIr {
         constant
     }
 
-    fn const_eq(&self, _ty: &Arc<TyData<ChalkIr>>, c1: &u32, c2: &u32) -> bool {
+    fn const_eq(
+        &self,
+        _ty: &Arc<TyData<ChalkIr>>,
+        c1: &ChalkConstValue,
+        c2: &ChalkConstValue,
+    ) -> bool {
         c1 == c2
     }
 
+    fn const_eval_bin_op(
+        &self,
+        op: ConstBinOp,
+        _ty: &Arc<TyData<ChalkIr>>,
+        lhs: &ChalkConstValue,
+        rhs: &ChalkConstValue,
+    ) -> Result<ChalkConstValue, ConstEvalError> {
+        lhs.eval_bin_op(op, *rhs).ok_or(ConstEvalError::Undefined)
+    }
+
     fn intern_generic_arg(&self, generic_arg: GenericArgData<ChalkIr>) -> GenericArgData<ChalkIr> {
         generic_arg
     }
@@ -2,6 +2,7 @@ mod env;
 mod program_lowerer;
 
 use chalk_ir::cast::{Cast, Caster};
+use chalk_ir::visit::{ControlFlow, Visit, Visitor};
 use chalk_ir::{
     self, BoundVar, ClausePriority, DebruijnIndex, ImplId, QuantifiedWhereClauses, Substitution,
     TyVariableKind,
@@ -10,11 +11,12 @@ use chalk_parse::ast::*;
 use chalk_solve::rust_ir::{self, IntoWhereClauses};
 use program_lowerer::ProgramLowerer;
 use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use string_cache::DefaultAtom as Atom;
 use tracing::debug;
 
 use crate::error::RustIrError;
-use crate::interner::{ChalkFnAbi, ChalkIr};
+use crate::interner::{ChalkConstValue, ChalkFnAbi, ChalkIr};
 use crate::program::Program as LoweredProgram;
 use crate::{Identifier as Ident, TypeSort};
 use env::*;
@@ -46,6 +48,8 @@ impl Lower for Program {
 
         lowerer.extract_associated_types(self, &raw_ids)?;
         lowerer.extract_ids(self, &raw_ids)?;
+        lowerer.extract_param_defaults(self, &raw_ids)?;
+        lowerer.extract_associated_consts(self, &raw_ids)?;
         lowerer.lower(self, &raw_ids)
     }
 }
@@ -107,6 +111,77 @@ lower_param_map!(
     ))
 );
 
+/// Lowers the defaults of the parameters of an ADT or a trait, each of
+/// which may only refer to the parameters that come before it. The result
+/// has an entry for each parameter, and lives under the binders of the item.
+fn lower_param_defaults(
+    env: &Env,
+    name: &Identifier,
+    parameters: Vec<chalk_ir::WithKind<ChalkIr, Ident>>,
+    defaults: impl IntoIterator<Item = Option<GenericArg>>,
+) -> LowerResult<ParamDefaults> {
+    let mut lowered: ParamDefaults = Vec::with_capacity(parameters.len());
+    for (i, default) in defaults.into_iter().enumerate() {
+        let default = match default {
+            Some(default) => {
+                let kind = &parameters[i].kind;
+                let env = env.introduce(parameters[..i].iter().cloned())?;
+                let default = (&default, kind).lower(&env)?;
+                if kind.kind() != default.kind() {
+                    Err(RustIrError::IncorrectParameterKind {
+                        identifier: name.clone(),
+                        expected: kind.kind(),
+                        actual: default.kind(),
+                    })?;
+                }
+                Some(default)
+            }
+            None if lowered.iter().any(Option::is_some) => {
+                Err(RustIrError::DefaultedParameterNotTrailing(name.clone()))?
+            }
+            None => None,
+        };
+        lowered.push(default);
+    }
+    Ok(lowered)
+}
+
+/// Whether `default`, the default of a trait parameter under the binders of
+/// the trait, refers to `Self`.
+fn mentions_self(default: &chalk_ir::GenericArg<ChalkIr>) -> bool {
+    struct SelfVisitor;
+
+    impl<'i> Visitor<'i, ChalkIr> for SelfVisitor {
+        type BreakTy = ();
+
+        fn as_dyn(&mut self) -> &mut dyn Visitor<'i, ChalkIr, BreakTy = Self::BreakTy> {
+            self
+        }
+
+        fn interner(&self) -> &'i ChalkIr {
+            &ChalkIr
+        }
+
+        fn visit_free_var(
+            &mut self,
+            bound_var: BoundVar,
+            outer_binder: DebruijnIndex,
+        ) -> ControlFlow<()> {
+            if bound_var.shifted_out_to(outer_binder)
+                == Some(BoundVar::new(DebruijnIndex::INNERMOST, 0))
+            {
+                ControlFlow::BREAK
+            } else {
+                ControlFlow::CONTINUE
+            }
+        }
+    }
+
+    default
+        .visit_with(&mut SelfVisitor, DebruijnIndex::INNERMOST)
+        .is_break()
+}
+
 fn get_type_of_u32() -> chalk_ir::Ty<ChalkIr> {
     chalk_ir::TyKind::Scalar(chalk_ir::Scalar::Uint(chalk_ir::UintTy::U32)).intern(&ChalkIr)
 }
@@ -128,7 +203,12 @@ impl Lower for VariableKind {
                 n,
             ),
             VariableKind::Lifetime(n) => (chalk_ir::VariableKind::Lifetime, n),
-            VariableKind::Const(ref n) => (chalk_ir::VariableKind::Const(get_type_of_u32()), n),
+            VariableKind::Const(ref n, ty) => (
+                chalk_ir::VariableKind::Const(
+                    chalk_ir::TyKind::Scalar(ty.lower()).intern(&ChalkIr),
+                ),
+                n,
+            ),
         };
 
         chalk_ir::WithKind::new(kind, n.str.clone())
@@ -160,6 +240,9 @@ impl LowerWithEnv for WhereClause {
             WhereClause::Implemented { trait_ref } => {
                 vec![chalk_ir::WhereClause::Implemented(trait_ref.lower(env)?)]
             }
+            WhereClause::NotImplemented { trait_ref } => {
+                vec![chalk_ir::WhereClause::NotImplemented(trait_ref.lower(env)?)]
+            }
             WhereClause::ProjectionEq { projection, ty } => vec![
                 chalk_ir::WhereClause::AliasEq(chalk_ir::AliasEq {
                     alias: chalk_ir::AliasTy::Projection(projection.lower(env)?),
@@ -212,12 +295,37 @@ impl LowerWithEnv for DomainGoal {
                 .into_iter()
                 .casted(interner)
                 .collect(),
+            DomainGoal::Normalize { projection, ty } if env.is_associated_const(projection) => {
+                // `Normalize(<T as Trait>::N -> M)`: the right-hand side
+                // parses as a type, but is a const.
+                let value = match ty {
+                    Ty::Id { name } => Const::Id(name.clone()),
+                    Ty::Projection { proj } => Const::Projection(proj.clone()),
+                    _ => Err(RustIrError::IncorrectAssociatedConstType(
+                        projection.name.clone(),
+                    ))?,
+                };
+                DomainGoal::NormalizeConst {
+                    projection: projection.clone(),
+                    value,
+                }
+                .lower(env)?
+            }
             DomainGoal::Normalize { projection, ty } => {
                 vec![chalk_ir::DomainGoal::Normalize(chalk_ir::Normalize {
                     alias: chalk_ir::AliasTy::Projection(projection.lower(env)?),
                     ty: ty.lower(env)?,
                 })]
             }
+            DomainGoal::NormalizeConst { projection, value } => {
+                let (alias, ty) = lower_projection_const(projection, env)?;
+                vec![chalk_ir::DomainGoal::NormalizeConst(
+                    chalk_ir::NormalizeConst {
+                        alias,
+                        value: (value, &ty).lower(env)?,
+                    },
+                )]
+            }
             DomainGoal::TyWellFormed { ty } => vec![chalk_ir::DomainGoal::WellFormed(
                 chalk_ir::WellFormed::Ty(ty.lower(env)?),
             )],
@@ -299,6 +407,7 @@ impl LowerWithEnv for (&AdtDefn, chalk_ir::AdtId<ChalkIr>) {
                     })
                     .collect::<LowerResult<_>>()?,
                 where_clauses: adt_defn.where_clauses.lower(env)?,
+                param_defaults: env.adt_defaults(*adt_id)?.clone(),
             })
         })?;
 
@@ -428,14 +537,21 @@ impl LowerWithEnv for TraitRef {
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let interner = env.interner();
-        let without_self = TraitBound {
-            trait_name: self.trait_name.clone(),
-            args_no_self: self.args.iter().cloned().skip(1).collect(),
-        }
-        .lower(env)?;
+        // Report an invalid trait before an invalid self type.
+        env.lookup_trait(&self.trait_name)?;
 
         let self_parameter = self.args[0].lower(env)?;
-        Ok(without_self.as_trait_ref(interner, self_parameter.assert_ty_ref(interner).clone()))
+        let self_ty = self_parameter.assert_ty_ref(interner);
+        let without_self = (
+            &TraitBound {
+                trait_name: self.trait_name.clone(),
+                args_no_self: self.args.iter().cloned().skip(1).collect(),
+            },
+            Some(self_ty),
+        )
+            .lower(env)?;
+
+        Ok(without_self.as_trait_ref(interner, self_ty.clone()))
     }
 }
 
@@ -443,32 +559,71 @@ impl LowerWithEnv for TraitBound {
     type Lowered = rust_ir::TraitBound<ChalkIr>;
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        (self, None).lower(env)
+    }
+}
+
+/// Lowers a trait bound for the given self type, if it is known. The self
+/// type is only needed when an omitted parameter has a default that refers
+/// to `Self`.
+impl LowerWithEnv for (&TraitBound, Option<&chalk_ir::Ty<ChalkIr>>) {
+    type Lowered = rust_ir::TraitBound<ChalkIr>;
+
+    fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        let (bound, self_ty) = *self;
         let interner = env.interner();
-        let trait_id = env.lookup_trait(&self.trait_name)?;
+        let trait_id = env.lookup_trait(&bound.trait_name)?;
 
         let k = env.trait_kind(trait_id);
         if k.sort != TypeSort::Trait {
-            Err(RustIrError::NotTrait(self.trait_name.clone()))?;
+            Err(RustIrError::NotTrait(bound.trait_name.clone()))?;
+        }
+
+        let incorrect_number_of_parameters = || RustIrError::IncorrectNumberOfTypeParameters {
+            identifier: bound.trait_name.clone(),
+            expected: k.binders.len(interner),
+            actual: bound.args_no_self.len(),
+        };
+        if bound.args_no_self.len() > k.binders.len(interner) {
+            Err(incorrect_number_of_parameters())?;
         }
 
-        let parameters = self
+        let mut parameters = bound
             .args_no_self
             .iter()
-            .map(|a| a.lower(env))
+            .zip(k.binders.binders.iter(interner))
+            .map(|(a, kind)| (a, kind).lower(env))
             .collect::<LowerResult<Vec<_>>>()?;
 
-        if parameters.len() != k.binders.len(interner) {
-            Err(RustIrError::IncorrectNumberOfTypeParameters {
-                identifier: self.trait_name.clone(),
-                expected: k.binders.len(interner),
-                actual: parameters.len(),
-            })?;
+        if parameters.len() < k.binders.len(interner) {
+            let defaults = env.trait_defaults(trait_id)?;
+            let self_ty = match self_ty {
+                Some(self_ty) => self_ty.clone(),
+                None => {
+                    let omitted = defaults.iter().skip(1 + parameters.len());
+                    if omitted.flatten().any(mentions_self) {
+                        Err(RustIrError::ParameterDefaultReferencesSelf(
+                            bound.trait_name.clone(),
+                        ))?;
+                    }
+                    // None of the defaults we use refer to `Self`, so any
+                    // type will do.
+                    chalk_ir::TyKind::Error.intern(interner)
+                }
+            };
+            let mut all_parameters = vec![self_ty.cast(interner)];
+            all_parameters.extend(parameters);
+            env.fill_param_defaults(defaults, &mut all_parameters);
+            parameters = all_parameters.split_off(1);
+            if parameters.len() < k.binders.len(interner) {
+                Err(incorrect_number_of_parameters())?;
+            }
         }
 
         for (binder, param) in k.binders.binders.iter(interner).zip(parameters.iter()) {
             if binder.kind() != param.kind() {
                 Err(RustIrError::IncorrectTraitParameterKind {
-                    identifier: self.trait_name.clone(),
+                    identifier: bound.trait_name.clone(),
                     expected: binder.kind(),
                     actual: param.kind(),
                 })?;
@@ -488,20 +643,21 @@ impl LowerWithEnv for AliasEqBound {
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let trait_bound = self.trait_bound.lower(env)?;
         let lookup = env.lookup_associated_ty(trait_bound.trait_id, &self.name)?;
-        let args: Vec<_> = self
-            .args
-            .iter()
-            .map(|a| a.lower(env))
-            .collect::<LowerResult<_>>()?;
-
-        if args.len() != lookup.addl_variable_kinds.len() {
+        if self.args.len() != lookup.addl_variable_kinds.len() {
             Err(RustIrError::IncorrectNumberOfAssociatedTypeParameters {
                 identifier: self.name.clone(),
                 expected: lookup.addl_variable_kinds.len(),
-                actual: args.len(),
+                actual: self.args.len(),
             })?;
         }
 
+        let args: Vec<_> = self
+            .args
+            .iter()
+            .zip(lookup.addl_variable_kinds.iter())
+            .map(|(a, kind)| (a, kind).lower(env))
+            .collect::<LowerResult<_>>()?;
+
         for (param, arg) in lookup.addl_variable_kinds.iter().zip(args.iter()) {
             if param.kind() != arg.kind() {
                 Err(RustIrError::IncorrectAssociatedTypeParameterKind {
@@ -606,6 +762,7 @@ impl Lower for TraitFlags {
             fundamental: self.fundamental,
             non_enumerable: self.non_enumerable,
             coinductive: self.coinductive,
+            alias: self.alias,
         }
     }
 }
@@ -625,11 +782,6 @@ impl LowerWithEnv for ProjectionTy {
             substitution: trait_substitution,
         } = trait_ref.lower(env)?;
         let lookup = env.lookup_associated_ty(trait_id, name)?;
-        let mut args: Vec<_> = args
-            .iter()
-            .map(|a| a.lower(env))
-            .collect::<LowerResult<_>>()?;
-
         if args.len() != lookup.addl_variable_kinds.len() {
             Err(RustIrError::IncorrectNumberOfAssociatedTypeParameters {
                 identifier: self.name.clone(),
@@ -638,6 +790,12 @@ impl LowerWithEnv for ProjectionTy {
             })?;
         }
 
+        let mut args: Vec<_> = args
+            .iter()
+            .zip(lookup.addl_variable_kinds.iter())
+            .map(|(a, kind)| (a, kind).lower(env))
+            .collect::<LowerResult<_>>()?;
+
         for (param, arg) in lookup.addl_variable_kinds.iter().zip(args.iter()) {
             if param.kind() != arg.kind() {
                 Err(RustIrError::IncorrectAssociatedTypeParameterKind {
@@ -705,19 +863,32 @@ impl LowerWithEnv for Ty {
 
             Ty::Apply { name, ref args } => {
                 macro_rules! tykind {
-                    ($k:expr, $tykind:ident, $id:expr) => {{
-                        if $k.binders.len(interner) != args.len() {
-                            Err(RustIrError::IncorrectNumberOfTypeParameters {
+                    ($k:expr, $tykind:ident, $id:expr) => {
+                        tykind!($k, $tykind, $id, &[])
+                    };
+                    ($k:expr, $tykind:ident, $id:expr, $defaults:expr) => {{
+                        let incorrect_number_of_parameters =
+                            || RustIrError::IncorrectNumberOfTypeParameters {
                                 identifier: name.clone(),
                                 expected: $k.binders.len(interner),
                                 actual: args.len(),
-                            })?;
+                            };
+                        if args.len() > $k.binders.len(interner) {
+                            Err(incorrect_number_of_parameters())?;
                         }
 
-                        let substitution = chalk_ir::Substitution::from_fallible(
-                            interner,
-                            args.iter().map(|t| Ok(t.lower(env)?)),
-                        )?;
+                        let mut parameters = args
+                            .iter()
+                            .zip($k.binders.binders.iter(interner))
+                            .map(|(a, kind)| (a, kind).lower(env))
+                            .collect::<LowerResult<Vec<_>>>()?;
+                        if parameters.len() < $k.binders.len(interner) {
+                            env.fill_param_defaults($defaults, &mut parameters);
+                            if parameters.len() < $k.binders.len(interner) {
+                                Err(incorrect_number_of_parameters())?;
+                            }
+                        }
+                        let substitution = chalk_ir::Substitution::from_iter(interner, parameters);
 
                         for (param, arg) in $k
                             .binders
@@ -740,7 +911,9 @@ impl LowerWithEnv for Ty {
                     TypeLookup::Parameter(_) => {
                         return Err(RustIrError::CannotApplyTypeParameter(name.clone()))
                     }
-                    TypeLookup::Adt(id) => tykind!(env.adt_kind(id), Adt, id),
+                    TypeLookup::Adt(id) => {
+                        tykind!(env.adt_kind(id), Adt, id, env.adt_defaults(id)?)
+                    }
                     TypeLookup::FnDef(id) => tykind!(env.fn_def_kind(id), FnDef, id),
                     TypeLookup::Closure(id) => tykind!(env.closure_kind(id), Closure, id),
                     TypeLookup::Opaque(id) => tykind!(env.opaque_kind(id), OpaqueType, id),
@@ -789,7 +962,8 @@ impl LowerWithEnv for Ty {
             Ty::Scalar { ty } => chalk_ir::TyKind::Scalar(ty.lower()).intern(interner),
 
             Ty::Array { ty, len } => {
-                chalk_ir::TyKind::Array(ty.lower(env)?, len.lower(env)?).intern(interner)
+                chalk_ir::TyKind::Array(ty.lower(env)?, (len, &get_type_of_u32()).lower(env)?)
+                    .intern(interner)
             }
 
             Ty::Slice { ty } => chalk_ir::TyKind::Slice(ty.lower(env)?).intern(interner),
@@ -829,21 +1003,164 @@ impl LowerWithEnv for Const {
                     })
                     .map(|c| c.clone())
             }
+            Const::Value(value) => {
+                // Without an expected type, literals get the type they
+                // would be given by default in Rust.
+                let ty = match value {
+                    ConstValue::Bool(_) => chalk_ir::Scalar::Bool,
+                    ConstValue::Char(_) => chalk_ir::Scalar::Char,
+                    ConstValue::Int { negative: true, .. } => {
+                        chalk_ir::Scalar::Int(chalk_ir::IntTy::I32)
+                    }
+                    ConstValue::Int {
+                        negative: false, ..
+                    } => chalk_ir::Scalar::Uint(chalk_ir::UintTy::U32),
+                };
+                (self, &chalk_ir::TyKind::Scalar(ty).intern(interner)).lower(env)
+            }
+            Const::Projection(projection) => {
+                let (projection, ty) = lower_projection_const(projection, env)?;
+                Ok(chalk_ir::ConstData {
+                    ty,
+                    value: chalk_ir::ConstValue::Projection(projection),
+                }
+                .intern(interner))
+            }
+            Const::Expr { op, lhs, rhs } => {
+                // The left-hand side determines the type of the expression.
+                let lhs = lhs.lower(env)?;
+                let ty = lhs.data(interner).ty.clone();
+                let rhs = (&**rhs, &ty).lower(env)?;
+                Ok(chalk_ir::ConstData {
+                    ty,
+                    value: chalk_ir::ConstValue::Expr(chalk_ir::ConstExpr {
+                        op: op.lower(),
+                        lhs,
+                        rhs,
+                    }),
+                }
+                .intern(interner))
+            }
+        }
+    }
+}
+
+/// Lowers a const that is expected to have type `ty`. Literals are given
+/// that type, and must be valid values of it.
+impl LowerWithEnv for (&Const, &chalk_ir::Ty<ChalkIr>) {
+    type Lowered = chalk_ir::Const<ChalkIr>;
+
+    fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        let interner = env.interner();
+        let (c, ty) = *self;
+        match c {
             Const::Value(value) => Ok(chalk_ir::ConstData {
-                ty: get_type_of_u32(),
-                value: chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst { interned: *value }),
+                ty: ty.clone(),
+                value: chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst {
+                    interned: lower_const_value(value, ty)?,
+                }),
             }
             .intern(interner)),
+            Const::Expr { op, lhs, rhs } => Ok(chalk_ir::ConstData {
+                ty: ty.clone(),
+                value: chalk_ir::ConstValue::Expr(chalk_ir::ConstExpr {
+                    op: op.lower(),
+                    lhs: (&**lhs, ty).lower(env)?,
+                    rhs: (&**rhs, ty).lower(env)?,
+                }),
+            }
+            .intern(interner)),
+            _ => {
+                let c = c.lower(env)?;
+                let actual = &c.data(interner).ty;
+                if actual != ty {
+                    Err(RustIrError::IncorrectConstType {
+                        expected: ty.clone(),
+                        actual: actual.clone(),
+                    })?;
+                }
+                Ok(c)
+            }
         }
     }
 }
 
+/// Converts a literal into a value of the scalar type `ty`, checking that it
+/// is in range.
+fn lower_const_value(
+    value: &ConstValue,
+    ty: &chalk_ir::Ty<ChalkIr>,
+) -> LowerResult<ChalkConstValue> {
+    let invalid = || RustIrError::InvalidConstValue {
+        value: *value,
+        ty: ty.clone(),
+    };
+    let scalar = match ty.kind(&ChalkIr) {
+        chalk_ir::TyKind::Scalar(scalar) => *scalar,
+        _ => return Err(invalid()),
+    };
+    Ok(match (*value, scalar) {
+        (ConstValue::Bool(b), chalk_ir::Scalar::Bool) => ChalkConstValue::Bool(b),
+        (ConstValue::Char(c), chalk_ir::Scalar::Char) => ChalkConstValue::Char(c),
+        (ConstValue::Int { negative, value }, chalk_ir::Scalar::Int(int_ty)) => {
+            // `i128::MIN` has no positive counterpart, so negate after the
+            // conversion, which wraps exactly in that case.
+            let value = if negative {
+                if value > i128::MIN.unsigned_abs() {
+                    return Err(invalid());
+                }
+                (value as i128).wrapping_neg()
+            } else {
+                i128::try_from(value).map_err(|_| invalid())?
+            };
+            ChalkConstValue::int(int_ty, value).ok_or_else(invalid)?
+        }
+        (ConstValue::Int { negative, value }, chalk_ir::Scalar::Uint(uint_ty)) => {
+            if negative {
+                return Err(invalid());
+            }
+            ChalkConstValue::uint(uint_ty, value).ok_or_else(invalid)?
+        }
+        _ => return Err(invalid()),
+    })
+}
+
+/// Lowers a projection naming an associated const, e.g. `<T as Trait>::N`,
+/// returning it along with the type of the const.
+fn lower_projection_const(
+    projection: &ProjectionTy,
+    env: &Env,
+) -> LowerResult<(chalk_ir::ProjectionConst<ChalkIr>, chalk_ir::Ty<ChalkIr>)> {
+    let chalk_ir::TraitRef {
+        trait_id,
+        substitution,
+    } = projection.trait_ref.lower(env)?;
+    let lookup = env.lookup_associated_const(trait_id, &projection.name)?;
+    if !projection.args.is_empty() {
+        Err(RustIrError::AssociatedConstParameters(
+            projection.name.clone(),
+        ))?;
+    }
+
+    let ty = lookup.ty.clone().substitute(env.interner(), &substitution);
+    Ok((
+        chalk_ir::ProjectionConst {
+            associated_const_id: lookup.id,
+            substitution,
+        },
+        ty,
+    ))
+}
+
 impl LowerWithEnv for GenericArg {
     type Lowered = chalk_ir::GenericArg<ChalkIr>;
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let interner = env.interner();
         match self {
+            GenericArg::Ty(Ty::Projection { proj }) if env.is_associated_const(proj) => {
+                Ok(Const::Projection(proj.clone()).lower(env)?.cast(interner))
+            }
             GenericArg::Ty(ref t) => Ok(t.lower(env)?.cast(interner)),
             GenericArg::Lifetime(ref l) => Ok(l.lower(env)?.cast(interner)),
             GenericArg::Id(name) => env.lookup_generic_arg(&name),
@@ -852,6 +1169,44 @@ impl LowerWithEnv for GenericArg {
     }
 }
 
+/// Lowers a generic argument given for a parameter of kind `kind`. Consts
+/// are checked against the type of a const parameter; mismatched kinds are
+/// left for the caller to report.
+impl LowerWithEnv for (&GenericArg, &chalk_ir::VariableKind<ChalkIr>) {
+    type Lowered = chalk_ir::GenericArg<ChalkIr>;
+
+    fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        let interner = env.interner();
+        let (arg, kind) = *self;
+        let ty = match kind {
+            chalk_ir::VariableKind::Const(ty) => ty,
+            _ => return arg.lower(env),
+        };
+        match arg {
+            GenericArg::Const(c) => Ok((c, ty).lower(env)?.cast(interner)),
+            GenericArg::Ty(Ty::Projection { proj }) if env.is_associated_const(proj) => {
+                Ok((&Const::Projection(proj.clone()), ty)
+                    .lower(env)?
+                    .cast(interner))
+            }
+            GenericArg::Id(name) => {
+                let arg = env.lookup_generic_arg(name)?;
+                if let Some(c) = arg.constant(interner) {
+                    let actual = &c.data(interner).ty;
+                    if actual != ty {
+                        Err(RustIrError::IncorrectConstType {
+                            expected: ty.clone(),
+                            actual: actual.clone(),
+                        })?;
+                    }
+                }
+                Ok(arg)
+            }
+            _ => arg.lower(env),
+        }
+    }
+}
+
 impl LowerWithEnv for Lifetime {
     type Lowered = chalk_ir::Lifetime<ChalkIr>;
 
@@ -884,18 +1239,27 @@ impl LowerWithEnv for Lifetime {
     }
 }
 
-impl LowerWithEnv for (&Impl, ImplId<ChalkIr>, &AssociatedTyValueIds) {
+impl LowerWithEnv
+    for (
+        &Impl,
+        ImplId<ChalkIr>,
+        &AssociatedTyValueIds,
+        &AssociatedConstValueIds,
+    )
+{
     type Lowered = rust_ir::ImplDatum<ChalkIr>;
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
-        let (impl_, impl_id, associated_ty_value_ids) = self;
+        let (impl_, impl_id, associated_ty_value_ids, associated_const_value_ids) = self;
 
         let polarity = impl_.polarity.lower();
         let binders = env.in_binders(impl_.all_parameters(), |env| {
             let trait_ref = impl_.trait_ref.lower(env)?;
             debug!(?trait_ref);
 
-            if !polarity.is_positive() && !impl_.assoc_ty_values.is_empty() {
+            let has_assoc_values =
+                !impl_.assoc_ty_values.is_empty() || !impl_.assoc_const_values.is_empty();
+            if !polarity.is_positive() && has_assoc_values {
                 Err(RustIrError::NegativeImplAssociatedValues(
                     impl_.trait_ref.trait_name.clone(),
                 ))?;
@@ -920,11 +1284,18 @@ impl LowerWithEnv for (&Impl, ImplId<ChalkIr>, &AssociatedTyValueIds) {
 
         debug!(?associated_ty_value_ids);
 
+        let associated_const_value_ids = impl_
+            .assoc_const_values
+            .iter()
+            .map(|acv| associated_const_value_ids[&(*impl_id, acv.name.str.clone())])
+            .collect();
+
         Ok(rust_ir::ImplDatum {
             polarity,
             binders,
             impl_type: impl_.impl_type.lower(),
             associated_ty_value_ids,
+            associated_const_value_ids,
         })
     }
 }
@@ -987,8 +1358,23 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
                 }
             }
 
+            // The bounds of a trait alias are where clauses on `Self`, which
+            // come before the where clauses of the alias itself.
+            let self_ty = chalk_ir::TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, 0))
+                .intern(env.interner());
+            let mut where_clauses = Vec::new();
+            for bound in &trait_defn.bounds {
+                where_clauses.extend(
+                    bound
+                        .lower(env)?
+                        .into_where_clauses(env.interner(), self_ty.clone()),
+                );
+            }
+            where_clauses.extend(trait_defn.where_clauses.lower(env)?);
+
             Ok(rust_ir::TraitDatumBound {
-                where_clauses: trait_defn.where_clauses.lower(env)?,
+                where_clauses,
+                param_defaults: env.trait_defaults(*trait_id)?.clone(),
             })
         })?;
 
@@ -998,11 +1384,22 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
             .map(|defn| env.lookup_associated_ty(*trait_id, &defn.name).unwrap().id)
             .collect();
 
+        let associated_const_ids: Vec<_> = trait_defn
+            .assoc_const_defns
+            .iter()
+            .map(|defn| {
+                env.lookup_associated_const(*trait_id, &defn.name)
+                    .unwrap()
+                    .id
+            })
+            .collect();
+
         let trait_datum = rust_ir::TraitDatum {
             id: *trait_id,
             binders,
             flags: trait_defn.flags.lower(),
             associated_ty_ids,
+            associated_const_ids,
             well_known: trait_defn.well_known.map(|def| def.lower()),
         };
 
@@ -1031,12 +1428,41 @@ pub fn lower_goal(goal: &Goal, program: &LoweredProgram) -> LowerResult<chalk_ir
         })
         .collect();
 
+    let associated_const_lookups: BTreeMap<_, _> = program
+        .associated_const_data
+        .iter()
+        .map(|(&associated_const_id, datum)| {
+            let lookup = AssociatedConstLookup {
+                id: associated_const_id,
+                ty: datum.ty.clone(),
+            };
+            ((datum.trait_id, datum.name.clone()), lookup)
+        })
+        .collect();
+
     let auto_traits = program
         .trait_data
         .iter()
         .map(|(&trait_id, datum)| (trait_id, datum.flags.auto))
         .collect();
 
+    let adt_defaults = program
+        .adt_data
+        .iter()
+        .map(|(&adt_id, datum)| (adt_id, datum.binders.skip_binders().param_defaults.clone()))
+        .collect();
+
+    let trait_defaults = program
+        .trait_data
+        .iter()
+        .map(|(&trait_id, datum)| {
+            (
+                trait_id,
+                datum.binders.skip_binders().param_defaults.clone(),
+            )
+        })
+        .collect();
+
     let env = Env {
         adt_ids: &program.adt_ids,
         fn_def_ids: &program.fn_def_ids,
@@ -1046,11 +1472,14 @@ pub fn lower_goal(goal: &Goal, program: &LoweredProgram) -> LowerResult<chalk_ir
         generator_ids: &program.generator_ids,
         generator_kinds: &program.generator_kinds,
         adt_kinds: &program.adt_kinds,
+        adt_defaults: &adt_defaults,
         fn_def_kinds: &program.fn_def_kinds,
         closure_kinds: &program.closure_kinds,
         trait_kinds: &program.trait_kinds,
+        trait_defaults: &trait_defaults,
         opaque_ty_kinds: &program.opaque_ty_kinds,
         associated_ty_lookups: &associated_ty_lookups,
+        associated_const_lookups: &associated_const_lookups,
         foreign_ty_ids: &program.foreign_ty_ids,
         parameter_map: BTreeMap::new(),
         auto_traits: &auto_traits,
@@ -1133,6 +1562,31 @@ impl Lower for WellKnownTrait {
             WellKnownTrait::Unpin => rust_ir::WellKnownTrait::Unpin,
             WellKnownTrait::CoerceUnsized => rust_ir::WellKnownTrait::CoerceUnsized,
             WellKnownTrait::DiscriminantKind => rust_ir::WellKnownTrait::DiscriminantKind,
+            WellKnownTrait::DispatchFromDyn => rust_ir::WellKnownTrait::DispatchFromDyn,
+            WellKnownTrait::Deref => rust_ir::WellKnownTrait::Deref,
+            WellKnownTrait::Pointee => rust_ir::WellKnownTrait::Pointee,
+            WellKnownTrait::Generator => rust_ir::WellKnownTrait::Generator,
+        }
+    }
+}
+
+impl Lower for Movability {
+    type Lowered = rust_ir::Movability;
+
+    fn lower(&self) -> Self::Lowered {
+        match self {
+            Movability::Static => rust_ir::Movability::Static,
+            Movability::Movable => rust_ir::Movability::Movable,
+        }
+    }
+}
+
+impl Lower for WellKnownAdt {
+    type Lowered = rust_ir::WellKnownAdt;
+
+    fn lower(&self) -> Self::Lowered {
+        match self {
+            WellKnownAdt::DynMetadata => rust_ir::WellKnownAdt::DynMetadata,
         }
     }
 }
@@ -1217,6 +1671,20 @@ impl Lower for ScalarType {
     }
 }
 
+impl Lower for ConstBinOp {
+    type Lowered = chalk_ir::ConstBinOp;
+
+    fn lower(&self) -> Self::Lowered {
+        match self {
+            ConstBinOp::Add => chalk_ir::ConstBinOp::Add,
+            ConstBinOp::Sub => chalk_ir::ConstBinOp::Sub,
+            ConstBinOp::Mul => chalk_ir::ConstBinOp::Mul,
+            ConstBinOp::Div => chalk_ir::ConstBinOp::Div,
+            ConstBinOp::Rem => chalk_ir::ConstBinOp::Rem,
+        }
+    }
+}
+
 impl Lower for Mutability {
     type Lowered = chalk_ir::Mutability;
     fn lower(&self) -> Self::Lowered {
@@ -3,9 +3,9 @@ use chalk_ir::{
     self, AdtId, BoundVar, ClosureId, DebruijnIndex, FnDefId, GeneratorId, OpaqueTyId, TraitId,
     VariableKinds,
 };
-use chalk_ir::{cast::Cast, ForeignDefId, WithKind};
+use chalk_ir::{cast::Cast, fold::Subst, ForeignDefId, WithKind};
 use chalk_parse::ast::*;
-use chalk_solve::rust_ir::AssociatedTyValueId;
+use chalk_solve::rust_ir::{AssociatedConstValueId, AssociatedTyValueId};
 use std::collections::BTreeMap;
 
 use crate::error::RustIrError;
@@ -28,7 +28,14 @@ pub type GeneratorKinds = BTreeMap<chalk_ir::GeneratorId<ChalkIr>, TypeKind>;
 pub type AssociatedTyLookups = BTreeMap<(chalk_ir::TraitId<ChalkIr>, Ident), AssociatedTyLookup>;
 pub type AssociatedTyValueIds =
     BTreeMap<(chalk_ir::ImplId<ChalkIr>, Ident), AssociatedTyValueId<ChalkIr>>;
+pub type AssociatedConstLookups =
+    BTreeMap<(chalk_ir::TraitId<ChalkIr>, Ident), AssociatedConstLookup>;
+pub type AssociatedConstValueIds =
+    BTreeMap<(chalk_ir::ImplId<ChalkIr>, Ident), AssociatedConstValueId<ChalkIr>>;
 pub type ForeignIds = BTreeMap<Ident, chalk_ir::ForeignDefId<ChalkIr>>;
+pub type ParamDefaults = Vec<Option<chalk_ir::GenericArg<ChalkIr>>>;
+pub type AdtDefaults = BTreeMap<chalk_ir::AdtId<ChalkIr>, ParamDefaults>;
+pub type TraitDefaults = BTreeMap<chalk_ir::TraitId<ChalkIr>, ParamDefaults>;
 
 pub type ParameterMap = BTreeMap<Ident, chalk_ir::WithKind<ChalkIr, BoundVar>>;
 
@@ -38,15 +45,18 @@ pub type LowerResult<T> = Result<T, RustIrError>;
 pub struct Env<'k> {
     pub adt_ids: &'k AdtIds,
     pub adt_kinds: &'k AdtKinds,
+    pub adt_defaults: &'k AdtDefaults,
     pub fn_def_ids: &'k FnDefIds,
     pub fn_def_kinds: &'k FnDefKinds,
     pub closure_ids: &'k ClosureIds,
     pub closure_kinds: &'k ClosureKinds,
     pub trait_ids: &'k TraitIds,
     pub trait_kinds: &'k TraitKinds,
+    pub trait_defaults: &'k TraitDefaults,
     pub opaque_ty_ids: &'k OpaqueTyIds,
     pub opaque_ty_kinds: &'k OpaqueTyVariableKinds,
     pub associated_ty_lookups: &'k AssociatedTyLookups,
+    pub associated_const_lookups: &'k AssociatedConstLookups,
     pub auto_traits: &'k AutoTraits,
     pub foreign_ty_ids: &'k ForeignIds,
     pub generator_ids: &'k GeneratorIds,
@@ -75,6 +85,22 @@ pub struct AssociatedTyLookup {
     pub addl_variable_kinds: Vec<chalk_ir::VariableKind<ChalkIr>>,
 }
 
+/// Information about an associated const **declaration** (i.e., an
+/// `AssociatedConstDatum`), used to lookup the "id" and the type of an
+/// associated const.
+///
+/// ```ignore
+/// trait Foo {
+///     const N: usize; // <-- associated const declaration
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct AssociatedConstLookup {
+    pub id: chalk_ir::AssocConstId<ChalkIr>,
+    /// The type of the const, with the parameters of the trait bound.
+    pub ty: chalk_ir::Binders<chalk_ir::Ty<ChalkIr>>,
+}
+
 pub enum TypeLookup<'k> {
     Parameter(&'k WithKind<ChalkIr, BoundVar>),
     Adt(AdtId<ChalkIr>),
@@ -99,20 +125,28 @@ impl Env<'_> {
 
         macro_rules! tykind {
             ($k:expr, $tykind:ident, $id:expr) => {
+                tykind!($k, $tykind, $id, &[])
+            };
+            ($k:expr, $tykind:ident, $id:expr, $defaults:expr) => {{
+                let mut parameters = Vec::new();
                 if $k.binders.len(interner) > 0 {
+                    self.fill_param_defaults($defaults, &mut parameters);
+                }
+                if $k.binders.len(interner) != parameters.len() {
                     Err(RustIrError::IncorrectNumberOfTypeParameters {
                         identifier: name.clone(),
                         expected: $k.binders.len(interner),
                         actual: 0,
                     })
                 } else {
-                    Ok(
-                        chalk_ir::TyKind::$tykind($id, chalk_ir::Substitution::empty(interner))
-                            .intern(interner),
+                    Ok(chalk_ir::TyKind::$tykind(
+                        $id,
+                        chalk_ir::Substitution::from_iter(interner, parameters),
                     )
+                    .intern(interner))
                     .cast(interner)
                 }
-            };
+            }};
         }
 
         match self.lookup_type(name) {
@@ -130,7 +164,7 @@ impl Env<'_> {
                     }
                 })
             }
-            Ok(TypeLookup::Adt(id)) => tykind!(self.adt_kind(id), Adt, id),
+            Ok(TypeLookup::Adt(id)) => tykind!(self.adt_kind(id), Adt, id, self.adt_defaults(id)?),
             Ok(TypeLookup::FnDef(id)) => tykind!(self.fn_def_kind(id), FnDef, id),
             Ok(TypeLookup::Closure(id)) => tykind!(self.closure_kind(id), Closure, id),
             Ok(TypeLookup::Generator(id)) => tykind!(self.generator_kind(id), Generator, id),
@@ -196,6 +230,37 @@ impl Env<'_> {
         &self.adt_kinds[&id]
     }
 
+    /// The parameter defaults of an ADT, under the binders of the ADT.
+    pub fn adt_defaults(&self, id: chalk_ir::AdtId<ChalkIr>) -> LowerResult<&ParamDefaults> {
+        self.adt_defaults
+            .get(&id)
+            .ok_or_else(|| RustIrError::CyclicParameterDefault(self.adt_kind(id).name.clone()))
+    }
+
+    /// The parameter defaults of a trait, under the binders of the trait
+    /// (so the first entry is the one for `Self`).
+    pub fn trait_defaults(&self, id: chalk_ir::TraitId<ChalkIr>) -> LowerResult<&ParamDefaults> {
+        self.trait_defaults
+            .get(&id)
+            .ok_or_else(|| RustIrError::CyclicParameterDefault(self.trait_kind(id).name.clone()))
+    }
+
+    /// Extends `parameters`, the arguments given for the leading parameters
+    /// of an item, with the defaults of the parameters that follow, stopping
+    /// at the first one that has no default. It is up to the caller to check
+    /// that all parameters were given a value.
+    pub fn fill_param_defaults(
+        &self,
+        defaults: &[Option<chalk_ir::GenericArg<ChalkIr>>],
+        parameters: &mut Vec<chalk_ir::GenericArg<ChalkIr>>,
+    ) {
+        while let Some(Some(default)) = defaults.get(parameters.len()) {
+            // A default only refers to the parameters that come before it.
+            let default = Subst::apply(self.interner(), parameters, default.clone());
+            parameters.push(default);
+        }
+    }
+
     pub fn fn_def_kind(&self, id: chalk_ir::FnDefId<ChalkIr>) -> &TypeKind {
         &self.fn_def_kinds[&id]
     }
@@ -222,6 +287,28 @@ impl Env<'_> {
             .ok_or(RustIrError::MissingAssociatedType(ident.clone()))
     }
 
+    pub fn lookup_associated_const(
+        &self,
+        trait_id: TraitId<ChalkIr>,
+        ident: &Identifier,
+    ) -> LowerResult<&AssociatedConstLookup> {
+        self.associated_const_lookups
+            .get(&(trait_id, ident.str.clone()))
+            .ok_or(RustIrError::MissingAssociatedConst(ident.clone()))
+    }
+
+    /// Whether `projection` names an associated const of its trait (e.g.
+    /// `<T as Trait>::N`) rather than an associated type. The two are
+    /// written the same way, so this is used to tell them apart.
+    pub fn is_associated_const(&self, projection: &ProjectionTy) -> bool {
+        match self.lookup_trait(&projection.trait_ref.trait_name) {
+            Ok(trait_id) => self
+                .associated_const_lookups
+                .contains_key(&(trait_id, projection.name.str.clone())),
+            Err(_) => false,
+        }
+    }
+
     /// Introduces new parameters, shifting the indices of existing
     /// parameters to accommodate them. The indices of the new binders
     /// will be assigned in order as they are iterated.
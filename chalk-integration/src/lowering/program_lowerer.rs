@@ -1,19 +1,20 @@
 use chalk_ir::cast::Cast;
 use chalk_ir::{
-    self, AdtId, AssocTypeId, BoundVar, ClosureId, DebruijnIndex, FnDefId, ForeignDefId,
-    GeneratorId, ImplId, OpaqueTyId, TraitId, TyVariableKind, VariableKinds,
+    self, AdtId, AssocConstId, AssocTypeId, BoundVar, ClosureId, DebruijnIndex, FnDefId,
+    ForeignDefId, GeneratorId, ImplId, OpaqueTyId, TraitId, TyVariableKind, VariableKinds,
 };
 use chalk_parse::ast::*;
 use chalk_solve::rust_ir::{
-    self, Anonymize, AssociatedTyValueId, GeneratorDatum, GeneratorInputOutputDatum,
-    GeneratorWitnessDatum, GeneratorWitnessExistential, OpaqueTyDatum, OpaqueTyDatumBound,
+    self, Anonymize, AssociatedConstValueId, AssociatedTyValueId, GeneratorDatum,
+    GeneratorInputOutputDatum, GeneratorWitnessDatum, GeneratorWitnessExistential, OpaqueTyDatum,
+    OpaqueTyDatumBound,
 };
 use rust_ir::IntoWhereClauses;
 use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use string_cache::DefaultAtom as Atom;
 
-use super::{env::*, Lower, LowerParameterMap, LowerWithEnv, FIXME_SELF};
+use super::{env::*, lower_param_defaults, Lower, LowerParameterMap, LowerWithEnv, FIXME_SELF};
 use crate::error::RustIrError;
 use crate::program::Program as LoweredProgram;
 use crate::RawId;
@@ -25,6 +26,8 @@ pub(super) struct ProgramLowerer {
 
     associated_ty_lookups: AssociatedTyLookups,
     associated_ty_value_ids: AssociatedTyValueIds,
+    associated_const_lookups: AssociatedConstLookups,
+    associated_const_value_ids: AssociatedConstValueIds,
     adt_ids: AdtIds,
     fn_def_ids: FnDefIds,
     closure_ids: ClosureIds,
@@ -32,11 +35,13 @@ pub(super) struct ProgramLowerer {
     auto_traits: AutoTraits,
     opaque_ty_ids: OpaqueTyIds,
     adt_kinds: AdtKinds,
+    adt_defaults: AdtDefaults,
     fn_def_kinds: FnDefKinds,
     generator_ids: GeneratorIds,
     generator_kinds: GeneratorKinds,
     closure_kinds: ClosureKinds,
     trait_kinds: TraitKinds,
+    trait_defaults: TraitDefaults,
     opaque_ty_kinds: OpaqueTyVariableKinds,
     object_safe_traits: HashSet<TraitId<ChalkIr>>,
     foreign_ty_ids: ForeignIds,
@@ -86,6 +91,71 @@ impl ProgramLowerer {
         Ok(())
     }
 
+    /// Create ids for associated const declarations and values, and lower
+    /// the types of the declarations. This comes after `extract_ids`, as
+    /// the type of an associated const may refer to any other item.
+    pub fn extract_associated_consts(
+        &mut self,
+        program: &Program,
+        raw_ids: &Vec<RawId>,
+    ) -> LowerResult<()> {
+        let mut assoc_const_defns = vec![];
+        for (item, &raw_id) in program.items.iter().zip(raw_ids) {
+            match item {
+                Item::TraitDefn(d) => {
+                    for defn in &d.assoc_const_defns {
+                        let id = AssocConstId(self.next_item_id());
+                        assoc_const_defns.push((TraitId(raw_id), d, defn, id));
+                    }
+                }
+
+                Item::Impl(d) => {
+                    for acv in &d.assoc_const_values {
+                        let acv_id = AssociatedConstValueId(self.next_item_id());
+                        self.associated_const_value_ids
+                            .insert((ImplId(raw_id), acv.name.str.clone()), acv_id);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        let no_associated_consts = BTreeMap::new();
+        let empty_env = Env {
+            adt_ids: &self.adt_ids,
+            adt_kinds: &self.adt_kinds,
+            adt_defaults: &self.adt_defaults,
+            fn_def_ids: &self.fn_def_ids,
+            fn_def_kinds: &self.fn_def_kinds,
+            closure_ids: &self.closure_ids,
+            closure_kinds: &self.closure_kinds,
+            trait_ids: &self.trait_ids,
+            trait_kinds: &self.trait_kinds,
+            trait_defaults: &self.trait_defaults,
+            opaque_ty_ids: &self.opaque_ty_ids,
+            opaque_ty_kinds: &self.opaque_ty_kinds,
+            generator_ids: &self.generator_ids,
+            generator_kinds: &self.generator_kinds,
+            associated_ty_lookups: &self.associated_ty_lookups,
+            associated_const_lookups: &no_associated_consts,
+            parameter_map: BTreeMap::new(),
+            auto_traits: &self.auto_traits,
+            foreign_ty_ids: &self.foreign_ty_ids,
+        };
+
+        let mut associated_const_lookups = BTreeMap::new();
+        for (trait_id, trait_defn, defn, id) in assoc_const_defns {
+            let ty = empty_env.in_binders(trait_defn.all_parameters(), |env| defn.ty.lower(env))?;
+            associated_const_lookups.insert(
+                (trait_id, defn.name.str.clone()),
+                AssociatedConstLookup { id, ty },
+            );
+        }
+        self.associated_const_lookups = associated_const_lookups;
+        Ok(())
+    }
+
     pub fn extract_ids(&mut self, program: &Program, raw_ids: &Vec<RawId>) -> LowerResult<()> {
         for (item, &raw_id) in program.items.iter().zip(raw_ids) {
             match item {
@@ -140,6 +210,101 @@ impl ProgramLowerer {
         Ok(())
     }
 
+    /// Lower the defaults of the parameters of ADTs and traits. This comes
+    /// after `extract_ids`, as a default may refer to any other item. A
+    /// default may in turn omit defaulted parameters of other items, so we
+    /// lower the defaults of an item once those of the items it uses are
+    /// known, and report a cycle if that never happens.
+    pub fn extract_param_defaults(
+        &mut self,
+        program: &Program,
+        raw_ids: &Vec<RawId>,
+    ) -> LowerResult<()> {
+        let mut pending = vec![];
+        for (item, &raw_id) in program.items.iter().zip(raw_ids) {
+            match item {
+                Item::AdtDefn(defn) if defn.param_defaults.iter().all(Option::is_none) => {
+                    self.adt_defaults
+                        .insert(AdtId(raw_id), vec![None; defn.all_parameters().len()]);
+                }
+                Item::TraitDefn(defn) if defn.param_defaults.iter().all(Option::is_none) => {
+                    self.trait_defaults
+                        .insert(TraitId(raw_id), vec![None; defn.all_parameters().len()]);
+                }
+                Item::AdtDefn(_) | Item::TraitDefn(_) => pending.push((item, raw_id)),
+                _ => {}
+            }
+        }
+
+        while !pending.is_empty() {
+            let mut deferred = vec![];
+            let mut cycle = None;
+            for &(item, raw_id) in &pending {
+                let no_associated_consts = BTreeMap::new();
+                let empty_env = Env {
+                    adt_ids: &self.adt_ids,
+                    adt_kinds: &self.adt_kinds,
+                    adt_defaults: &self.adt_defaults,
+                    fn_def_ids: &self.fn_def_ids,
+                    fn_def_kinds: &self.fn_def_kinds,
+                    closure_ids: &self.closure_ids,
+                    closure_kinds: &self.closure_kinds,
+                    trait_ids: &self.trait_ids,
+                    trait_kinds: &self.trait_kinds,
+                    trait_defaults: &self.trait_defaults,
+                    opaque_ty_ids: &self.opaque_ty_ids,
+                    opaque_ty_kinds: &self.opaque_ty_kinds,
+                    generator_ids: &self.generator_ids,
+                    generator_kinds: &self.generator_kinds,
+                    associated_ty_lookups: &self.associated_ty_lookups,
+                    associated_const_lookups: &no_associated_consts,
+                    parameter_map: BTreeMap::new(),
+                    auto_traits: &self.auto_traits,
+                    foreign_ty_ids: &self.foreign_ty_ids,
+                };
+                let defaults = match item {
+                    Item::AdtDefn(defn) => lower_param_defaults(
+                        &empty_env,
+                        &defn.name,
+                        defn.all_parameters(),
+                        defn.param_defaults.iter().cloned(),
+                    ),
+                    Item::TraitDefn(defn) => lower_param_defaults(
+                        &empty_env,
+                        &defn.name,
+                        defn.all_parameters(),
+                        // `Self` has no default
+                        Some(None)
+                            .into_iter()
+                            .chain(defn.param_defaults.iter().cloned()),
+                    ),
+                    _ => unreachable!(),
+                };
+
+                match (item, defaults) {
+                    (Item::AdtDefn(_), Ok(defaults)) => {
+                        self.adt_defaults.insert(AdtId(raw_id), defaults);
+                    }
+                    (Item::TraitDefn(_), Ok(defaults)) => {
+                        self.trait_defaults.insert(TraitId(raw_id), defaults);
+                    }
+                    (_, Err(RustIrError::CyclicParameterDefault(name))) => {
+                        deferred.push((item, raw_id));
+                        cycle = Some(name);
+                    }
+                    (_, Err(e)) => return Err(e),
+                    _ => unreachable!(),
+                }
+            }
+
+            if deferred.len() == pending.len() {
+                return Err(RustIrError::CyclicParameterDefault(cycle.unwrap()));
+            }
+            pending = deferred;
+        }
+        Ok(())
+    }
+
     pub fn lower(self, program: &Program, raw_ids: &Vec<RawId>) -> LowerResult<LoweredProgram> {
         let mut adt_data = BTreeMap::new();
         let mut adt_reprs = BTreeMap::new();
@@ -151,9 +316,12 @@ impl ProgramLowerer {
         let mut closure_upvars = BTreeMap::new();
         let mut trait_data = BTreeMap::new();
         let mut well_known_traits = BTreeMap::new();
+        let mut well_known_adts = BTreeMap::new();
         let mut impl_data = BTreeMap::new();
         let mut associated_ty_data = BTreeMap::new();
         let mut associated_ty_values = BTreeMap::new();
+        let mut associated_const_data = BTreeMap::new();
+        let mut associated_const_values = BTreeMap::new();
         let mut opaque_ty_data = BTreeMap::new();
         let mut generator_data = BTreeMap::new();
         let mut generator_witness_data = BTreeMap::new();
@@ -164,17 +332,20 @@ impl ProgramLowerer {
             let empty_env = Env {
                 adt_ids: &self.adt_ids,
                 adt_kinds: &self.adt_kinds,
+                adt_defaults: &self.adt_defaults,
                 fn_def_ids: &self.fn_def_ids,
                 fn_def_kinds: &self.fn_def_kinds,
                 closure_ids: &self.closure_ids,
                 closure_kinds: &self.closure_kinds,
                 trait_ids: &self.trait_ids,
                 trait_kinds: &self.trait_kinds,
+                trait_defaults: &self.trait_defaults,
                 opaque_ty_ids: &self.opaque_ty_ids,
                 opaque_ty_kinds: &self.opaque_ty_kinds,
                 generator_ids: &self.generator_ids,
                 generator_kinds: &self.generator_kinds,
                 associated_ty_lookups: &self.associated_ty_lookups,
+                associated_const_lookups: &self.associated_const_lookups,
                 parameter_map: BTreeMap::new(),
                 auto_traits: &self.auto_traits,
                 foreign_ty_ids: &self.foreign_ty_ids,
@@ -184,6 +355,9 @@ impl ProgramLowerer {
                 Item::AdtDefn(ref d) => {
                     let identifier = d.name.clone();
                     let adt_id = AdtId(raw_id);
+                    if let Some(well_known) = d.well_known {
+                        well_known_adts.insert(well_known.lower(), adt_id);
+                    }
                     adt_data.insert(adt_id, Arc::new((d, adt_id).lower(&empty_env)?));
                     adt_reprs.insert(adt_id, Arc::new(d.repr.lower(&empty_env)?));
                     let n_params = d.all_parameters().len();
@@ -296,6 +470,11 @@ impl ProgramLowerer {
                             Ok(rust_ir::AssociatedTyDatumBound {
                                 bounds: assoc_ty_defn.bounds.lower(&env)?,
                                 where_clauses: assoc_ty_defn.where_clauses.lower(&env)?,
+                                default: assoc_ty_defn
+                                    .default
+                                    .as_ref()
+                                    .map(|ty| ty.lower(env))
+                                    .transpose()?,
                             })
                         })?;
 
@@ -309,11 +488,32 @@ impl ProgramLowerer {
                             }),
                         );
                     }
+
+                    for assoc_const_defn in &trait_defn.assoc_const_defns {
+                        let lookup = &self.associated_const_lookups
+                            [&(trait_id, assoc_const_defn.name.str.clone())];
+
+                        associated_const_data.insert(
+                            lookup.id,
+                            Arc::new(rust_ir::AssociatedConstDatum {
+                                trait_id,
+                                id: lookup.id,
+                                name: assoc_const_defn.name.str.clone(),
+                                ty: lookup.ty.clone(),
+                            }),
+                        );
+                    }
                 }
                 Item::Impl(ref impl_defn) => {
                     let impl_id = ImplId(raw_id);
                     let impl_datum = Arc::new(
-                        (impl_defn, impl_id, &self.associated_ty_value_ids).lower(&empty_env)?,
+                        (
+                            impl_defn,
+                            impl_id,
+                            &self.associated_ty_value_ids,
+                            &self.associated_const_value_ids,
+                        )
+                            .lower(&empty_env)?,
                     );
                     impl_data.insert(impl_id, impl_datum.clone());
                     let trait_id = impl_datum.trait_id();
@@ -342,6 +542,39 @@ impl ProgramLowerer {
                                 impl_id,
                                 associated_ty_id: lookup.id,
                                 value,
+                                default: atv.default,
+                            }),
+                        );
+                    }
+
+                    for acv in &impl_defn.assoc_const_values {
+                        let acv_id =
+                            self.associated_const_value_ids[&(impl_id, acv.name.str.clone())];
+                        let lookup = empty_env.lookup_associated_const(trait_id, &acv.name)?;
+
+                        // The declared type must agree with the type
+                        // declared in the trait (instantiated with the
+                        // parameters of the impl's trait reference), and the
+                        // value is then lowered at that type.
+                        let value = empty_env.in_binders(impl_defn.all_parameters(), |env| {
+                            let interner = env.interner();
+                            let ty = acv.ty.lower(env)?;
+                            let trait_ty = lookup.ty.clone().substitute(
+                                interner,
+                                &impl_datum.binders.skip_binders().trait_ref.substitution,
+                            );
+                            if trait_ty != ty {
+                                Err(RustIrError::IncorrectAssociatedConstType(acv.name.clone()))?;
+                            }
+                            (&acv.value, &ty).lower(env)
+                        })?;
+
+                        associated_const_values.insert(
+                            acv_id,
+                            Arc::new(rust_ir::AssociatedConstValue {
+                                impl_id,
+                                associated_const_id: lookup.id,
+                                value,
                             }),
                         );
                     }
@@ -453,7 +686,10 @@ impl ProgramLowerer {
                         Ok(GeneratorWitnessExistential { types: witnesses })
                     })?;
 
-                    let generator_datum = GeneratorDatum { input_output };
+                    let generator_datum = GeneratorDatum {
+                        movability: defn.movability.lower(),
+                        input_output,
+                    };
                     let generator_witness = GeneratorWitnessDatum { inner_types };
 
                     let id = self.generator_ids[&defn.name.str];
@@ -487,9 +723,12 @@ impl ProgramLowerer {
             generator_witness_data,
             trait_data,
             well_known_traits,
+            well_known_adts,
             impl_data,
             associated_ty_values,
             associated_ty_data,
+            associated_const_values,
+            associated_const_data,
             opaque_ty_ids: self.opaque_ty_ids,
             opaque_ty_kinds: self.opaque_ty_kinds,
             opaque_ty_data,
@@ -3,15 +3,17 @@ use crate::{tls, Identifier, TypeKind};
 use chalk_ir::{could_match::CouldMatch, UnificationDatabase};
 use chalk_ir::{debug::Angle, Variance};
 use chalk_ir::{
-    debug::SeparatorTraitRef, AdtId, AliasTy, AssocTypeId, Binders, CanonicalVarKinds, ClosureId,
-    FnDefId, ForeignDefId, GeneratorId, GenericArg, Goal, Goals, ImplId, IntTy, Lifetime, OpaqueTy,
-    OpaqueTyId, ProgramClause, ProgramClauseImplication, ProgramClauses, ProjectionTy, Scalar,
-    Substitution, TraitId, Ty, TyKind, UintTy, Variances,
+    debug::SeparatorTraitRef, AdtId, AliasTy, AssocConstId, AssocTypeId, Binders,
+    CanonicalVarKinds, ClosureId, FnDefId, ForeignDefId, GeneratorId, GenericArg, Goal, Goals,
+    ImplId, IntTy, Lifetime, OpaqueTy, OpaqueTyId, ProgramClause, ProgramClauseImplication,
+    ProgramClauses, ProjectionConst, ProjectionTy, Scalar, Substitution, TraitId, Ty, TyKind,
+    UintTy, Variances,
 };
 use chalk_solve::rust_ir::{
-    AdtDatum, AdtRepr, AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind,
-    FnDefDatum, FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum,
-    ImplType, OpaqueTyDatum, TraitDatum, WellKnownTrait,
+    AdtDatum, AdtRepr, AssociatedConstDatum, AssociatedConstValue, AssociatedConstValueId,
+    AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind, FnDefDatum,
+    FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum, ImplType,
+    OpaqueTyDatum, TraitDatum, WellKnownAdt, WellKnownTrait,
 };
 use chalk_solve::split::Split;
 use chalk_solve::RustIrDatabase;
@@ -94,9 +96,19 @@ pub struct Program {
     /// For each trait lang item
     pub well_known_traits: BTreeMap<WellKnownTrait, TraitId<ChalkIr>>,
 
+    /// For each ADT lang item
+    pub well_known_adts: BTreeMap<WellKnownAdt, AdtId<ChalkIr>>,
+
     /// For each associated ty declaration `type Foo` found in a trait:
     pub associated_ty_data: BTreeMap<AssocTypeId<ChalkIr>, Arc<AssociatedTyDatum<ChalkIr>>>,
 
+    /// For each associated const value `const N: T = XXX` found in an impl:
+    pub associated_const_values:
+        BTreeMap<AssociatedConstValueId<ChalkIr>, Arc<AssociatedConstValue<ChalkIr>>>,
+
+    /// For each associated const declaration `const N: T` found in a trait:
+    pub associated_const_data: BTreeMap<AssocConstId<ChalkIr>, Arc<AssociatedConstDatum<ChalkIr>>>,
+
     /// For each user-specified clause
     pub custom_clauses: Vec<ProgramClause<ChalkIr>>,
 
@@ -161,6 +173,20 @@ impl tls::DebugContext for Program {
         }
     }
 
+    fn debug_assoc_const_id(
+        &self,
+        assoc_const_id: AssocConstId<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        if let Some(d) = self.associated_const_data.get(&assoc_const_id) {
+            write!(fmt, "({:?}::{})", d.trait_id, d.name)
+        } else {
+            fmt.debug_struct("InvalidAssocConstId")
+                .field("index", &assoc_const_id.0)
+                .finish()
+        }
+    }
+
     fn debug_opaque_ty_id(
         &self,
         opaque_ty_id: OpaqueTyId<ChalkIr>,
@@ -217,6 +243,25 @@ impl tls::DebugContext for Program {
         )
     }
 
+    fn debug_projection_const(
+        &self,
+        projection_const: &ProjectionConst<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let interner = self.interner();
+        let associated_const_data =
+            &self.associated_const_data[&projection_const.associated_const_id];
+        let trait_params = projection_const.substitution.as_slice(interner);
+        write!(
+            fmt,
+            "<{:?} as {:?}{:?}>::{}",
+            &trait_params[0],
+            associated_const_data.trait_id,
+            Angle(&trait_params[1..]),
+            associated_const_data.name,
+        )
+    }
+
     fn debug_opaque_ty(
         &self,
         opaque_ty: &OpaqueTy<ChalkIr>,
@@ -403,6 +448,20 @@ impl RustIrDatabase<ChalkIr> for Program {
         self.associated_ty_values[&id].clone()
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        self.associated_const_data[&id].clone()
+    }
+
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        self.associated_const_values[&id].clone()
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<ChalkIr>) -> Arc<OpaqueTyDatum<ChalkIr>> {
         self.opaque_ty_data[&id].clone()
     }
@@ -520,6 +579,10 @@ impl RustIrDatabase<ChalkIr> for Program {
         self.well_known_traits.get(&well_known_trait).copied()
     }
 
+    fn well_known_adt_id(&self, well_known_adt: WellKnownAdt) -> Option<AdtId<ChalkIr>> {
+        self.well_known_adts.get(&well_known_adt).copied()
+    }
+
     fn program_clauses_for_env(
         &self,
         environment: &chalk_ir::Environment<ChalkIr>,
@@ -590,6 +653,14 @@ impl RustIrDatabase<ChalkIr> for Program {
             .to_string()
     }
 
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<ChalkIr>) -> String {
+        self.associated_const_data
+            .get(&assoc_const_id)
+            .unwrap()
+            .name
+            .to_string()
+    }
+
     // Mirrors current (07a63e6d1fabf3560e8e1e17c1d56b10a06152d9) implementation in rustc
     fn discriminant_type(&self, ty: Ty<ChalkIr>) -> Ty<ChalkIr> {
         let interner = self.interner();
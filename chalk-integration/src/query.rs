@@ -42,6 +42,13 @@ pub trait LoweringDatabase:
         &self,
     ) -> Result<BTreeMap<TraitId<ChalkIr>, Arc<SpecializationPriorities<ChalkIr>>>, ChalkError>;
 
+    /// Performs coherence check for a single trait and computes the
+    /// specialization priorities of its impls.
+    fn coherence_for_trait(
+        &self,
+        trait_id: TraitId<ChalkIr>,
+    ) -> Result<Arc<SpecializationPriorities<ChalkIr>>, ChalkError>;
+
     fn orphan_check(&self) -> Result<(), ChalkError>;
 
     /// The lowered IR, with coherence, orphan, and WF checks performed.
@@ -143,25 +150,32 @@ fn orphan_check(db: &dyn LoweringDatabase) -> Result<(), ChalkError> {
 fn coherence(
     db: &dyn LoweringDatabase,
 ) -> Result<BTreeMap<TraitId<ChalkIr>, Arc<SpecializationPriorities<ChalkIr>>>, ChalkError> {
+    let program = db.program_ir()?;
+    let priorities_map = program
+        .trait_data
+        .keys()
+        .map(|&trait_id| Ok((trait_id, db.coherence_for_trait(trait_id)?)))
+        .collect::<Result<BTreeMap<_, _>, ChalkError>>()?;
+    let () = db.orphan_check()?;
+    Ok(priorities_map)
+}
+
+fn coherence_for_trait(
+    db: &dyn LoweringDatabase,
+    trait_id: TraitId<ChalkIr>,
+) -> Result<Arc<SpecializationPriorities<ChalkIr>>, ChalkError> {
     let program = db.program_ir()?;
     let solver_choice = db.solver_choice();
-    let priorities_map = tls::set_current_program(&program, || -> Result<_, ChalkError> {
+    tls::set_current_program(&program, || -> Result<_, ChalkError> {
         let solver_builder = || solver_choice.into_solver();
-        let priorities_map: Result<BTreeMap<_, _>, ChalkError> = program
-            .trait_data
-            .keys()
-            .map(|&trait_id| {
-                let solver: CoherenceSolver<ChalkIr> =
-                    CoherenceSolver::new(db.upcast(), &solver_builder, trait_id);
-                let priorities = solver.specialization_priorities()?;
-                Ok((trait_id, priorities))
-            })
-            .collect();
-        let priorities_map = priorities_map?;
-        Ok(priorities_map)
-    });
-    let () = db.orphan_check()?;
-    priorities_map
+        // The priorities are computed by solving against the program itself
+        // rather than the database: the program gives no specialization
+        // priorities, while the database would ask this very query for them
+        // when generating the clauses of associated type values.
+        let solver: CoherenceSolver<ChalkIr> =
+            CoherenceSolver::new(&*program, &solver_builder, trait_id);
+        Ok(solver.specialization_priorities()?)
+    })
 }
 
 fn checked_program(db: &dyn LoweringDatabase) -> Result<Arc<Program>, ChalkError> {
@@ -177,6 +191,10 @@ fn checked_program(db: &dyn LoweringDatabase) -> Result<Arc<Program>, ChalkError
             solver.verify_adt_decl(id)?;
         }
 
+        for &trait_id in program.trait_data.keys() {
+            solver.verify_trait_decl(trait_id)?;
+        }
+
         for &opaque_ty_id in program.opaque_ty_data.keys() {
             solver.verify_opaque_ty_decl(opaque_ty_id)?;
         }
@@ -242,6 +260,11 @@ fn environment(db: &dyn LoweringDatabase) -> Result<Arc<ProgramEnvironment>, Cha
                 .iter()
                 .map(|&atv_id| db.associated_ty_value(atv_id))
                 .for_each(|atv| atv.to_program_clauses(builder, &env));
+            datum
+                .associated_const_value_ids
+                .iter()
+                .map(|&acv_id| db.associated_const_value(acv_id))
+                .for_each(|acv| acv.to_program_clauses(builder, &env));
         }
     }
 
@@ -1,9 +1,9 @@
 use crate::interner::ChalkIr;
 use chalk_ir::{
-    debug::SeparatorTraitRef, AdtId, AliasTy, AssocTypeId, CanonicalVarKinds, Constraints, FnDefId,
-    GenericArg, Goal, Goals, Lifetime, OpaqueTy, OpaqueTyId, ProgramClause,
-    ProgramClauseImplication, ProgramClauses, ProjectionTy, QuantifiedWhereClauses, Substitution,
-    TraitId, Ty, VariableKinds, Variances,
+    debug::SeparatorTraitRef, AdtId, AliasTy, AssocConstId, AssocTypeId, CanonicalVarKinds,
+    Constraints, FnDefId, GenericArg, Goal, Goals, Lifetime, OpaqueTy, OpaqueTyId, ProgramClause,
+    ProgramClauseImplication, ProgramClauses, ProjectionConst, ProjectionTy,
+    QuantifiedWhereClauses, Substitution, TraitId, Ty, VariableKinds, Variances,
 };
 use std::cell::RefCell;
 use std::fmt;
@@ -32,6 +32,12 @@ pub trait DebugContext {
         fmt: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error>;
 
+    fn debug_assoc_const_id(
+        &self,
+        id: AssocConstId<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error>;
+
     fn debug_opaque_ty_id(
         &self,
         id: OpaqueTyId<ChalkIr>,
@@ -62,6 +68,12 @@ pub trait DebugContext {
         fmt: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error>;
 
+    fn debug_projection_const(
+        &self,
+        proj: &ProjectionConst<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error>;
+
     fn debug_ty(&self, ty: &Ty<ChalkIr>, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error>;
 
     fn debug_lifetime(
@@ -168,9 +180,11 @@ where
 {
     let p: Arc<dyn DebugContext> = p.clone();
     PROGRAM.with(|prog_cell| {
-        *prog_cell.borrow_mut() = Some(p);
+        // Queries may run while a program is already set, so restore the
+        // previous one afterwards rather than clearing it.
+        let old = prog_cell.borrow_mut().replace(p);
         let r = op();
-        *prog_cell.borrow_mut() = None;
+        *prog_cell.borrow_mut() = old;
         r
     })
 }
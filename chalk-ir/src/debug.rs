@@ -23,6 +23,13 @@ impl<I: Interner> Debug for AssocTypeId<I> {
     }
 }
 
+impl<I: Interner> Debug for AssocConstId<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        I::debug_assoc_const_id(*self, fmt)
+            .unwrap_or_else(|| write!(fmt, "AssocConstId({:?})", self.0))
+    }
+}
+
 impl<I: Interner> Debug for FnDefId<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
         I::debug_fn_def_id(*self, fmt).unwrap_or_else(|| write!(fmt, "FnDefId({:?})", self.0))
@@ -144,6 +151,18 @@ impl<I: Interner> Debug for ProjectionTy<I> {
     }
 }
 
+impl<I: Interner> Debug for ProjectionConst<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        I::debug_projection_const(self, fmt).unwrap_or_else(|| {
+            write!(
+                fmt,
+                "({:?}){:?}",
+                self.associated_const_id, self.substitution
+            )
+        })
+    }
+}
+
 impl<I: Interner> Debug for OpaqueTy<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         I::debug_opaque_ty(self, fmt).unwrap_or_else(|| {
@@ -359,10 +378,24 @@ impl<I: Interner> Debug for ConstData<I> {
             ConstValue::InferenceVar(var) => write!(fmt, "{:?}", var),
             ConstValue::Placeholder(index) => write!(fmt, "{:?}", index),
             ConstValue::Concrete(evaluated) => write!(fmt, "{:?}", evaluated),
+            ConstValue::Projection(projection) => write!(fmt, "{:?}", projection),
+            ConstValue::Expr(expr) => write!(fmt, "{:?}", expr),
         }
     }
 }
 
+impl<I: Interner> Debug for ConstExpr<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            fmt,
+            "{{{:?} {} {:?}}}",
+            self.lhs,
+            self.op.as_str(),
+            self.rhs
+        )
+    }
+}
+
 impl<I: Interner> Debug for GoalData<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         match self {
@@ -766,6 +799,12 @@ impl<I: Interner> Debug for Normalize<I> {
     }
 }
 
+impl<I: Interner> Debug for NormalizeConst<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(fmt, "NormalizeConst({:?} -> {:?})", self.alias, self.value)
+    }
+}
+
 impl<I: Interner> Debug for AliasEq<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         write!(fmt, "AliasEq({:?} = {:?})", self.alias, self.ty)
@@ -776,6 +815,9 @@ impl<I: Interner> Debug for WhereClause<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         match self {
             WhereClause::Implemented(tr) => write!(fmt, "Implemented({:?})", tr.with_colon()),
+            WhereClause::NotImplemented(tr) => {
+                write!(fmt, "NotImplemented({:?})", tr.with_colon())
+            }
             WhereClause::AliasEq(a) => write!(fmt, "{:?}", a),
             WhereClause::LifetimeOutlives(l_o) => write!(fmt, "{:?}", l_o),
             WhereClause::TypeOutlives(t_o) => write!(fmt, "{:?}", t_o),
@@ -808,6 +850,7 @@ impl<I: Interner> Debug for DomainGoal<I> {
             DomainGoal::WellFormed(n) => write!(fmt, "{:?}", n),
             DomainGoal::FromEnv(n) => write!(fmt, "{:?}", n),
             DomainGoal::Normalize(n) => write!(fmt, "{:?}", n),
+            DomainGoal::NormalizeConst(n) => write!(fmt, "{:?}", n),
             DomainGoal::IsLocal(n) => write!(fmt, "IsLocal({:?})", n),
             DomainGoal::IsUpstream(n) => write!(fmt, "IsUpstream({:?})", n),
             DomainGoal::IsFullyVisible(n) => write!(fmt, "IsFullyVisible({:?})", n),
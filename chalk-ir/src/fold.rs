@@ -583,6 +583,16 @@ where
                 }),
             }
             .intern(folder.interner())),
+            ConstValue::Projection(projection) => Ok(ConstData {
+                ty: fold_ty()?,
+                value: ConstValue::Projection(projection.clone().fold_with(folder, outer_binder)?),
+            }
+            .intern(folder.interner())),
+            ConstValue::Expr(expr) => Ok(ConstData {
+                ty: fold_ty()?,
+                value: ConstValue::Expr(expr.clone().fold_with(folder, outer_binder)?),
+            }
+            .intern(folder.interner())),
         }
     }
 }
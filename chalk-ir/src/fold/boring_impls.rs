@@ -224,6 +224,7 @@ copy_fold!(Scalar);
 copy_fold!(ClausePriority);
 copy_fold!(Mutability);
 copy_fold!(Safety);
+copy_fold!(ConstBinOp);
 
 #[doc(hidden)]
 #[macro_export]
@@ -249,6 +250,7 @@ id_fold!(ImplId);
 id_fold!(AdtId);
 id_fold!(TraitId);
 id_fold!(AssocTypeId);
+id_fold!(AssocConstId);
 id_fold!(OpaqueTyId);
 id_fold!(FnDefId);
 id_fold!(ClosureId);
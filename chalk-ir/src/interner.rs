@@ -1,6 +1,7 @@
 //! Encapsulates the concrete representation of core types such as types and goals.
 use crate::AdtId;
 use crate::AliasTy;
+use crate::AssocConstId;
 use crate::AssocTypeId;
 use crate::CanonicalVarKind;
 use crate::CanonicalVarKinds;
@@ -24,6 +25,7 @@ use crate::ProgramClause;
 use crate::ProgramClauseData;
 use crate::ProgramClauseImplication;
 use crate::ProgramClauses;
+use crate::ProjectionConst;
 use crate::ProjectionTy;
 use crate::QuantifiedWhereClause;
 use crate::QuantifiedWhereClauses;
@@ -37,6 +39,7 @@ use crate::VariableKinds;
 use crate::Variance;
 use crate::Variances;
 use crate::{Const, ConstData};
+use crate::{ConstBinOp, ConstEvalError};
 use std::fmt::{self, Debug};
 use std::hash::Hash;
 use std::marker::PhantomData;
@@ -227,6 +230,16 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
         None
     }
 
+    /// Prints the debug representation of an associated-const-id.
+    /// Returns `None` to fallback to the default debug output.
+    #[allow(unused_variables)]
+    fn debug_assoc_const_id(
+        const_id: AssocConstId<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        None
+    }
+
     /// Prints the debug representation of an opaque type.
     /// Returns `None` to fallback to the default debug output.
     #[allow(unused_variables)]
@@ -299,6 +312,16 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
         None
     }
 
+    /// Prints the debug representation of a ProjectionConst.
+    /// Returns `None` to fallback to the default debug output.
+    #[allow(unused_variables)]
+    fn debug_projection_const(
+        projection_const: &ProjectionConst<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        None
+    }
+
     /// Prints the debug representation of an OpaqueTy.
     /// Returns `None` to fallback to the default debug output.
     #[allow(unused_variables)]
@@ -501,6 +524,21 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
         c2: &Self::InternedConcreteConst,
     ) -> bool;
 
+    /// Evaluates `lhs op rhs` for two concrete const values of type `ty`.
+    /// Returns `ConstEvalError::Undefined` if the result is not defined
+    /// (e.g. on overflow or division by zero). By default consts are not
+    /// evaluated, and expressions are left unevaluated.
+    #[allow(unused_variables)]
+    fn const_eval_bin_op(
+        &self,
+        op: ConstBinOp,
+        ty: &Self::InternedType,
+        lhs: &Self::InternedConcreteConst,
+        rhs: &Self::InternedConcreteConst,
+    ) -> Result<Self::InternedConcreteConst, ConstEvalError> {
+        Err(ConstEvalError::Unsupported)
+    }
+
     /// Create an "interned" parameter from `data`. This is not
     /// normally invoked directly; instead, you invoke
     /// `GenericArgData::intern` (which will ultimately call this
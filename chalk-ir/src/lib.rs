@@ -384,6 +384,13 @@ pub struct ClauseId<I: Interner>(pub I::DefId);
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct AssocTypeId<I: Interner>(pub I::DefId);
 
+/// The id for the associated const member of a trait. The details of the
+/// const can be found by invoking the [`associated_const_data`] method.
+///
+/// [`associated_const_data`]: ../chalk_solve/trait.RustIrDatabase.html#tymethod.associated_const_data
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssocConstId<I: Interner>(pub I::DefId);
+
 /// Id for an opaque type.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OpaqueTyId<I: Interner>(pub I::DefId);
@@ -449,19 +456,32 @@ fn compute_generic_arg_flags<I: Interner>(generic_arg: &GenericArg<I>, interner:
     match generic_arg.data(&interner) {
         GenericArgData::Ty(ty) => ty.data(interner).flags,
         GenericArgData::Lifetime(lifetime) => compute_lifetime_flags(lifetime, interner),
-        GenericArgData::Const(constant) => {
-            let data = constant.data(&interner);
-            let flags = data.ty.data(interner).flags;
-            match data.value {
-                ConstValue::BoundVar(_) => flags,
-                ConstValue::InferenceVar(_) => {
-                    flags | TypeFlags::HAS_CT_INFER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
-                }
-                ConstValue::Placeholder(_) => {
-                    flags | TypeFlags::HAS_CT_PLACEHOLDER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
-                }
-                ConstValue::Concrete(_) => flags,
-            }
+        GenericArgData::Const(constant) => compute_const_flags(constant, interner),
+    }
+}
+
+/// Compute type flags for a const
+fn compute_const_flags<I: Interner>(constant: &Const<I>, interner: &I) -> TypeFlags {
+    let data = constant.data(&interner);
+    let flags = data.ty.data(interner).flags;
+    match data.value {
+        ConstValue::BoundVar(_) => flags,
+        ConstValue::InferenceVar(_) => {
+            flags | TypeFlags::HAS_CT_INFER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
+        }
+        ConstValue::Placeholder(_) => {
+            flags | TypeFlags::HAS_CT_PLACEHOLDER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
+        }
+        ConstValue::Concrete(_) => flags,
+        ConstValue::Projection(ref projection) => {
+            flags
+                | TypeFlags::HAS_CT_PROJECTION
+                | compute_substitution_flags(&projection.substitution, interner)
+        }
+        ConstValue::Expr(ref expr) => {
+            flags
+                | compute_const_flags(&expr.lhs, interner)
+                | compute_const_flags(&expr.rhs, interner)
         }
     }
 }
@@ -498,19 +518,7 @@ fn compute_flags<I: Interner>(kind: &TyKind<I>, interner: &I) -> TypeFlags {
             compute_lifetime_flags(lifetime, interner) | ty.data(interner).flags
         }
         TyKind::Array(ty, const_ty) => {
-            let flags = ty.data(interner).flags;
-            let const_data = const_ty.data(interner);
-            flags
-                | const_data.ty.data(interner).flags
-                | match const_data.value {
-                    ConstValue::BoundVar(_) | ConstValue::Concrete(_) => TypeFlags::empty(),
-                    ConstValue::InferenceVar(_) => {
-                        TypeFlags::HAS_CT_INFER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
-                    }
-                    ConstValue::Placeholder(_) => {
-                        TypeFlags::HAS_CT_PLACEHOLDER | TypeFlags::STILL_FURTHER_SPECIALIZABLE
-                    }
-                }
+            ty.data(interner).flags | compute_const_flags(const_ty, interner)
         }
         TyKind::Placeholder(_) => TypeFlags::HAS_TY_PLACEHOLDER,
         TyKind::Dyn(dyn_ty) => {
@@ -518,7 +526,8 @@ fn compute_flags<I: Interner>(kind: &TyKind<I>, interner: &I) -> TypeFlags {
             let mut dyn_flags = TypeFlags::empty();
             for var_kind in dyn_ty.bounds.value.iter(&interner) {
                 match &(var_kind.value) {
-                    WhereClause::Implemented(trait_ref) => {
+                    WhereClause::Implemented(trait_ref)
+                    | WhereClause::NotImplemented(trait_ref) => {
                         dyn_flags |= compute_substitution_flags(&(trait_ref.substitution), interner)
                     }
                     WhereClause::AliasEq(alias_eq) => {
@@ -1253,6 +1262,21 @@ impl<I: Interner> Const<I> {
             ConstValue::InferenceVar(_) => false,
             ConstValue::Placeholder(_) => false,
             ConstValue::Concrete(_) => false,
+            ConstValue::Projection(_) => false,
+            ConstValue::Expr(_) => false,
+        }
+    }
+
+    /// Evaluates this const if it is a closed expression, i.e. one built
+    /// only from concrete values.
+    pub fn evaluate(&self, interner: &I) -> Result<ConcreteConst<I>, ConstEvalError> {
+        let data = self.data(interner);
+        match &data.value {
+            ConstValue::Concrete(c) => Ok(c.clone()),
+            ConstValue::Expr(expr) => {
+                expr.evaluate_with(interner, &data.ty, |operand| operand.evaluate(interner))
+            }
+            _ => Err(ConstEvalError::TooGeneric),
         }
     }
 }
@@ -1277,9 +1301,96 @@ pub enum ConstValue<I: Interner> {
     Placeholder(PlaceholderIndex),
     /// Concrete constant value.
     Concrete(ConcreteConst<I>),
+    /// A projection of an associated const, e.g. `<T as Trait>::N`, whose
+    /// value is found by normalizing it (see [`NormalizeConst`]).
+    Projection(ProjectionConst<I>),
+    /// An unevaluated const expression, e.g. `{ N + 1 }`.
+    Expr(ConstExpr<I>),
+}
+
+impl<I: Interner> Copy for ConstValue<I>
+where
+    I::InternedConcreteConst: Copy,
+    I::InternedSubstitution: Copy,
+    I::InternedConst: Copy,
+{
+}
+
+/// A binary operation on two consts of the same type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConstBinOp {
+    /// Addition, `+`.
+    Add,
+    /// Subtraction, `-`.
+    Sub,
+    /// Multiplication, `*`.
+    Mul,
+    /// Division, `/`.
+    Div,
+    /// Remainder, `%`.
+    Rem,
+}
+
+/// The reason a const expression could not be evaluated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConstEvalError {
+    /// Some operand is not known yet, e.g. because it is a parameter, an
+    /// unresolved inference variable or an unnormalized projection.
+    TooGeneric,
+    /// The interner does not evaluate const expressions.
+    Unsupported,
+    /// The result is not defined, e.g. because the operation overflows or
+    /// divides by zero.
+    Undefined,
+}
+
+impl ConstBinOp {
+    /// The operator as it is written in Rust source.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConstBinOp::Add => "+",
+            ConstBinOp::Sub => "-",
+            ConstBinOp::Mul => "*",
+            ConstBinOp::Div => "/",
+            ConstBinOp::Rem => "%",
+        }
+    }
+}
+
+/// An unevaluated const expression `lhs op rhs`. The operands have the same
+/// type as the expression itself.
+///
+/// Expressions are compared structurally; one whose operands are all
+/// concrete can be evaluated with [`Const::evaluate`], and one that cannot
+/// be evaluated for its operands (e.g. `{ 255u8 + 1 }`) equals nothing.
+#[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, HasInterner, Zip)]
+pub struct ConstExpr<I: Interner> {
+    /// The operation applied.
+    pub op: ConstBinOp,
+    /// The left-hand operand.
+    pub lhs: Const<I>,
+    /// The right-hand operand.
+    pub rhs: Const<I>,
 }
 
-impl<I: Interner> Copy for ConstValue<I> where I::InternedConcreteConst: Copy {}
+impl<I: Interner> Copy for ConstExpr<I> where I::InternedConst: Copy {}
+
+impl<I: Interner> ConstExpr<I> {
+    /// Evaluates this expression, which has type `ty`, using
+    /// `evaluate_operand` to find the values of its operands.
+    pub fn evaluate_with(
+        &self,
+        interner: &I,
+        ty: &Ty<I>,
+        mut evaluate_operand: impl FnMut(&Const<I>) -> Result<ConcreteConst<I>, ConstEvalError>,
+    ) -> Result<ConcreteConst<I>, ConstEvalError> {
+        let lhs = evaluate_operand(&self.lhs)?;
+        let rhs = evaluate_operand(&self.rhs)?;
+        interner
+            .const_eval_bin_op(self.op, &ty.interned, &lhs.interned, &rhs.interned)
+            .map(|interned| ConcreteConst { interned })
+    }
+}
 
 impl<I: Interner> ConstData<I> {
     /// Wraps the constant data in a `Const`.
@@ -1682,6 +1793,28 @@ impl<I: Interner> ProjectionTy<I> {
     }
 }
 
+/// A projection of an associated const `<P0 as TraitName<P1..Pn>>::CONST`.
+#[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, HasInterner)]
+pub struct ProjectionConst<I: Interner> {
+    /// The id for the associated const member.
+    pub associated_const_id: AssocConstId<I>,
+    /// The substitution for the projection.
+    pub substitution: Substitution<I>,
+}
+
+impl<I: Interner> Copy for ProjectionConst<I> where I::InternedSubstitution: Copy {}
+
+impl<I: Interner> ProjectionConst<I> {
+    /// Gets the type parameters of the `Self` type in this projection.
+    pub fn self_type_parameter(&self, interner: &I) -> Ty<I> {
+        self.substitution
+            .iter(interner)
+            .find_map(move |p| p.ty(interner))
+            .unwrap()
+            .clone()
+    }
+}
+
 /// An opaque type `opaque type T<..>: Trait = HiddenTy`.
 #[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, HasInterner)]
 pub struct OpaqueTy<I: Interner> {
@@ -1767,6 +1900,10 @@ where
 pub enum WhereClause<I: Interner> {
     /// Type implements a trait.
     Implemented(TraitRef<I>),
+    /// Type is declared, by a negative impl, to not implement a trait
+    /// (`T: !Trait`). This is not the negation of `Implemented`: it only
+    /// holds if some negative impl applies.
+    NotImplemented(TraitRef<I>),
     /// Type is equal to an alias.
     AliasEq(AliasEq<I>),
     /// One lifetime outlives another.
@@ -1875,6 +2012,10 @@ pub enum DomainGoal<I: Interner> {
     /// True if the alias type can be normalized to some other type
     Normalize(Normalize<I>),
 
+    /// True if the associated const projection can be normalized to some
+    /// other const
+    NormalizeConst(NormalizeConst<I>),
+
     /// True if a type is considered to have been "defined" by the current crate. This is true for
     /// a `struct Foo { }` but false for a `#[upstream] struct Foo { }`. However, for fundamental types
     /// like `Box<T>`, it is true if `T` is local.
@@ -1938,6 +2079,7 @@ where
     I::InternedSubstitution: Copy,
     I::InternedLifetime: Copy,
     I::InternedType: Copy,
+    I::InternedConst: Copy,
 {
 }
 
@@ -1964,10 +2106,13 @@ impl<I: Interner> WhereClause<I> {
         }
     }
 
-    /// If where clause is a `TraitRef`, returns its trait id.
+    /// If where clause is a `TraitRef`, returns its trait id. Negative
+    /// bounds are not considered, as they imply nothing about the trait's
+    /// where clauses.
     pub fn trait_id(&self) -> Option<TraitId<I>> {
         match self {
             WhereClause::Implemented(trait_ref) => Some(trait_ref.trait_id),
+            WhereClause::NotImplemented(_) => None,
             WhereClause::AliasEq(_) => None,
             WhereClause::LifetimeOutlives(_) => None,
             WhereClause::TypeOutlives(_) => None,
@@ -2057,6 +2202,24 @@ where
 {
 }
 
+/// Proves that the given associated const projection **normalizes** to the
+/// given const. A projection `<T as Trait>::N` normalizes to the const `C`
+/// if we can **match it to an impl** and that impl has a `const N: .. = V`
+/// where `C = V`.
+#[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, Zip)]
+#[allow(missing_docs)]
+pub struct NormalizeConst<I: Interner> {
+    pub alias: ProjectionConst<I>,
+    pub value: Const<I>,
+}
+
+impl<I: Interner> Copy for NormalizeConst<I>
+where
+    I::InternedSubstitution: Copy,
+    I::InternedConst: Copy,
+{
+}
+
 /// Proves **equality** between an alias and a type.
 #[derive(Clone, PartialEq, Eq, Hash, Fold, Visit, Zip)]
 #[allow(missing_docs)]
@@ -2716,6 +2879,7 @@ where
     I::InternedGoals: Copy,
     I::InternedProgramClauses: Copy,
     I::InternedVariableKinds: Copy,
+    I::InternedConst: Copy,
 {
 }
 
@@ -321,6 +321,7 @@ macro_rules! eq_zip {
 eq_zip!(I => AdtId<I>);
 eq_zip!(I => TraitId<I>);
 eq_zip!(I => AssocTypeId<I>);
+eq_zip!(I => AssocConstId<I>);
 eq_zip!(I => OpaqueTyId<I>);
 eq_zip!(I => GeneratorId<I>);
 eq_zip!(I => ForeignDefId<I>);
@@ -331,6 +332,7 @@ eq_zip!(I => PhantomData<I>);
 eq_zip!(I => PlaceholderIndex);
 eq_zip!(I => ClausePriority);
 eq_zip!(I => Mutability);
+eq_zip!(I => ConstBinOp);
 eq_zip!(I => Scalar);
 
 impl<T: HasInterner<Interner = I> + Zip<I>, I: Interner> Zip<I> for InEnvironment<T> {
@@ -549,6 +551,32 @@ impl<I: Interner> Zip<I> for ProjectionTy<I> {
     }
 }
 
+impl<I: Interner> Zip<I> for ProjectionConst<I> {
+    fn zip_with<'i, Z: Zipper<'i, I>>(
+        zipper: &mut Z,
+        variance: Variance,
+        a: &Self,
+        b: &Self,
+    ) -> Fallible<()>
+    where
+        I: 'i,
+    {
+        let interner = zipper.interner();
+        Zip::zip_with(
+            zipper,
+            variance,
+            &a.associated_const_id,
+            &b.associated_const_id,
+        )?;
+        zipper.zip_substs(
+            variance,
+            None,
+            a.substitution.as_slice(interner),
+            b.substitution.as_slice(interner),
+        )
+    }
+}
+
 impl<I: Interner> Zip<I> for OpaqueTy<I> {
     fn zip_with<'i, Z: Zipper<'i, I>>(
         zipper: &mut Z,
@@ -38,11 +38,15 @@ pub struct ForeignDefn(pub Identifier);
 pub struct AdtDefn {
     pub name: Identifier,
     pub variable_kinds: Vec<VariableKind>,
+    /// The default of each parameter in `variable_kinds`, e.g. `u32` in
+    /// `struct Foo<T = u32>`.
+    pub param_defaults: Vec<Option<GenericArg>>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub variants: Vec<Variant>,
     pub flags: AdtFlags,
     pub repr: AdtRepr,
     pub variances: Option<Vec<Variance>>,
+    pub well_known: Option<WellKnownAdt>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -54,6 +58,7 @@ pub struct Variant {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct GeneratorDefn {
     pub name: Identifier,
+    pub movability: Movability,
     pub variable_kinds: Vec<VariableKind>,
     pub upvars: Vec<Ty>,
     pub resume_ty: Ty,
@@ -63,6 +68,12 @@ pub struct GeneratorDefn {
     pub witness_lifetimes: Vec<Identifier>,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct AdtFlags {
     pub upstream: bool,
@@ -133,12 +144,38 @@ impl Default for FnAbi {
 pub struct TraitDefn {
     pub name: Identifier,
     pub variable_kinds: Vec<VariableKind>,
+    /// The default of each parameter in `variable_kinds`, e.g. `Self` in
+    /// `trait Add<Rhs = Self>`.
+    pub param_defaults: Vec<Option<GenericArg>>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub assoc_ty_defns: Vec<AssocTyDefn>,
+    pub assoc_const_defns: Vec<AssocConstDefn>,
+    /// The bounds on `Self` of a trait alias, e.g. `Foo + Bar` in
+    /// `trait Alias = Foo + Bar;`. Always empty for other traits.
+    pub bounds: Vec<QuantifiedInlineBound>,
     pub flags: TraitFlags,
     pub well_known: Option<WellKnownTrait>,
 }
 
+/// What follows the parameters of a trait declaration: either the where
+/// clauses and associated types of a trait, or the bounds and where clauses
+/// of a trait alias.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraitBody {
+    pub bounds: Vec<QuantifiedInlineBound>,
+    pub where_clauses: Vec<QuantifiedWhereClause>,
+    pub assoc_ty_defns: Vec<AssocTyDefn>,
+    pub assoc_const_defns: Vec<AssocConstDefn>,
+    pub alias: bool,
+}
+
+/// An item declared in the body of a trait.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TraitItem {
+    AssocTyDefn(AssocTyDefn),
+    AssocConstDefn(AssocConstDefn),
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WellKnownTrait {
     Sized,
@@ -152,6 +189,15 @@ pub enum WellKnownTrait {
     Unpin,
     CoerceUnsized,
     DiscriminantKind,
+    DispatchFromDyn,
+    Deref,
+    Pointee,
+    Generator,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WellKnownAdt {
+    DynMetadata,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -163,6 +209,7 @@ pub struct TraitFlags {
     pub non_enumerable: bool,
     pub coinductive: bool,
     pub object_safe: bool,
+    pub alias: bool,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -171,6 +218,14 @@ pub struct AssocTyDefn {
     pub variable_kinds: Vec<VariableKind>,
     pub bounds: Vec<QuantifiedInlineBound>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
+    /// The default value, e.g. `Self` in `type Output = Self;`.
+    pub default: Option<Ty>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssocConstDefn {
+    pub name: Identifier,
+    pub ty: Ty,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -188,7 +243,8 @@ pub enum VariableKind {
     IntegerTy(Identifier),
     FloatTy(Identifier),
     Lifetime(Identifier),
-    Const(Identifier),
+    /// A const parameter along with its type, e.g. `const N: usize`.
+    Const(Identifier, ScalarType),
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -202,7 +258,44 @@ pub enum GenericArg {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Const {
     Id(Identifier),
-    Value(u32),
+    Value(ConstValue),
+    /// An associated const, e.g. `<T as Trait>::N`.
+    Projection(ProjectionTy),
+    /// An unevaluated const expression, e.g. `{ N + 1 }`.
+    Expr {
+        op: ConstBinOp,
+        lhs: Box<Const>,
+        rhs: Box<Const>,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ConstBinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// A literal const value, e.g. `3`, `-1`, `true` or `'a'`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ConstValue {
+    Bool(bool),
+    Char(char),
+    Int { negative: bool, value: u128 },
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConstValue::Bool(b) => write!(f, "{}", b),
+            ConstValue::Char(c) => write!(f, "{:?}", c),
+            ConstValue::Int { negative, value } => {
+                write!(f, "{}{}", if negative { "-" } else { "" }, value)
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -260,9 +353,17 @@ pub struct Impl {
     pub polarity: Polarity,
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub assoc_ty_values: Vec<AssocTyValue>,
+    pub assoc_const_values: Vec<AssocConstValue>,
     pub impl_type: ImplType,
 }
 
+/// An item defined in the body of an impl.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ImplItem {
+    AssocTyValue(AssocTyValue),
+    AssocConstValue(AssocConstValue),
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum ImplType {
     Local,
@@ -277,6 +378,13 @@ pub struct AssocTyValue {
     pub default: bool,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssocConstValue {
+    pub name: Identifier,
+    pub ty: Ty,
+    pub value: Const,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Ty {
     Id {
@@ -432,6 +540,7 @@ impl fmt::Display for Identifier {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum WhereClause {
     Implemented { trait_ref: TraitRef },
+    NotImplemented { trait_ref: TraitRef },
     ProjectionEq { projection: ProjectionTy, ty: Ty },
     LifetimeOutlives { a: Lifetime, b: Lifetime },
     TypeOutlives { ty: Ty, lifetime: Lifetime },
@@ -439,20 +548,49 @@ pub enum WhereClause {
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum DomainGoal {
-    Holds { where_clause: WhereClause },
-    Normalize { projection: ProjectionTy, ty: Ty },
-    TraitRefWellFormed { trait_ref: TraitRef },
-    TyWellFormed { ty: Ty },
-    TyFromEnv { ty: Ty },
-    TraitRefFromEnv { trait_ref: TraitRef },
-    IsLocal { ty: Ty },
-    IsUpstream { ty: Ty },
-    IsFullyVisible { ty: Ty },
-    LocalImplAllowed { trait_ref: TraitRef },
+    Holds {
+        where_clause: WhereClause,
+    },
+    Normalize {
+        projection: ProjectionTy,
+        ty: Ty,
+    },
+    NormalizeConst {
+        projection: ProjectionTy,
+        value: Const,
+    },
+    TraitRefWellFormed {
+        trait_ref: TraitRef,
+    },
+    TyWellFormed {
+        ty: Ty,
+    },
+    TyFromEnv {
+        ty: Ty,
+    },
+    TraitRefFromEnv {
+        trait_ref: TraitRef,
+    },
+    IsLocal {
+        ty: Ty,
+    },
+    IsUpstream {
+        ty: Ty,
+    },
+    IsFullyVisible {
+        ty: Ty,
+    },
+    LocalImplAllowed {
+        trait_ref: TraitRef,
+    },
     Compatible,
-    DownstreamType { ty: Ty },
+    DownstreamType {
+        ty: Ty,
+    },
     Reveal,
-    ObjectSafe { id: Identifier },
+    ObjectSafe {
+        id: Identifier,
+    },
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
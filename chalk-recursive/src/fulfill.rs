@@ -1,5 +1,6 @@
+use crate::proof::{self, ProofEdge, ProofNode};
 use crate::solve::SolveDatabase;
-use crate::{Guidance, Minimums, Solution};
+use crate::{Guidance, Minimums, Solution, UCanonicalGoal};
 use chalk_ir::cast::Cast;
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::{HasInterner, Interner};
@@ -12,22 +13,18 @@ use chalk_ir::{
     UnificationDatabase, UniverseMap, Variance,
 };
 use chalk_solve::debug_span;
+use chalk_solve::proof::ProofStep;
+use chalk_solve::AmbiguityCause;
 use rustc_hash::FxHashSet;
 use std::fmt::Debug;
+use std::mem;
 use tracing::{debug, instrument};
 
-enum Outcome {
+enum Outcome<I: Interner> {
     Complete,
-    Incomplete,
-}
 
-impl Outcome {
-    fn is_complete(&self) -> bool {
-        match *self {
-            Outcome::Complete => true,
-            _ => false,
-        }
-    }
+    /// Some obligations remain ambiguous, for the given reason.
+    Incomplete(AmbiguityCause<I>),
 }
 
 /// A goal that must be resolved
@@ -53,10 +50,10 @@ struct PositiveSolution<I: Interner> {
 }
 
 /// When refuting a goal, there's no impact on inference state.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum NegativeSolution {
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum NegativeSolution<I: Interner> {
     Refuted,
-    Ambiguous,
+    Ambiguous(AmbiguityCause<I>),
 }
 
 pub(super) trait RecursiveInferenceTable<I: Interner> {
@@ -151,9 +148,14 @@ pub(super) struct Fulfill<
     constraints: FxHashSet<InEnvironment<Constraint<I>>>,
 
     /// Record that a goal has been processed that can neither be proved nor
-    /// refuted. In such a case the solution will be either `CannotProve`, or `Err`
-    /// in the case where some other goal leads to an error.
-    cannot_prove: bool,
+    /// refuted, and why. In such a case the solution will be either
+    /// `CannotProve`, or `Err` in the case where some other goal leads to an
+    /// error.
+    cannot_prove: Option<AmbiguityCause<I>>,
+
+    /// The derivations of the subgoals processed so far, if the solver is
+    /// recording proofs.
+    proof_subgoals: Vec<ProofEdge<I>>,
 }
 
 impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I>>
@@ -173,7 +175,8 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
             subst,
             obligations: vec![],
             constraints: FxHashSet::default(),
-            cannot_prove: false,
+            cannot_prove: None,
+            proof_subgoals: vec![],
         };
 
         let ProgramClauseImplication {
@@ -223,7 +226,8 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
             subst,
             obligations: vec![],
             constraints: FxHashSet::default(),
-            cannot_prove: false,
+            cannot_prove: None,
+            proof_subgoals: vec![],
         };
 
         if let Err(e) = fulfill.push_goal(&canonical_goal.environment, canonical_goal.goal.clone())
@@ -243,7 +247,13 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
                     .needs_truncation(self.solver.interner(), self.solver.max_size(), goal)
                 {
                     // the goal is too big. Record that we should return Ambiguous
-                    self.cannot_prove = true;
+                    self.mark_cannot_prove(AmbiguityCause::Truncation);
+                    self.solver.stats().truncations += 1;
+                    self.record_ambiguous_leaf(
+                        goal.clone(),
+                        ProofStep::Truncated,
+                        AmbiguityCause::Truncation,
+                    );
                     return;
                 }
             }
@@ -253,7 +263,15 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
                     .needs_truncation(self.solver.interner(), self.solver.max_size(), goal)
                 {
                     // the goal is too big. Record that we should return Ambiguous
-                    self.cannot_prove = true;
+                    self.mark_cannot_prove(AmbiguityCause::Truncation);
+                    self.solver.stats().truncations += 1;
+                    let interner = self.solver.interner();
+                    let negated = goal.clone().map(|g| GoalData::Not(g).intern(interner));
+                    self.record_ambiguous_leaf(
+                        negated,
+                        ProofStep::Truncated,
+                        AmbiguityCause::Truncation,
+                    );
                     return;
                 }
             }
@@ -261,6 +279,33 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
         self.obligations.push(obligation);
     }
 
+    /// Records that a goal can neither be proved nor refuted, because of
+    /// `cause`.
+    fn mark_cannot_prove(&mut self, cause: AmbiguityCause<I>) {
+        self.cannot_prove = Some(match self.cannot_prove.take() {
+            Some(previous) => previous.and(cause),
+            None => cause,
+        });
+    }
+
+    /// If proofs are being recorded, records `goal` as an ambiguous subgoal
+    /// that was not solved any further.
+    fn record_ambiguous_leaf(
+        &mut self,
+        goal: InEnvironment<Goal<I>>,
+        step: ProofStep<I>,
+        cause: AmbiguityCause<I>,
+    ) {
+        if !self.solver.recording_proofs() {
+            return;
+        }
+        let interner = self.solver.interner();
+        let (canonical, _) = self.infer.canonicalize(interner, goal);
+        let (goal, _) = self.infer.u_canonicalize(interner, &canonical);
+        let node = ProofNode::leaf(Ok(Solution::Ambig(Guidance::Unknown, cause)), step);
+        self.proof_subgoals.push(ProofEdge::Inline(goal, node));
+    }
+
     /// Unifies `a` and `b` in the given environment.
     ///
     /// Wraps `InferenceTable::unify`; any resulting normalizations are added
@@ -348,14 +393,19 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
                     b.kind(interner),
                     TyKind::InferenceVar(_, TyVariableKind::General)
                 ) {
-                    self.cannot_prove = true;
+                    self.mark_cannot_prove(AmbiguityCause::Ambiguity);
                 } else {
                     self.unify(&environment, Variance::Covariant, &a, &b)?;
                 }
             }
             GoalData::CannotProve => {
-                debug!("Pushed a CannotProve goal, setting cannot_prove = true");
-                self.cannot_prove = true;
+                debug!("Pushed a CannotProve goal, setting cannot_prove");
+                self.mark_cannot_prove(AmbiguityCause::Ambiguity);
+                self.record_ambiguous_leaf(
+                    InEnvironment::new(environment, goal.clone()),
+                    ProofStep::CannotProve,
+                    AmbiguityCause::Ambiguity,
+                );
             }
         }
         Ok(())
@@ -370,6 +420,10 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
         let interner = self.solver.interner();
         let (quantified, free_vars) = self.infer.canonicalize(interner, wc);
         let (quantified, universes) = self.infer.u_canonicalize(interner, &quantified);
+        if self.solver.recording_proofs() {
+            self.proof_subgoals
+                .push(ProofEdge::Goal(quantified.clone()));
+        }
         let result = self.solver.solve_goal(quantified, minimums);
         Ok(PositiveSolution {
             free_vars,
@@ -378,16 +432,31 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
         })
     }
 
-    fn refute(&mut self, goal: InEnvironment<Goal<I>>) -> Fallible<NegativeSolution> {
+    fn refute(&mut self, goal: InEnvironment<Goal<I>>) -> Fallible<NegativeSolution<I>> {
+        let negated = if self.solver.recording_proofs() {
+            let interner = self.solver.interner();
+            let negated = goal.clone().map(|g| GoalData::Not(g).intern(interner));
+            let (negated, _) = self.infer.canonicalize(interner, negated);
+            Some(self.infer.u_canonicalize(interner, &negated).0)
+        } else {
+            None
+        };
         let canonicalized = match self
             .infer
-            .invert_then_canonicalize(self.solver.interner(), goal)
+            .invert_then_canonicalize(self.solver.interner(), goal.clone())
         {
             Some(v) => v,
             None => {
                 // Treat non-ground negatives as ambiguous. Note that, as inference
                 // proceeds, we may wind up with more information here.
-                return Ok(NegativeSolution::Ambiguous);
+                let interner = self.solver.interner();
+                let negated = goal.map(|g| GoalData::Not(g).intern(interner));
+                self.record_ambiguous_leaf(
+                    negated,
+                    ProofStep::Floundered,
+                    AmbiguityCause::Floundered,
+                );
+                return Ok(NegativeSolution::Ambiguous(AmbiguityCause::Floundered));
             }
         };
 
@@ -396,15 +465,43 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
             .infer
             .u_canonicalize(self.solver.interner(), &canonicalized);
         let mut minimums = Minimums::new(); // FIXME -- minimums here seems wrong
-        if let Ok(solution) = self.solver.solve_goal(quantified, &mut minimums) {
-            if solution.is_unique() {
-                Err(NoSolution)
-            } else {
-                Ok(NegativeSolution::Ambiguous)
-            }
-        } else {
-            Ok(NegativeSolution::Refuted)
+        let result = match self.solver.solve_goal(quantified.clone(), &mut minimums) {
+            Ok(solution) => match solution {
+                Solution::Unique(_) => Err(NoSolution),
+                Solution::Ambig(_, cause) => Ok(NegativeSolution::Ambiguous(cause)),
+            },
+            Err(_) => Ok(NegativeSolution::Refuted),
+        };
+
+        if let Some(negated) = negated {
+            self.record_negation(negated, quantified, &result);
         }
+        result
+    }
+
+    /// Records the proof of the `negated` goal, i.e. the refutation of
+    /// `goal` (which is the inverted form of the goal that `negated` negates)
+    /// with the given `result`.
+    fn record_negation(
+        &mut self,
+        negated: UCanonicalGoal<I>,
+        goal: UCanonicalGoal<I>,
+        result: &Fallible<NegativeSolution<I>>,
+    ) {
+        let interner = self.solver.interner();
+        let solution = match result {
+            Ok(NegativeSolution::Refuted) => Ok(proof::trivial_solution(interner, &negated)),
+            Ok(NegativeSolution::Ambiguous(cause)) => {
+                Ok(Solution::Ambig(Guidance::Unknown, cause.clone()))
+            }
+            Err(e) => Err(*e),
+        };
+        let node = ProofNode {
+            solution,
+            step: ProofStep::Negation,
+            subgoals: vec![ProofEdge::Goal(goal)],
+        };
+        self.proof_subgoals.push(ProofEdge::Inline(negated, node));
     }
 
     /// Trying to prove some goal led to a the substitution `subst`; we
@@ -451,7 +548,7 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
         }
     }
 
-    fn fulfill(&mut self, minimums: &mut Minimums) -> Fallible<Outcome> {
+    fn fulfill(&mut self, minimums: &mut Minimums) -> Fallible<Outcome<I>> {
         debug_span!("fulfill", obligations=?self.obligations);
 
         // Try to solve all the obligations. We do this via a fixed-point
@@ -462,9 +559,19 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
         let mut obligations = Vec::with_capacity(self.obligations.len());
         let mut progress = true;
 
+        // The derivations of the obligations that were ambiguous in the
+        // current round. These are retried in the next round, so we only keep
+        // the derivations from the last round.
+        let mut ambiguous_proofs = vec![];
+
+        // Why the obligations that were ambiguous in the current round are.
+        let mut ambiguity: Option<AmbiguityCause<I>> = None;
+
         while progress {
             progress = false;
             debug!("start of round, {} obligations", self.obligations.len());
+            ambiguous_proofs.clear();
+            ambiguity = None;
 
             // Take the list of `obligations` to solve this round and replace it
             // with an empty vector. Iterate through each obligation to solve
@@ -474,7 +581,8 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
             // directly.
             assert!(obligations.is_empty());
             while let Some(obligation) = self.obligations.pop() {
-                let ambiguous = match &obligation {
+                let proofs_len = self.proof_subgoals.len();
+                let ambiguity_cause = match &obligation {
                     Obligation::Prove(wc) => {
                         let PositiveSolution {
                             free_vars,
@@ -499,17 +607,24 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
                             }
                         }
 
-                        solution.is_ambig()
-                    }
-                    Obligation::Refute(goal) => {
-                        let answer = self.refute(goal.clone())?;
-                        answer == NegativeSolution::Ambiguous
+                        solution.ambiguity_cause().cloned()
                     }
+                    Obligation::Refute(goal) => match self.refute(goal.clone())? {
+                        NegativeSolution::Refuted => None,
+                        NegativeSolution::Ambiguous(cause) => Some(cause),
+                    },
                 };
 
-                if ambiguous {
+                if let Some(cause) = ambiguity_cause {
                     debug!("ambiguous result: {:?}", obligation);
+                    ambiguity = Some(match ambiguity.take() {
+                        Some(previous) => previous.and(cause),
+                        None => cause,
+                    });
                     obligations.push(obligation);
+                    if self.proof_subgoals.len() > proofs_len {
+                        ambiguous_proofs.push(self.proof_subgoals.remove(proofs_len));
+                    }
                 }
             }
 
@@ -521,42 +636,62 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
         // all of the ambiguous obligations, and `obligations` should
         // be empty.
         assert!(obligations.is_empty());
+        self.proof_subgoals.extend(ambiguous_proofs);
 
-        if self.obligations.is_empty() {
-            Ok(Outcome::Complete)
-        } else {
-            Ok(Outcome::Incomplete)
+        match ambiguity {
+            None => Ok(Outcome::Complete),
+            Some(cause) => Ok(Outcome::Incomplete(cause)),
         }
     }
 
     /// Try to fulfill all pending obligations and build the resulting
     /// solution. The returned solution will transform `subst` substitution with
     /// the outcome of type inference by updating the replacements it provides.
-    pub(super) fn solve(mut self, minimums: &mut Minimums) -> Fallible<Solution<I>> {
-        let outcome = match self.fulfill(minimums) {
-            Ok(o) => o,
-            Err(e) => return Err(e),
-        };
+    ///
+    /// If the solver is recording proofs, this also returns the derivations of
+    /// the subgoals that were processed.
+    pub(super) fn solve(
+        mut self,
+        minimums: &mut Minimums,
+    ) -> (Fallible<Solution<I>>, Vec<ProofEdge<I>>) {
+        let outcome = self.fulfill(minimums);
+        let proof_subgoals = mem::take(&mut self.proof_subgoals);
+        let solution = outcome.and_then(|outcome| self.into_solution(outcome, minimums));
+        (solution, proof_subgoals)
+    }
 
-        if self.cannot_prove {
-            debug!("Goal cannot be proven (cannot_prove = true), returning ambiguous");
-            return Ok(Solution::Ambig(Guidance::Unknown));
+    /// Packages up the result of `fulfill` as a solution.
+    fn into_solution(
+        mut self,
+        outcome: Outcome<I>,
+        minimums: &mut Minimums,
+    ) -> Fallible<Solution<I>> {
+        if let Some(cause) = self.cannot_prove.take() {
+            debug!("Goal cannot be proven (cannot_prove is set), returning ambiguous");
+            let cause = match outcome {
+                Outcome::Complete => cause,
+                Outcome::Incomplete(other) => cause.and(other),
+            };
+            return Ok(Solution::Ambig(Guidance::Unknown, cause));
         }
 
-        if outcome.is_complete() {
-            // No obligations remain, so we have definitively solved our goals,
-            // and the current inference state is the unique way to solve them.
-
-            let constraints = Constraints::from_iter(self.interner(), self.constraints.clone());
-            let constrained = self.infer.canonicalize(
-                self.solver.interner(),
-                ConstrainedSubst {
-                    subst: self.subst,
-                    constraints,
-                },
-            );
-            return Ok(Solution::Unique(constrained.0));
-        }
+        let cause = match outcome {
+            Outcome::Complete => {
+                // No obligations remain, so we have definitively solved our goals,
+                // and the current inference state is the unique way to solve them.
+
+                let constraints = Constraints::from_iter(self.interner(), self.constraints.clone());
+                let constrained = self.infer.canonicalize(
+                    self.solver.interner(),
+                    ConstrainedSubst {
+                        subst: self.subst,
+                        constraints,
+                    },
+                );
+                return Ok(Solution::Unique(constrained.0));
+            }
+            Outcome::Incomplete(cause) => cause,
+        };
 
         // Otherwise, we have (positive or negative) obligations remaining, but
         // haven't proved that it's *impossible* to satisfy out obligations. we
@@ -590,12 +725,15 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
                         solution.constrained_subst(self.solver.interner())
                     {
                         self.apply_solution(free_vars, universes, constrained_subst);
-                        return Ok(Solution::Ambig(Guidance::Suggested(canonical_subst.0)));
+                        return Ok(Solution::Ambig(
+                            Guidance::Suggested(canonical_subst.0),
+                            cause,
+                        ));
                     }
                 }
             }
 
-            Ok(Solution::Ambig(Guidance::Unknown))
+            Ok(Solution::Ambig(Guidance::Unknown, cause))
         } else {
             // While we failed to prove the goal, we still learned that
             // something had to hold. Here's an example where this happens:
@@ -617,7 +755,10 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>, Infer: RecursiveInferenceTable<I
             // for sure what `T` must be (it could be either `Foo<Bar>` or
             // `Foo<Baz>`, but we *can* say for sure that it must be of the
             // form `Foo<?0>`.
-            Ok(Solution::Ambig(Guidance::Definite(canonical_subst.0)))
+            Ok(Solution::Ambig(
+                Guidance::Definite(canonical_subst.0),
+                cause,
+            ))
         }
     }
 
@@ -3,6 +3,7 @@ use chalk_ir::interner::Interner;
 use chalk_ir::{
     Canonical, ConstrainedSubst, Constraints, Goal, InEnvironment, Substitution, UCanonical,
 };
+use chalk_solve::AmbiguityCause;
 use std::fmt;
 use tracing::debug;
 
@@ -10,6 +11,7 @@ pub type UCanonicalGoal<I> = UCanonical<InEnvironment<Goal<I>>>;
 
 mod combine;
 mod fulfill;
+mod proof;
 mod recursive;
 mod search_graph;
 pub mod solve;
@@ -47,8 +49,9 @@ pub enum Solution<I: Interner> {
     /// The goal may be provable in multiple ways, but regardless we may have some guidance
     /// for type inference. In this case, we don't return any lifetime
     /// constraints, since we have not "committed" to any particular solution
-    /// yet.
-    Ambig(Guidance<I>),
+    /// yet. The cause says whether the goal is really ambiguous, or whether
+    /// the solver gave up on it.
+    Ambig(Guidance<I>, AmbiguityCause<I>),
 }
 
 /// When a goal holds ambiguously (e.g., because there are multiple possible
@@ -103,8 +106,14 @@ impl<I: Interner> Solution<I> {
             other.display(interner)
         );
 
-        // Otherwise, always downgrade to Ambig:
+        // Otherwise, always downgrade to Ambig. Two different unique solutions
+        // are really ambiguous; otherwise, the ambiguous ones decide why.
 
+        let cause = match (self.ambiguity_cause(), other.ambiguity_cause()) {
+            (Some(cause1), Some(cause2)) => cause1.clone().or(cause2.clone()),
+            (Some(cause), None) | (None, Some(cause)) => cause.clone(),
+            (None, None) => AmbiguityCause::Ambiguity,
+        };
         let guidance = match (self.into_guidance(), other.into_guidance()) {
             (Definite(ref subst1), Definite(ref subst2)) if subst1 == subst2 => {
                 Definite(subst1.clone())
@@ -114,7 +123,43 @@ impl<I: Interner> Solution<I> {
             }
             _ => Unknown,
         };
-        Solution::Ambig(guidance)
+        Solution::Ambig(guidance, cause)
+    }
+
+    /// Converts this solution into the solution type shared by all solvers.
+    pub(crate) fn into_solve_solution(self) -> chalk_solve::Solution<I> {
+        match self {
+            Solution::Unique(c) => chalk_solve::Solution::Unique(c),
+            Solution::Ambig(g, cause) => chalk_solve::Solution::Ambig(
+                match g {
+                    Guidance::Definite(g) => chalk_solve::Guidance::Definite(g),
+                    Guidance::Suggested(g) => chalk_solve::Guidance::Suggested(g),
+                    Guidance::Unknown => chalk_solve::Guidance::Unknown,
+                },
+                cause,
+            ),
+        }
+    }
+
+    /// Converts this solution to `goal` into a single answer of
+    /// `Solver::solve_multiple`. Ambiguous solutions without any guidance
+    /// are reported as ambiguous with the identity substitution.
+    pub(crate) fn into_substitution_result(
+        self,
+        interner: &I,
+        goal: &UCanonicalGoal<I>,
+    ) -> chalk_solve::SubstitutionResult<Canonical<ConstrainedSubst<I>>> {
+        match self.constrained_subst(interner) {
+            Some(subst) if self.is_unique() => chalk_solve::SubstitutionResult::Definite(subst),
+            Some(subst) => chalk_solve::SubstitutionResult::Ambiguous(subst),
+            None => chalk_solve::SubstitutionResult::Ambiguous(Canonical {
+                value: ConstrainedSubst {
+                    subst: goal.trivial_substitution(interner),
+                    constraints: Constraints::empty(interner),
+                },
+                binders: goal.canonical.binders.clone(),
+            }),
+        }
     }
 
     /// View this solution purely in terms of type inference guidance
@@ -124,7 +169,15 @@ impl<I: Interner> Solution<I> {
                 value: constrained.value.subst,
                 binders: constrained.binders,
             }),
-            Solution::Ambig(guidance) => guidance,
+            Solution::Ambig(guidance, _) => guidance,
+        }
+    }
+
+    /// Why this solution is ambiguous, if it is.
+    pub(crate) fn ambiguity_cause(&self) -> Option<&AmbiguityCause<I>> {
+        match self {
+            Solution::Unique(_) => None,
+            Solution::Ambig(_, cause) => Some(cause),
         }
     }
 
@@ -132,8 +185,8 @@ impl<I: Interner> Solution<I> {
     pub(crate) fn constrained_subst(&self, interner: &I) -> Option<Canonical<ConstrainedSubst<I>>> {
         match *self {
             Solution::Unique(ref constrained) => Some(constrained.clone()),
-            Solution::Ambig(Guidance::Definite(ref canonical))
-            | Solution::Ambig(Guidance::Suggested(ref canonical)) => {
+            Solution::Ambig(Guidance::Definite(ref canonical), _)
+            | Solution::Ambig(Guidance::Suggested(ref canonical), _) => {
                 let value = ConstrainedSubst {
                     subst: canonical.value.clone(),
                     constraints: Constraints::empty(interner),
@@ -143,7 +196,7 @@ impl<I: Interner> Solution<I> {
                     binders: canonical.binders.clone(),
                 })
             }
-            Solution::Ambig(_) => None,
+            Solution::Ambig(..) => None,
         }
     }
 
@@ -152,7 +205,7 @@ impl<I: Interner> Solution<I> {
     pub(crate) fn definite_subst(&self, interner: &I) -> Option<Canonical<ConstrainedSubst<I>>> {
         match self {
             Solution::Unique(constrained) => Some(constrained.clone()),
-            Solution::Ambig(Guidance::Definite(canonical)) => {
+            Solution::Ambig(Guidance::Definite(canonical), _) => {
                 let value = ConstrainedSubst {
                     subst: canonical.value.clone(),
                     constraints: Constraints::empty(interner),
@@ -175,7 +228,7 @@ impl<I: Interner> Solution<I> {
 
     pub(crate) fn is_ambig(&self) -> bool {
         match *self {
-            Solution::Ambig(_) => true,
+            Solution::Ambig(..) => true,
             _ => false,
         }
     }
@@ -198,17 +251,19 @@ impl<'a, I: Interner> fmt::Display for SolutionDisplay<'a, I> {
         let SolutionDisplay { solution, interner } = self;
         match solution {
             Solution::Unique(constrained) => write!(f, "Unique; {}", constrained.display(interner)),
-            Solution::Ambig(Guidance::Definite(subst)) => write!(
+            Solution::Ambig(Guidance::Definite(subst), _) => write!(
                 f,
                 "Ambiguous; definite substitution {}",
                 subst.display(interner)
             ),
-            Solution::Ambig(Guidance::Suggested(subst)) => write!(
+            Solution::Ambig(Guidance::Suggested(subst), _) => write!(
                 f,
                 "Ambiguous; suggested substitution {}",
                 subst.display(interner)
             ),
-            Solution::Ambig(Guidance::Unknown) => write!(f, "Ambiguous; no inference guidance"),
+            Solution::Ambig(Guidance::Unknown, _) => {
+                write!(f, "Ambiguous; no inference guidance")
+            }
         }
     }
 }
@@ -0,0 +1,122 @@
+use crate::{Solution, UCanonicalGoal};
+use chalk_ir::interner::Interner;
+use chalk_ir::{Canonical, ConstrainedSubst, Constraints, Fallible};
+use chalk_solve::proof::{ProofStep, ProofTree};
+use rustc_hash::FxHashMap;
+
+/// The derivation recorded for a goal while solving. Subgoals that were
+/// themselves solved through `solve_goal` are only referenced by their goal;
+/// their derivations are looked up when the final `ProofTree` is assembled.
+#[derive(Clone, Debug)]
+pub(crate) struct ProofNode<I: Interner> {
+    pub(crate) solution: Fallible<Solution<I>>,
+    pub(crate) step: ProofStep<I>,
+    pub(crate) subgoals: Vec<ProofEdge<I>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum ProofEdge<I: Interner> {
+    /// A subgoal that was solved through `solve_goal`.
+    Goal(UCanonicalGoal<I>),
+
+    /// A subgoal whose derivation was recorded in place, e.g. a negative or
+    /// truncated goal.
+    Inline(UCanonicalGoal<I>, ProofNode<I>),
+
+    /// Another derivation of the goal of the enclosing node; see
+    /// `ProofStep::Alternatives`.
+    Alternative(ProofNode<I>),
+}
+
+impl<I: Interner> ProofNode<I> {
+    pub(crate) fn leaf(solution: Fallible<Solution<I>>, step: ProofStep<I>) -> Self {
+        ProofNode {
+            solution,
+            step,
+            subgoals: vec![],
+        }
+    }
+}
+
+/// The solution of a goal that holds without constraining any of its
+/// variables.
+pub(crate) fn trivial_solution<I: Interner>(interner: &I, goal: &UCanonicalGoal<I>) -> Solution<I> {
+    Solution::Unique(Canonical {
+        value: ConstrainedSubst {
+            subst: goal.trivial_substitution(interner),
+            constraints: Constraints::empty(interner),
+        },
+        binders: goal.canonical.binders.clone(),
+    })
+}
+
+/// Builds the `ProofTree` for `goal` out of the derivations recorded in
+/// `proofs`. Goals that are already being expanded further up the tree are
+/// not expanded again, but show up as `ProofStep::Cycle` leaves, and goals
+/// whose derivation was not recorded show up as `ProofStep::Cached` leaves,
+/// with their solution from `cache`.
+pub(crate) fn assemble<I: Interner>(
+    proofs: &FxHashMap<UCanonicalGoal<I>, ProofNode<I>>,
+    cache: &FxHashMap<UCanonicalGoal<I>, Fallible<Solution<I>>>,
+    goal: &UCanonicalGoal<I>,
+) -> ProofTree<I> {
+    assemble_goal(proofs, cache, goal, &mut vec![])
+}
+
+fn assemble_goal<I: Interner>(
+    proofs: &FxHashMap<UCanonicalGoal<I>, ProofNode<I>>,
+    cache: &FxHashMap<UCanonicalGoal<I>, Fallible<Solution<I>>>,
+    goal: &UCanonicalGoal<I>,
+    stack: &mut Vec<UCanonicalGoal<I>>,
+) -> ProofTree<I> {
+    let node = proofs.get(goal);
+    if stack.contains(goal) {
+        leaf(goal, node.map(|node| &node.solution), ProofStep::Cycle)
+    } else if let Some(node) = node {
+        assemble_node(proofs, cache, goal, node, stack)
+    } else {
+        leaf(goal, cache.get(goal), ProofStep::Cached)
+    }
+}
+
+fn assemble_node<I: Interner>(
+    proofs: &FxHashMap<UCanonicalGoal<I>, ProofNode<I>>,
+    cache: &FxHashMap<UCanonicalGoal<I>, Fallible<Solution<I>>>,
+    goal: &UCanonicalGoal<I>,
+    node: &ProofNode<I>,
+    stack: &mut Vec<UCanonicalGoal<I>>,
+) -> ProofTree<I> {
+    stack.push(goal.clone());
+    let subgoals = node
+        .subgoals
+        .iter()
+        .map(|edge| match edge {
+            ProofEdge::Goal(subgoal) => assemble_goal(proofs, cache, subgoal, stack),
+            ProofEdge::Inline(subgoal, subnode) => {
+                assemble_node(proofs, cache, subgoal, subnode, stack)
+            }
+            ProofEdge::Alternative(subnode) => assemble_node(proofs, cache, goal, subnode, stack),
+        })
+        .collect();
+    stack.pop();
+
+    ProofTree {
+        subgoals,
+        ..leaf(goal, Some(&node.solution), node.step.clone())
+    }
+}
+
+fn leaf<I: Interner>(
+    goal: &UCanonicalGoal<I>,
+    solution: Option<&Fallible<Solution<I>>>,
+    step: ProofStep<I>,
+) -> ProofTree<I> {
+    ProofTree {
+        goal: goal.clone(),
+        solution: solution
+            .and_then(|solution| solution.clone().ok())
+            .map(Solution::into_solve_solution),
+        step,
+        subgoals: vec![],
+    }
+}
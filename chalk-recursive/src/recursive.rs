@@ -1,14 +1,22 @@
+use crate::proof::{self, ProofNode};
 use crate::search_graph::DepthFirstNumber;
 use crate::search_graph::SearchGraph;
 use crate::solve::{SolveDatabase, SolveIteration};
 use crate::stack::{Stack, StackDepth};
 use crate::{combine, Guidance, Minimums, Solution, UCanonicalGoal};
 use chalk_ir::interner::Interner;
-use chalk_ir::Fallible;
-use chalk_ir::{Canonical, ConstrainedSubst, Constraints, Goal, InEnvironment, UCanonical};
-use chalk_solve::{coinductive_goal::IsCoinductive, RustIrDatabase};
+use chalk_ir::{
+    Canonical, ClausePriority, ConstrainedSubst, Fallible, Floundered, Goal, GoalData,
+    InEnvironment, UCanonical,
+};
+use chalk_solve::proof::{ProofStep, ProofTree};
+use chalk_solve::{
+    coinductive_goal::IsCoinductive, AmbiguityCause, FuelExhausted, RustIrDatabase, SolverStats,
+    SubstitutionResult,
+};
 use rustc_hash::FxHashMap;
 use std::fmt;
+use std::time::Instant;
 use tracing::debug;
 use tracing::{info, instrument};
 
@@ -28,6 +36,20 @@ struct RecursiveContext<I: Interner> {
     max_size: usize,
 
     caching_enabled: bool,
+
+    /// The derivations of the goals we have solved, if we are recording
+    /// proofs (see `RecursiveSolver::solve_with_proof`).
+    proofs: Option<FxHashMap<UCanonicalGoal<I>, ProofNode<I>>>,
+
+    /// Statistics about the current (or most recent) query.
+    stats: SolverStats,
+
+    /// The number of solve iterations the current query may still take, if
+    /// it was given a fuel budget.
+    fuel: Option<usize>,
+
+    /// Set once the current query runs out of fuel.
+    fuel_exhausted: bool,
 }
 
 /// A Solver is the basic context in which you can propose goals for a given
@@ -54,6 +76,37 @@ impl<I: Interner> RecursiveSolver<I> {
             )),
         }
     }
+
+    /// Solves the given goal like `Solver::solve`, and additionally returns
+    /// a proof tree recording how the solution was reached (or, if the goal
+    /// cannot be proven, which clauses were tried).
+    ///
+    /// The first call puts the solver into proof-recording mode: from then
+    /// on, the derivation of every goal the solver works on is kept. Goals
+    /// whose results were cached before that are solved again (and cached
+    /// anew) the next time they come up, so that their derivation is
+    /// recorded as well.
+    pub fn solve_with_proof(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> (Option<chalk_solve::Solution<I>>, ProofTree<I>) {
+        if self.ctx.proofs.is_none() {
+            self.ctx.proofs = Some(FxHashMap::default());
+        }
+        let solution = self.measure(|ctx| ctx.solver(program).solve_root_goal(goal).ok());
+        let proof = proof::assemble(self.ctx.proofs.as_ref().unwrap(), &self.ctx.cache, goal);
+        (solution.map(Solution::into_solve_solution), proof)
+    }
+
+    /// Runs `query`, collecting statistics about it from scratch.
+    fn measure<R>(&mut self, query: impl FnOnce(&mut RecursiveContext<I>) -> R) -> R {
+        self.ctx.stats = SolverStats::default();
+        let start = Instant::now();
+        let result = query(&mut self.ctx);
+        self.ctx.stats.wall_time = start.elapsed();
+        result
+    }
 }
 
 impl<I: Interner> fmt::Debug for RecursiveSolver<I> {
@@ -90,6 +143,10 @@ impl<I: Interner> RecursiveContext<I> {
             cache: FxHashMap::default(),
             max_size,
             caching_enabled,
+            proofs: None,
+            stats: SolverStats::default(),
+            fuel: None,
+            fuel_exhausted: false,
         }
     }
 
@@ -130,6 +187,88 @@ impl<'me, I: Interner> Solver<'me, I> {
         self.solve_goal(canonical_goal.clone(), minimums)
     }
 
+    /// Solves a canonical goal like `solve_root_goal`, but gives one answer
+    /// for each program clause that proves it (if it is a domain goal),
+    /// leaving out duplicates. Other goals get a single answer.
+    pub(crate) fn solve_root_goal_per_clause(
+        &mut self,
+        canonical_goal: &UCanonicalGoal<I>,
+    ) -> Vec<SubstitutionResult<Canonical<ConstrainedSubst<I>>>> {
+        debug!(
+            "solve_root_goal_per_clause(canonical_goal={:?})",
+            canonical_goal
+        );
+        assert!(self.context.stack.is_empty());
+        let interner = self.program.interner();
+        let solutions = match canonical_goal.canonical.value.goal.data(interner) {
+            GoalData::DomainGoal(domain_goal) => {
+                let canonical_domain_goal = UCanonical {
+                    universes: canonical_goal.universes,
+                    canonical: Canonical {
+                        binders: canonical_goal.canonical.binders.clone(),
+                        value: InEnvironment::new(
+                            &canonical_goal.canonical.value.environment,
+                            domain_goal.clone(),
+                        ),
+                    },
+                };
+                // Solve the goal as a whole first, so that subgoals that
+                // cycle back to it see its final solution, like they would
+                // in `solve_root_goal`.
+                let solution = match self.solve_root_goal(canonical_goal) {
+                    Ok(solution) => solution,
+                    Err(_) => return vec![],
+                };
+
+                // Then solve it once per clause, with the goal on the stack
+                // and in the search graph like any other goal.
+                let coinductive_goal = canonical_goal.is_coinductive(self.program);
+                let depth = self.context.stack.push(coinductive_goal);
+                let dfn = self.context.search_graph.insert(canonical_goal, depth);
+                self.context.search_graph[dfn].solution = Ok(solution.clone());
+                let solutions = if self.consume_fuel() {
+                    let minimums = &mut Minimums::new();
+                    self.solve_per_clause(&canonical_domain_goal, minimums)
+                } else {
+                    Ok(vec![solution])
+                };
+                self.context.stack.pop(depth);
+                self.context.search_graph.rollback_to(dfn);
+                match solutions {
+                    Ok(solutions) => solutions,
+                    Err(Floundered) => return vec![SubstitutionResult::Floundered],
+                }
+            }
+            _ => self.solve_root_goal(canonical_goal).into_iter().collect(),
+        };
+        let mut answers = vec![];
+        for solution in solutions {
+            if !answers.contains(&solution) {
+                answers.push(solution);
+            }
+        }
+        answers
+            .into_iter()
+            .map(|solution| solution.into_substitution_result(interner, canonical_goal))
+            .collect()
+    }
+
+    /// Takes one unit of fuel, if the current query is limited. Returns
+    /// `false` (and remembers it) once the fuel has run out.
+    fn consume_fuel(&mut self) -> bool {
+        match &mut self.context.fuel {
+            Some(0) => {
+                self.context.fuel_exhausted = true;
+                false
+            }
+            Some(fuel) => {
+                *fuel -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+
     #[instrument(level = "debug", skip(self))]
     fn solve_new_subgoal(
         &mut self,
@@ -146,7 +285,18 @@ impl<'me, I: Interner> Solver<'me, I> {
         // - None < Some(CannotProve)
         // the function which maps the loop iteration to `answer` is a nondecreasing function
         // so this function will eventually be constant and the loop terminates.
-        loop {
+        let minimums = loop {
+            // Once out of fuel, give up on the goal (and every goal after
+            // it) with an ambiguous answer; the root checks for this.
+            if !self.consume_fuel() {
+                self.context.search_graph[dfn].solution = Ok(Solution::Ambig(
+                    Guidance::Unknown,
+                    AmbiguityCause::FuelExhausted,
+                ));
+                self.context.search_graph[dfn].solution_priority = ClausePriority::High;
+                break Minimums::new();
+            }
+
             let minimums = &mut Minimums::new();
             let (current_answer, current_prio) = self.solve_iteration(&canonical_goal, minimums);
 
@@ -160,7 +310,7 @@ impl<'me, I: Interner> Solver<'me, I> {
                 // We can return.
                 self.context.search_graph[dfn].solution = current_answer;
                 self.context.search_graph[dfn].solution_priority = current_prio;
-                return *minimums;
+                break *minimums;
             }
 
             let old_answer = &self.context.search_graph[dfn].solution;
@@ -179,7 +329,7 @@ impl<'me, I: Interner> Solver<'me, I> {
             // with the current answer.
             if self.context.search_graph[dfn].solution == current_answer {
                 // Reached a fixed point.
-                return *minimums;
+                break *minimums;
             }
 
             let current_answer_is_ambig = match &current_answer {
@@ -194,12 +344,22 @@ impl<'me, I: Interner> Solver<'me, I> {
             // in fact we *must* -- otherwise, we sometimes fail to reach a
             // fixed point. See `multiple_ambiguous_cycles` for more.
             if current_answer_is_ambig {
-                return *minimums;
+                break *minimums;
             }
 
             // Otherwise: rollback the search tree and try again.
             self.context.search_graph.rollback_to(dfn + 1);
+        };
+
+        // The recorded derivation is the one from the last iteration, but the
+        // final solution may have been combined with earlier ones.
+        if let Some(proofs) = &mut self.context.proofs {
+            if let Some(proof) = proofs.get_mut(&canonical_goal) {
+                proof.solution = self.context.search_graph[dfn].solution.clone();
+            }
         }
+
+        minimums
     }
 }
 
@@ -213,11 +373,21 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
         goal: UCanonicalGoal<I>,
         minimums: &mut Minimums,
     ) -> Fallible<Solution<I>> {
-        // First check the cache.
+        // First check the cache. If we are recording proofs, results that
+        // were cached before we started doing so have no derivation, so the
+        // goal is solved again.
         if let Some(value) = self.context.cache.get(&goal) {
-            debug!("solve_reduced_goal: cache hit, value={:?}", value);
-            return value.clone();
+            let has_proof = match &self.context.proofs {
+                Some(proofs) => proofs.contains_key(&goal),
+                None => true,
+            };
+            if has_proof {
+                debug!("solve_reduced_goal: cache hit, value={:?}", value);
+                self.context.stats.cache_hits += 1;
+                return value.clone();
+            }
         }
+        self.context.stats.cache_misses += 1;
 
         // Next, check if the goal is in the search tree already.
         if let Some(dfn) = self.context.search_graph.lookup(&goal) {
@@ -229,15 +399,8 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
                 //
                 // XXX how does caching with coinduction work?
                 if self.context.stack.coinductive_cycle_from(depth) {
-                    let value = ConstrainedSubst {
-                        subst: goal.trivial_substitution(self.program.interner()),
-                        constraints: Constraints::empty(self.program.interner()),
-                    };
                     debug!("applying coinductive semantics");
-                    return Ok(Solution::Unique(Canonical {
-                        value,
-                        binders: goal.canonical.binders,
-                    }));
+                    return Ok(proof::trivial_solution(self.program.interner(), &goal));
                 }
 
                 self.context.stack[depth].flag_cycle();
@@ -253,12 +416,29 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
                 previous_solution, previous_solution_priority
             );
             previous_solution
+        } else if self.context.stack.is_full() {
+            // The goal is nested too deeply; give up on it rather than
+            // pushing it onto the stack.
+            let mut stack = self.context.search_graph.stack_goals();
+            stack.push(goal.clone());
+            info!("solve_goal: overflow, stack = {:?}", stack);
+            self.context.stats.overflows += 1;
+            let solution = Ok(Solution::Ambig(
+                Guidance::Unknown,
+                AmbiguityCause::Overflow(stack),
+            ));
+            self.record_proof(
+                &goal,
+                ProofNode::leaf(solution.clone(), ProofStep::Overflow),
+            );
+            solution
         } else {
             // Otherwise, push the goal onto the stack and create a table.
             // The initial result for this table is error.
             let coinductive_goal = goal.is_coinductive(self.program);
             let depth = self.context.stack.push(coinductive_goal);
             let dfn = self.context.search_graph.insert(&goal, depth);
+            self.context.stats.tables_created += 1;
             let subgoal_minimums = self.solve_new_subgoal(goal, depth, dfn);
             self.context.search_graph[dfn].links = subgoal_minimums;
             self.context.search_graph[dfn].stack_depth = None;
@@ -268,20 +448,26 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
             // Read final result from table.
             let result = self.context.search_graph[dfn].solution.clone();
             let priority = self.context.search_graph[dfn].solution_priority;
+            if result.is_ok() {
+                self.context.stats.answers_produced += 1;
+            }
 
             // If processing this subgoal did not involve anything
             // outside of its subtree, then we can promote it to the
             // cache now. This is a sort of hack to alleviate the
             // worst of the repeated work that we do during tabling.
             if subgoal_minimums.positive >= dfn {
-                if self.context.caching_enabled {
+                // The results of goals solved after running out of fuel may
+                // depend on goals that were given up on, so they are not
+                // cached.
+                if self.context.caching_enabled && !self.context.fuel_exhausted {
                     self.context
                         .search_graph
                         .move_to_cache(dfn, &mut self.context.cache);
                     debug!("solve_reduced_goal: SCC head encountered, moving to cache");
                 } else {
                     debug!(
-                        "solve_reduced_goal: SCC head encountered, rolling back as caching disabled"
+                        "solve_reduced_goal: SCC head encountered, rolling back as caching disabled or out of fuel"
                     );
                     self.context.search_graph.rollback_to(dfn);
                 }
@@ -303,6 +489,25 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
     fn max_size(&self) -> usize {
         self.context.max_size
     }
+
+    fn recording_proofs(&self) -> bool {
+        self.context.proofs.is_some()
+    }
+
+    fn record_proof(&mut self, goal: &UCanonicalGoal<I>, node: ProofNode<I>) {
+        // Like their results, the proofs of goals solved after running out
+        // of fuel are not kept.
+        if self.context.fuel_exhausted {
+            return;
+        }
+        if let Some(proofs) = &mut self.context.proofs {
+            proofs.insert(goal.clone(), node);
+        }
+    }
+
+    fn stats(&mut self) -> &mut SolverStats {
+        &mut self.context.stats
+    }
 }
 
 impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
@@ -311,18 +516,8 @@ impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
         program: &dyn RustIrDatabase<I>,
         goal: &UCanonical<InEnvironment<Goal<I>>>,
     ) -> Option<chalk_solve::Solution<I>> {
-        self.ctx
-            .solver(program)
-            .solve_root_goal(goal)
-            .ok()
-            .map(|s| match s {
-                Solution::Unique(c) => chalk_solve::Solution::Unique(c),
-                Solution::Ambig(g) => chalk_solve::Solution::Ambig(match g {
-                    Guidance::Definite(g) => chalk_solve::Guidance::Definite(g),
-                    Guidance::Suggested(g) => chalk_solve::Guidance::Suggested(g),
-                    Guidance::Unknown => chalk_solve::Guidance::Unknown,
-                }),
-            })
+        self.measure(|ctx| ctx.solver(program).solve_root_goal(goal).ok())
+            .map(Solution::into_solve_solution)
     }
 
     fn solve_limited(
@@ -332,29 +527,51 @@ impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
         _should_continue: &dyn std::ops::Fn() -> bool,
     ) -> Option<chalk_solve::Solution<I>> {
         // TODO support should_continue in recursive solver
-        self.ctx
-            .solver(program)
-            .solve_root_goal(goal)
-            .ok()
-            .map(|s| match s {
-                Solution::Unique(c) => chalk_solve::Solution::Unique(c),
-                Solution::Ambig(g) => chalk_solve::Solution::Ambig(match g {
-                    Guidance::Definite(g) => chalk_solve::Guidance::Definite(g),
-                    Guidance::Suggested(g) => chalk_solve::Guidance::Suggested(g),
-                    Guidance::Unknown => chalk_solve::Guidance::Unknown,
-                }),
-            })
+        self.measure(|ctx| ctx.solver(program).solve_root_goal(goal).ok())
+            .map(Solution::into_solve_solution)
+    }
+
+    fn solve_with_fuel(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+        fuel: usize,
+    ) -> Result<Option<chalk_solve::Solution<I>>, FuelExhausted> {
+        self.measure(|ctx| {
+            ctx.fuel = Some(fuel);
+            ctx.fuel_exhausted = false;
+            let solution = ctx.solver(program).solve_root_goal(goal).ok();
+            ctx.fuel = None;
+            if ctx.fuel_exhausted {
+                Err(FuelExhausted)
+            } else {
+                Ok(solution.map(Solution::into_solve_solution))
+            }
+        })
     }
 
     fn solve_multiple(
         &mut self,
-        _program: &dyn RustIrDatabase<I>,
-        _goal: &UCanonical<InEnvironment<Goal<I>>>,
-        _f: &mut dyn FnMut(
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+        f: &mut dyn FnMut(
             chalk_solve::SubstitutionResult<Canonical<ConstrainedSubst<I>>>,
             bool,
         ) -> bool,
     ) -> bool {
-        unimplemented!("Recursive solver doesn't support multiple answers")
+        let mut answers = self
+            .measure(|ctx| ctx.solver(program).solve_root_goal_per_clause(goal))
+            .into_iter()
+            .peekable();
+        while let Some(answer) = answers.next() {
+            if !f(answer, answers.peek().is_some()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn stats(&self) -> Option<&SolverStats> {
+        Some(&self.ctx.stats)
     }
 }
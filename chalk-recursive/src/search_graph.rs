@@ -50,6 +50,17 @@ impl<I: Interner> SearchGraph<I> {
         self.indices.get(goal).cloned()
     }
 
+    /// The goals that are currently on the stack, from the outermost one.
+    pub(crate) fn stack_goals(&self) -> Vec<UCanonicalGoal<I>> {
+        // Goals are pushed onto the stack as they are inserted, so the ones
+        // on the stack are in depth-first order.
+        self.nodes
+            .iter()
+            .filter(|node| node.stack_depth.is_some())
+            .map(|node| node.goal.clone())
+            .collect()
+    }
+
     /// Insert a new search node in the tree. The node will be in the initial
     /// state for a search node:
     ///
@@ -1,5 +1,6 @@
 use super::combine;
 use super::fulfill::{Fulfill, RecursiveInferenceTable};
+use crate::proof::{ProofEdge, ProofNode};
 use crate::{Guidance, Minimums, Solution, UCanonicalGoal};
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::{HasInterner, Interner};
@@ -14,7 +15,8 @@ use chalk_ir::{
 use chalk_solve::clauses::program_clauses_for_goal;
 use chalk_solve::debug_span;
 use chalk_solve::infer::{InferenceTable, ParameterEnaVariableExt};
-use chalk_solve::{solve::truncate, RustIrDatabase};
+use chalk_solve::proof::ProofStep;
+use chalk_solve::{solve::truncate, AmbiguityCause, RustIrDatabase, SolverStats};
 use std::fmt::Debug;
 use tracing::{debug, instrument};
 
@@ -30,6 +32,15 @@ pub(super) trait SolveDatabase<I: Interner>: Sized {
     fn interner(&self) -> &I;
 
     fn db(&self) -> &dyn RustIrDatabase<I>;
+
+    /// True if the derivations of the goals we solve are being recorded.
+    fn recording_proofs(&self) -> bool;
+
+    /// Records the derivation computed for `goal`, replacing any earlier one.
+    fn record_proof(&mut self, goal: &UCanonicalGoal<I>, node: ProofNode<I>);
+
+    /// The statistics for the current query.
+    fn stats(&mut self) -> &mut SolverStats;
 }
 
 /// The `solve_iteration` method -- implemented for any type that implements
@@ -53,7 +64,7 @@ pub(super) trait SolveIteration<I: Interner>: SolveDatabase<I> {
                 },
         } = canonical_goal.clone();
 
-        match goal.data(self.interner()) {
+        let (solution, prio, proof) = match goal.data(self.interner()) {
             GoalData::DomainGoal(domain_goal) => {
                 let canonical_goal = UCanonical {
                     universes,
@@ -72,20 +83,25 @@ pub(super) trait SolveIteration<I: Interner>: SolveDatabase<I> {
                 // or from the lowered program, which includes fallback
                 // clauses. We try each approach in turn:
 
-                let (prog_solution, prog_prio) = {
+                let (prog_solution, prog_prio, proof) = {
                     debug_span!("prog_clauses");
 
                     let prog_clauses = self.program_clauses_for_goal(&canonical_goal);
                     match prog_clauses {
                         Ok(clauses) => self.solve_from_clauses(&canonical_goal, clauses, minimums),
                         Err(Floundered) => {
-                            (Ok(Solution::Ambig(Guidance::Unknown)), ClausePriority::High)
+                            let solution = Ok(Solution::Ambig(
+                                Guidance::Unknown,
+                                AmbiguityCause::Floundered,
+                            ));
+                            let proof = ProofNode::leaf(solution.clone(), ProofStep::Floundered);
+                            (solution, ClausePriority::High, Some(proof))
                         }
                     }
                 };
                 debug!(?prog_solution);
 
-                (prog_solution, prog_prio)
+                (prog_solution, prog_prio, proof)
             }
 
             _ => {
@@ -99,7 +115,45 @@ pub(super) trait SolveIteration<I: Interner>: SolveDatabase<I> {
 
                 self.solve_via_simplification(&canonical_goal, minimums)
             }
+        };
+
+        if let Some(proof) = proof {
+            self.record_proof(canonical_goal, proof);
+        }
+
+        (solution, prio)
+    }
+
+    /// Solves a domain goal through each of the program clauses that apply
+    /// to it separately, instead of combining their solutions like
+    /// `solve_iteration` does. Returns the solutions of the clauses that
+    /// succeeded, leaving out low-priority ones if any high-priority clause
+    /// succeeded, or `Err(Floundered)` if the clauses cannot be enumerated.
+    fn solve_per_clause(
+        &mut self,
+        canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
+        minimums: &mut Minimums,
+    ) -> Result<Vec<Solution<I>>, Floundered> {
+        let clauses = self.program_clauses_for_goal(canonical_goal)?;
+        let mut solutions = vec![];
+        for program_clause in clauses {
+            let ProgramClauseData(implication) = program_clause.data(self.interner());
+            if let (Ok(solution), priority, _) =
+                self.solve_via_implication(canonical_goal, implication, minimums)
+            {
+                solutions.push((solution, priority));
+            }
+        }
+        if solutions
+            .iter()
+            .any(|(_, priority)| *priority == ClausePriority::High)
+        {
+            solutions.retain(|(_, priority)| *priority == ClausePriority::High);
         }
+        Ok(solutions
+            .into_iter()
+            .map(|(solution, _)| solution)
+            .collect())
     }
 }
 
@@ -112,42 +166,70 @@ where
 
 /// Helper methods for `solve_iteration`, private to this module.
 trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
+    /// Solves a compound goal by breaking it down into subgoals. If proofs
+    /// are being recorded, also returns the derivation of the goal.
     #[instrument(level = "debug", skip(self, minimums))]
     fn solve_via_simplification(
         &mut self,
         canonical_goal: &UCanonicalGoal<I>,
         minimums: &mut Minimums,
-    ) -> (Fallible<Solution<I>>, ClausePriority) {
+    ) -> (Fallible<Solution<I>>, ClausePriority, Option<ProofNode<I>>) {
         let (infer, subst, goal) = self.new_inference_table(canonical_goal);
-        match Fulfill::new_with_simplification(self, infer, subst, goal) {
-            Ok(fulfill) => (fulfill.solve(minimums), ClausePriority::High),
-            Err(e) => (Err(e), ClausePriority::High),
-        }
+        let (solution, subgoals) = match Fulfill::new_with_simplification(self, infer, subst, goal)
+        {
+            Ok(fulfill) => fulfill.solve(minimums),
+            Err(e) => (Err(e), vec![]),
+        };
+        let proof = if self.recording_proofs() {
+            Some(ProofNode {
+                solution: solution.clone(),
+                step: ProofStep::Simplification,
+                subgoals,
+            })
+        } else {
+            None
+        };
+        (solution, ClausePriority::High, proof)
     }
 
     /// See whether we can solve a goal by implication on any of the given
     /// clauses. If multiple such solutions are possible, we attempt to combine
-    /// them.
+    /// them. If proofs are being recorded, also returns the derivation of the
+    /// goal.
     fn solve_from_clauses<C>(
         &mut self,
         canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
         clauses: C,
         minimums: &mut Minimums,
-    ) -> (Fallible<Solution<I>>, ClausePriority)
+    ) -> (Fallible<Solution<I>>, ClausePriority, Option<ProofNode<I>>)
     where
         C: IntoIterator<Item = ProgramClause<I>>,
     {
         let mut cur_solution = None;
+        let mut proofs = vec![];
         for program_clause in clauses {
             debug_span!("solve_from_clauses", clause = ?program_clause);
 
             // If we have a completely ambiguous answer, it's not going to get better, so stop
-            if cur_solution == Some((Solution::Ambig(Guidance::Unknown), ClausePriority::High)) {
-                return (Ok(Solution::Ambig(Guidance::Unknown)), ClausePriority::High);
+            if let Some((solution @ Solution::Ambig(Guidance::Unknown, _), ClausePriority::High)) =
+                &cur_solution
+            {
+                let solution = Ok(solution.clone());
+                let proof = self.combine_proofs(&solution, proofs);
+                return (solution, ClausePriority::High, proof);
             }
 
             let ProgramClauseData(implication) = program_clause.data(self.interner());
-            let res = self.solve_via_implication(canonical_goal, implication, minimums);
+            let (solution, priority, subgoals) =
+                self.solve_via_implication(canonical_goal, implication, minimums);
+            if self.recording_proofs() {
+                proofs.push(ProofNode {
+                    solution: solution.clone(),
+                    step: ProofStep::Clause(program_clause.clone()),
+                    subgoals,
+                });
+            }
+            let res = (solution, priority);
 
             if let (Ok(solution), priority) = res {
                 debug!(?solution, ?priority, "Ok");
@@ -166,7 +248,37 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
                 debug!("Error");
             }
         }
-        cur_solution.map_or((Err(NoSolution), ClausePriority::High), |(s, p)| (Ok(s), p))
+        let (solution, priority) =
+            cur_solution.map_or((Err(NoSolution), ClausePriority::High), |(s, p)| (Ok(s), p));
+        let proof = self.combine_proofs(&solution, proofs);
+        (solution, priority, proof)
+    }
+
+    /// Builds the derivation of a goal out of the derivations through each of
+    /// the clauses we tried. If exactly one clause succeeded, that is the
+    /// derivation; otherwise, all successful clauses (or, if none succeeded,
+    /// all failed ones) are recorded as alternatives.
+    fn combine_proofs(
+        &self,
+        solution: &Fallible<Solution<I>>,
+        mut proofs: Vec<ProofNode<I>>,
+    ) -> Option<ProofNode<I>> {
+        if !self.recording_proofs() {
+            return None;
+        }
+        if proofs.iter().any(|proof| proof.solution.is_ok()) {
+            proofs.retain(|proof| proof.solution.is_ok());
+        }
+        if proofs.len() == 1 && solution.is_ok() {
+            let mut proof = proofs.pop().unwrap();
+            proof.solution = solution.clone();
+            return Some(proof);
+        }
+        Some(ProofNode {
+            solution: solution.clone(),
+            step: ProofStep::Alternatives,
+            subgoals: proofs.into_iter().map(ProofEdge::Alternative).collect(),
+        })
     }
 
     /// Modus ponens! That is: try to apply an implication by proving its premises.
@@ -176,11 +288,14 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
         canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
         clause: &Binders<ProgramClauseImplication<I>>,
         minimums: &mut Minimums,
-    ) -> (Fallible<Solution<I>>, ClausePriority) {
+    ) -> (Fallible<Solution<I>>, ClausePriority, Vec<ProofEdge<I>>) {
         let (infer, subst, goal) = self.new_inference_table(canonical_goal);
         match Fulfill::new_with_clause(self, infer, subst, goal, clause) {
-            Ok(fulfill) => (fulfill.solve(minimums), clause.skip_binders().priority),
-            Err(e) => (Err(e), ClausePriority::High),
+            Ok(fulfill) => {
+                let (solution, subgoals) = fulfill.solve(minimums);
+                (solution, clause.skip_binders().priority, subgoals)
+            }
+            Err(e) => (Err(e), ClausePriority::High, vec![]),
         }
     }
 
@@ -202,10 +317,13 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
     }
 
     fn program_clauses_for_goal(
-        &self,
+        &mut self,
         canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
     ) -> Result<Vec<ProgramClause<I>>, Floundered> {
-        program_clauses_for_goal(self.db(), &canonical_goal)
+        let clauses = program_clauses_for_goal(self.db(), &canonical_goal)?;
+        self.stats()
+            .record_program_clauses(&canonical_goal.canonical.value.goal, clauses.len());
+        Ok(clauses)
     }
 }
 
@@ -39,17 +39,18 @@ impl Stack {
         self.entries.is_empty()
     }
 
+    /// True if the stack has reached the overflow depth, so no more goals
+    /// can be pushed.
+    pub(crate) fn is_full(&self) -> bool {
+        self.entries.len() >= self.overflow_depth
+    }
+
     pub(crate) fn push(&mut self, coinductive_goal: bool) -> StackDepth {
         let depth = StackDepth {
             depth: self.entries.len(),
         };
 
-        if depth.depth >= self.overflow_depth {
-            // This shoudl perhaps be a result or something, though
-            // really I'd prefer to move to subgoal abstraction for
-            // guaranteeing termination. -nmatsakis
-            panic!("overflow depth reached")
-        }
+        assert!(!self.is_full(), "overflow depth reached");
 
         self.entries.push(StackEntry {
             coinductive_goal,
@@ -0,0 +1,208 @@
+//! Autoderef: the chain of types a value can be dereferenced to, as used
+//! by method resolution and field access.
+//!
+//! Starting from some type `T`, each step either dereferences a reference
+//! (`&U` and `&mut U` deref to `U`), or goes through an overloaded `Deref`
+//! impl, to `<T as Deref>::Target` normalized. The chain stops at the first
+//! type that can't be dereferenced, or whose `Target` is ambiguous.
+
+use crate::infer::ucanonicalize::UniverseMapExt;
+use crate::infer::InferenceTable;
+use crate::solve::{AmbiguityCause, Solution, Solver};
+use crate::{RustIrDatabase, WellKnownTrait};
+use chalk_ir::cast::Cast;
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use tracing::{debug, instrument};
+
+/// The number of steps after which we give up on a chain of derefs.
+pub const AUTODEREF_RECURSION_LIMIT: usize = 10;
+
+/// The result of [`autoderef_steps`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutoderefSteps<I: Interner> {
+    /// The starting type, followed by each type it derefs to, in order.
+    pub steps: Vec<Ty<I>>,
+
+    /// Whether the chain was cut short, because it got longer than
+    /// [`AUTODEREF_RECURSION_LIMIT`], because it derefs back to one of its
+    /// own types (as with `impl Deref for S { type Target = S; }`), or
+    /// because the solver overflowed looking for the next type.
+    pub overflowed: bool,
+}
+
+/// Computes the types that `ty` autoderefs to in `environment`, using
+/// `solver` to normalize `<T as Deref>::Target` for overloaded derefs.
+/// Overloaded derefs are only considered if the program has a trait with
+/// `WellKnownTrait::Deref`.
+///
+/// `ty` must not contain inference variables; placeholders are fine.
+#[instrument(level = "debug", skip(db, solver))]
+pub fn autoderef_steps<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solver: &mut dyn Solver<I>,
+    environment: &Environment<I>,
+    ty: Ty<I>,
+) -> AutoderefSteps<I> {
+    let interner = db.interner();
+
+    // Renumber the universes of the placeholders in `environment` and `ty`,
+    // so that we can create inference variables that can name all of them.
+    let mut infer = InferenceTable::new();
+    let canonical = infer
+        .canonicalize(interner, InEnvironment::new(environment, ty))
+        .quantified;
+    let ucanonical = infer.u_canonicalize(interner, &canonical);
+    let universes = ucanonical.universes;
+    let num_universes = ucanonical.quantified.universes;
+    let (mut infer, _, InEnvironment { environment, goal }) =
+        InferenceTable::from_canonical(interner, num_universes, ucanonical.quantified.canonical);
+    let max_universe = UniverseIndex {
+        counter: num_universes - 1,
+    };
+
+    let mut steps = vec![goal];
+    let overflowed = loop {
+        let ty = match deref_once(
+            db,
+            solver,
+            &mut infer,
+            max_universe,
+            &environment,
+            steps.last().unwrap(),
+        ) {
+            DerefStep::Target(ty) => ty,
+            DerefStep::Stop => break false,
+            DerefStep::Overflow => break true,
+        };
+        debug!(?ty, "deref step");
+        if steps.len() > AUTODEREF_RECURSION_LIMIT || steps.contains(&ty) {
+            break true;
+        }
+        steps.push(ty);
+    };
+
+    let steps = steps
+        .into_iter()
+        .map(|ty| {
+            let canonical = Canonical {
+                value: ty,
+                binders: CanonicalVarKinds::empty(interner),
+            };
+            universes.map_from_canonical(interner, &canonical).value
+        })
+        .collect();
+    AutoderefSteps { steps, overflowed }
+}
+
+/// The outcome of trying to deref a type once.
+enum DerefStep<I: Interner> {
+    /// The type derefs to this one.
+    Target(Ty<I>),
+    /// The type can't be dereferenced, or its `Target` isn't known.
+    Stop,
+    /// The solver overflowed (or truncated a goal) trying to find the
+    /// `Target` of the type.
+    Overflow,
+}
+
+/// The type `ty` derefs to, if it is a reference or if `<ty as Deref>::Target`
+/// normalizes to a unique type.
+fn deref_once<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solver: &mut dyn Solver<I>,
+    infer: &mut InferenceTable<I>,
+    max_universe: UniverseIndex,
+    environment: &Environment<I>,
+    ty: &Ty<I>,
+) -> DerefStep<I> {
+    let interner = db.interner();
+
+    if let TyKind::Ref(_, _, pointee) = ty.kind(interner) {
+        return DerefStep::Target(pointee.clone());
+    }
+
+    let deref_trait_id = match db.well_known_trait_id(WellKnownTrait::Deref) {
+        Some(id) => id,
+        None => return DerefStep::Stop,
+    };
+    let target_id = match db.trait_datum(deref_trait_id).associated_ty_ids.first() {
+        Some(&id) => id,
+        None => return DerefStep::Stop,
+    };
+
+    // `Normalize` only uses impls; if that fails, `AliasEq` also uses the
+    // bounds in the environment. We don't use `AliasEq` for everything, as
+    // the SLG solver finds it ambiguous whether the projection normalizes or
+    // stays a placeholder.
+    let projection = AliasTy::Projection(ProjectionTy {
+        associated_ty_id: target_id,
+        substitution: Substitution::from1(interner, ty.clone()),
+    });
+    let target = infer.new_variable(max_universe).to_ty(interner);
+    let normalize_goal: Goal<I> = Normalize {
+        alias: projection.clone(),
+        ty: target.clone(),
+    }
+    .cast(interner);
+    // The projection can always stay a placeholder, so we need to check
+    // that `ty` does implement `Deref`.
+    let implemented_goal: Goal<I> = TraitRef {
+        trait_id: deref_trait_id,
+        substitution: Substitution::from1(interner, ty.clone()),
+    }
+    .cast(interner);
+    let alias_eq_goal: Goal<I> = AliasEq {
+        alias: projection,
+        ty: target,
+    }
+    .cast(interner);
+    let alias_eq_goal = GoalData::All(Goals::from_iter(
+        interner,
+        vec![implemented_goal, alias_eq_goal],
+    ))
+    .intern(interner);
+
+    match solve_for_target(db, solver, infer, environment, normalize_goal) {
+        DerefStep::Stop => solve_for_target(db, solver, infer, environment, alias_eq_goal),
+        step => step,
+    }
+}
+
+/// Solves `goal`, whose only inference variable is the `Target` of some
+/// projection, for the unique value of the variable.
+fn solve_for_target<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solver: &mut dyn Solver<I>,
+    infer: &mut InferenceTable<I>,
+    environment: &Environment<I>,
+    goal: Goal<I>,
+) -> DerefStep<I> {
+    let interner = db.interner();
+
+    let canonicalized = infer.canonicalize(interner, InEnvironment::new(environment, goal));
+    let ucanonicalized = infer.u_canonicalize(interner, &canonicalized.quantified);
+    let subst = match solver.solve(db, &ucanonicalized.quantified) {
+        Some(Solution::Unique(subst)) => subst,
+        Some(Solution::Ambig(_, AmbiguityCause::Overflow(_)))
+        | Some(Solution::Ambig(_, AmbiguityCause::Truncation)) => return DerefStep::Overflow,
+        Some(Solution::Ambig(..)) | None => return DerefStep::Stop,
+    };
+
+    // If the value of the variable still mentions variables of the
+    // solution, the target isn't fully known.
+    if !subst.binders.is_empty(interner) {
+        return DerefStep::Stop;
+    }
+    let subst = ucanonicalized
+        .universes
+        .map_from_canonical(interner, &subst);
+    DerefStep::Target(
+        subst
+            .value
+            .subst
+            .at(interner, 0)
+            .assert_ty_ref(interner)
+            .clone(),
+    )
+}
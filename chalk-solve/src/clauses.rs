@@ -2,6 +2,8 @@ use self::builder::ClauseBuilder;
 use self::env_elaborator::elaborate_env_clauses;
 use self::program_clauses::ToProgramClauses;
 use crate::goal_builder::GoalBuilder;
+use crate::object_safety;
+use crate::rust_ir::{AssociatedTyValue, AssociatedTyValueBound, Movability, WellKnownTrait};
 use crate::split::Split;
 use crate::RustIrDatabase;
 use chalk_ir::cast::{Cast, Caster};
@@ -19,6 +21,8 @@ mod env_elaborator;
 mod generalize;
 pub mod program_clauses;
 
+pub(crate) use self::dyn_ty::super_traits;
+
 // yields the types "contained" in `app_ty`
 fn constituent_types<I: Interner>(db: &dyn RustIrDatabase<I>, ty: &TyKind<I>) -> Vec<Ty<I>> {
     let interner = db.interner();
@@ -179,6 +183,20 @@ pub fn push_auto_trait_impls<I: Interner>(
             Ok(())
         }
 
+        // `Unpin` depends on the movability of the generator, not on its
+        // constituent types
+        TyKind::Generator(generator_id, _)
+            if Some(auto_trait_id) == builder.db.well_known_trait_id(WellKnownTrait::Unpin) =>
+        {
+            match builder.db.generator_datum(*generator_id).movability {
+                // immovable generators are never `Unpin`
+                Movability::Static => (),
+                // movable generators are always `Unpin`
+                Movability::Movable => builder.push_fact(consequence),
+            }
+            Ok(())
+        }
+
         TyKind::OpaqueType(opaque_ty_id, _) => {
             push_auto_trait_impls_opaque(builder, auto_trait_id, *opaque_ty_id);
             Ok(())
@@ -410,7 +428,12 @@ fn program_clauses_that_could_match<I: Interner>(
                     // An alias could normalize to anything, including `dyn trait`
                     // or an opaque type, so push a clause that asks for the
                     // self type to be normalized and return.
-                    push_alias_implemented_clause(builder, trait_ref.clone(), alias.clone());
+                    push_alias_implemented_clause(
+                        builder,
+                        WhereClause::Implemented,
+                        trait_ref.clone(),
+                        alias.clone(),
+                    );
                     return Ok(clauses);
                 }
 
@@ -456,11 +479,15 @@ fn program_clauses_that_could_match<I: Interner>(
             // as for the `Implemented(Foo) :- FromEnv(Foo)` rule.
             trait_datum.to_program_clauses(builder, environment);
 
-            for impl_id in db.impls_for_trait(
+            // More specialized impls are tried first.
+            let mut impls = db.impls_for_trait(
                 trait_ref.trait_id,
                 trait_ref.substitution.as_slice(interner),
                 binders,
-            ) {
+            );
+            db.specialization_priorities(trait_id)
+                .sort_by_priority(&mut impls);
+            for impl_id in impls {
                 db.impl_datum(impl_id)
                     .to_program_clauses(builder, environment);
             }
@@ -486,6 +513,29 @@ fn program_clauses_that_could_match<I: Interner>(
                 )?;
             }
         }
+        DomainGoal::Holds(WhereClause::NotImplemented(trait_ref)) => {
+            let self_ty = trait_ref.self_type_parameter(interner);
+            if let TyKind::Alias(alias) = self_ty.kind(interner) {
+                push_alias_implemented_clause(
+                    builder,
+                    WhereClause::NotImplemented,
+                    trait_ref.clone(),
+                    alias.clone(),
+                );
+                return Ok(clauses);
+            }
+
+            // Only negative impls can prove that a type doesn't implement a
+            // trait: this is *not* negation as failure.
+            for impl_id in db.impls_for_trait(
+                trait_ref.trait_id,
+                trait_ref.substitution.as_slice(interner),
+                binders,
+            ) {
+                db.impl_datum(impl_id)
+                    .to_program_clauses(builder, environment);
+            }
+        }
         DomainGoal::Holds(WhereClause::AliasEq(alias_eq)) => match &alias_eq.alias {
             AliasTy::Projection(proj) => {
                 let trait_self_ty = db
@@ -563,7 +613,11 @@ fn program_clauses_that_could_match<I: Interner>(
                 .to_program_clauses(builder, environment);
         }
         DomainGoal::ObjectSafe(trait_id) => {
-            if builder.db.is_object_safe(*trait_id) {
+            // The database knows about violations chalk cannot see (e.g.
+            // in method receivers), so both have to agree.
+            if builder.db.is_object_safe(*trait_id)
+                && object_safety::is_object_safe(builder.db, *trait_id)
+            {
                 builder.push_fact(DomainGoal::ObjectSafe(*trait_id));
             }
         }
@@ -632,6 +686,34 @@ fn program_clauses_that_could_match<I: Interner>(
             }
             AliasTy::Opaque(_) => (),
         },
+        DomainGoal::NormalizeConst(NormalizeConst { alias, value: _ }) => {
+            // Like `Normalize` goals, `NormalizeConst` goals derive from the
+            // `AssociatedConstValue` datums found in the impls of the trait.
+            let trait_id = db.associated_const_data(alias.associated_const_id).trait_id;
+            let trait_parameters = alias.substitution.as_slice(interner);
+
+            let self_ty = alias.self_type_parameter(interner);
+            if let TyKind::InferenceVar(_, _) = self_ty.kind(interner) {
+                panic!("Inference vars not allowed when getting program clauses");
+            }
+
+            // Flounder if the self-type is unknown and the trait is non-enumerable.
+            //
+            // e.g., NormalizeConst(<?X as Trait>::N -> 3)
+            if self_ty.is_general_var(interner, binders)
+                && db.trait_datum(trait_id).is_non_enumerable_trait()
+            {
+                return Err(Floundered);
+            }
+
+            push_program_clauses_for_associated_const_values_in_impls_of(
+                builder,
+                environment,
+                trait_id,
+                trait_parameters,
+                binders,
+            );
+        }
         DomainGoal::Compatible | DomainGoal::Reveal => (),
     };
 
@@ -717,10 +799,15 @@ fn push_program_clauses_for_associated_type_values_in_impls_of<I: Interner>(
     trait_parameters: &[GenericArg<I>],
     binders: &CanonicalVarKinds<I>,
 ) {
-    for impl_id in builder
+    // The values of more specialized impls are tried first.
+    let mut impls = builder
         .db
-        .impls_for_trait(trait_id, trait_parameters, binders)
-    {
+        .impls_for_trait(trait_id, trait_parameters, binders);
+    builder
+        .db
+        .specialization_priorities(trait_id)
+        .sort_by_priority(&mut impls);
+    for impl_id in impls {
         let impl_datum = builder.db.impl_datum(impl_id);
         if !impl_datum.is_positive() {
             continue;
@@ -733,11 +820,139 @@ fn push_program_clauses_for_associated_type_values_in_impls_of<I: Interner>(
             debug!(?atv_id, ?atv);
             atv.to_program_clauses(builder, environment);
         }
+
+        let trait_datum = builder.db.trait_datum(trait_id);
+        for &associated_ty_id in &trait_datum.associated_ty_ids {
+            if let Some(atv) = default_associated_ty_value(builder.db, impl_id, associated_ty_id) {
+                debug!(?associated_ty_id, ?atv);
+                atv.to_program_clauses(builder, environment);
+            }
+        }
+    }
+}
+
+/// Returns the value that the impl `impl_id` takes from the default of the
+/// associated type `associated_ty_id` in the trait, if the impl does not give
+/// a value for it itself:
+///
+/// ```ignore
+/// trait Add<Rhs = Self> {
+///     type Output = Self;
+/// }
+///
+/// impl Add for u32 {} // <-- as if `type Output = u32;` was written here
+/// ```
+///
+/// Impls that specialize another impl inherit the value of the impl they
+/// specialize instead. The value can be overridden by impls specializing
+/// this one, so it is treated as a `default` value.
+///
+/// Which impls specialize others comes from `specialization_priorities`.
+/// While those are being computed, the coherence solver sees no impl as
+/// specializing, so every impl without a value takes the default then.
+fn default_associated_ty_value<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    impl_id: ImplId<I>,
+    associated_ty_id: AssocTypeId<I>,
+) -> Option<AssociatedTyValue<I>> {
+    let interner = db.interner();
+    let associated_ty = db.associated_ty_data(associated_ty_id);
+    let default = associated_ty
+        .binders
+        .map_ref(|b| b.default.clone())
+        .filter_map(|default| default)?;
+
+    let impl_datum = db.impl_datum(impl_id);
+    let defines_value = impl_datum
+        .associated_ty_value_ids
+        .iter()
+        .any(|&id| db.associated_ty_value(id).associated_ty_id == associated_ty_id);
+    if defines_value
+        || db
+            .specialization_priorities(impl_datum.trait_id())
+            .specializes_other_impl(impl_id)
+    {
+        return None;
+    }
+
+    // The binders of the value are those of the associated type itself,
+    // followed by those of the impl; the default refers to the former,
+    // followed by the parameters of the trait.
+    let trait_params_len = db.trait_datum(associated_ty.trait_id).binders.len(interner);
+    let assoc_params_len = default.len(interner) - trait_params_len;
+    let binders = VariableKinds::from_iter(
+        interner,
+        default
+            .binders
+            .iter(interner)
+            .take(assoc_params_len)
+            .chain(impl_datum.binders.binders.iter(interner))
+            .cloned(),
+    );
+    let parameters: Vec<_> = binders
+        .iter(interner)
+        .enumerate()
+        .map(|p| p.to_generic_arg(interner))
+        .collect();
+    let (assoc_params, impl_params) = parameters.split_at(assoc_params_len);
+    let trait_params = impl_datum
+        .binders
+        .map_ref(|b| &b.trait_ref.substitution)
+        .cloned()
+        .substitute(interner, impl_params);
+    let ty = default.substitute(
+        interner,
+        &Substitution::from_iter(
+            interner,
+            assoc_params
+                .iter()
+                .chain(trait_params.iter(interner))
+                .cloned(),
+        ),
+    );
+
+    Some(AssociatedTyValue {
+        impl_id,
+        associated_ty_id,
+        value: Binders::new(binders, AssociatedTyValueBound { ty }),
+        default: true,
+    })
+}
+
+/// Generate program clauses from the associated-const values
+/// found in impls of the given trait. i.e., if `trait_id` = Trait,
+/// then we would generate program clauses from each `const N: T = ...`
+/// found in any impls of `Trait`.
+#[instrument(level = "debug", skip(builder))]
+fn push_program_clauses_for_associated_const_values_in_impls_of<I: Interner>(
+    builder: &mut ClauseBuilder<'_, I>,
+    environment: &Environment<I>,
+    trait_id: TraitId<I>,
+    trait_parameters: &[GenericArg<I>],
+    binders: &CanonicalVarKinds<I>,
+) {
+    for impl_id in builder
+        .db
+        .impls_for_trait(trait_id, trait_parameters, binders)
+    {
+        let impl_datum = builder.db.impl_datum(impl_id);
+        if !impl_datum.is_positive() {
+            continue;
+        }
+
+        debug!(?impl_id);
+
+        for &acv_id in &impl_datum.associated_const_value_ids {
+            let acv = builder.db.associated_const_value(acv_id);
+            debug!(?acv_id, ?acv);
+            acv.to_program_clauses(builder, environment);
+        }
     }
 }
 
 fn push_alias_implemented_clause<I: Interner>(
     builder: &mut ClauseBuilder<'_, I>,
+    where_clause: fn(TraitRef<I>) -> WhereClause<I>,
     trait_ref: TraitRef<I>,
     alias: AliasTy<I>,
 ) {
@@ -769,9 +984,9 @@ fn push_alias_implemented_clause<I: Interner>(
                 substitution: fresh_self_subst,
             };
             builder.push_clause(
-                DomainGoal::Holds(WhereClause::Implemented(trait_ref.clone())),
+                DomainGoal::Holds(where_clause(trait_ref.clone())),
                 &[
-                    DomainGoal::Holds(WhereClause::Implemented(fresh_self_trait_ref)),
+                    DomainGoal::Holds(where_clause(fresh_self_trait_ref)),
                     DomainGoal::Holds(WhereClause::AliasEq(AliasEq {
                         alias: alias.clone(),
                         ty: bound_var,
@@ -923,7 +1138,8 @@ fn match_ty<I: Interner>(
                         WhereClause::Implemented(trait_ref) => {
                             vec![DomainGoal::WellFormed(WellFormed::Trait(trait_ref.clone()))]
                         }
-                        WhereClause::AliasEq(_)
+                        WhereClause::NotImplemented(_)
+                        | WhereClause::AliasEq(_)
                         | WhereClause::LifetimeOutlives(_)
                         | WhereClause::TypeOutlives(_) => vec![],
                     }
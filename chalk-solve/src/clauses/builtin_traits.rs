@@ -3,9 +3,13 @@ use crate::{CanonicalVarKinds, Interner, RustIrDatabase, TraitRef, WellKnownTrai
 use chalk_ir::{Floundered, Substitution, Ty};
 
 mod clone;
+mod coerce_unsized;
 mod copy;
+mod deref;
 mod discriminant_kind;
 mod fn_family;
+mod generator;
+mod pointee;
 mod sized;
 mod unsize;
 
@@ -42,10 +46,22 @@ pub fn add_builtin_program_clauses<I: Interner>(
             WellKnownTrait::Unsize => {
                 unsize::add_unsize_program_clauses(db, builder, trait_ref, ty)
             }
-            // DiscriminantKind is automatically implemented for all types
-            WellKnownTrait::DiscriminantKind => builder.push_fact(trait_ref),
+            WellKnownTrait::CoerceUnsized => {
+                coerce_unsized::add_coerce_unsized_program_clauses(db, builder, trait_ref, ty)
+            }
+            WellKnownTrait::DispatchFromDyn => {
+                coerce_unsized::add_dispatch_from_dyn_program_clauses(db, builder, trait_ref, ty)
+            }
+            WellKnownTrait::Deref => deref::add_deref_program_clauses(db, builder, self_ty),
+            WellKnownTrait::Generator => {
+                generator::add_generator_program_clauses(db, builder, self_ty)?
+            }
+            // DiscriminantKind and Pointee are automatically implemented for all types
+            WellKnownTrait::DiscriminantKind | WellKnownTrait::Pointee => {
+                builder.push_fact(trait_ref)
+            }
             // There are no builtin impls provided for the following traits:
-            WellKnownTrait::Unpin | WellKnownTrait::Drop | WellKnownTrait::CoerceUnsized => (),
+            WellKnownTrait::Unpin | WellKnownTrait::Drop => (),
         }
         Ok(())
     })
@@ -73,6 +89,26 @@ pub fn add_builtin_assoc_program_clauses<I: Interner>(
         WellKnownTrait::DiscriminantKind => {
             discriminant_kind::add_discriminant_clauses(db, builder, self_ty)
         }
+        WellKnownTrait::Deref => {
+            // If `self_ty` contains bound vars, we want to universally quantify them.
+            // `Generalize` collects them for us.
+            let generalized = generalize::Generalize::apply(db.interner(), self_ty);
+
+            builder.push_binders(generalized, |builder, self_ty| {
+                deref::add_deref_program_clauses(db, builder, self_ty);
+                Ok(())
+            })
+        }
+        WellKnownTrait::Pointee => pointee::add_pointee_program_clauses(db, builder, self_ty),
+        WellKnownTrait::Generator => {
+            // If `self_ty` contains bound vars, we want to universally quantify them.
+            // `Generalize` collects them for us.
+            let generalized = generalize::Generalize::apply(db.interner(), self_ty);
+
+            builder.push_binders(generalized, |builder, self_ty| {
+                generator::add_generator_program_clauses(db, builder, self_ty)
+            })
+        }
         _ => Ok(()),
     }
 }
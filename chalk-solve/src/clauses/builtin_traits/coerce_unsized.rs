@@ -0,0 +1,167 @@
+use crate::clauses::ClauseBuilder;
+use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
+use chalk_ir::{
+    cast::Cast, Goal, LifetimeOutlives, Mutability, Substitution, TraitId, Ty, TyKind, WhereClause,
+};
+
+/// Pushes `forall<U> { Implemented(Source: Trait<Target>) :- Implemented(T: Unsize<U>), extra }`,
+/// where `Source` is the self type of `trait_ref`, a pointer to `source_pointee` (`T`),
+/// and `Target` is the pointer that `target_ty` builds from the fresh `U`.
+fn push_unsizing_clause<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    trait_ref: &TraitRef<I>,
+    unsize_trait_id: TraitId<I>,
+    source_pointee: &Ty<I>,
+    target_ty: impl FnOnce(Ty<I>) -> Ty<I>,
+    extra_condition: Option<Goal<I>>,
+) {
+    let interner = db.interner();
+    let source_ty = trait_ref.self_type_parameter(interner);
+
+    builder.push_bound_ty(|builder, target_pointee| {
+        let unsize_goal: Goal<I> = TraitRef {
+            trait_id: unsize_trait_id,
+            substitution: Substitution::from_iter(
+                interner,
+                [source_pointee.clone(), target_pointee.clone()]
+                    .iter()
+                    .cloned(),
+            ),
+        }
+        .cast(interner);
+
+        let consequence = TraitRef {
+            trait_id: trait_ref.trait_id,
+            substitution: Substitution::from_iter(
+                interner,
+                [source_ty, target_ty(target_pointee)].iter().cloned(),
+            ),
+        };
+
+        builder.push_clause(
+            consequence,
+            Some(unsize_goal).into_iter().chain(extra_condition),
+        );
+    });
+}
+
+/// Pushes the `CoerceUnsized` impls that libcore provides for references and
+/// raw pointers:
+///
+/// ```notrust
+/// &'a T     -> &'b U, &'b mut U (if `&'a mut T`), *const U, *mut U (if `&'a mut T`)
+/// *const T  -> *const U
+/// *mut T    -> *const U, *mut U
+/// ```
+///
+/// each requiring `T: Unsize<U>`, and `'a: 'b` when coercing to a reference.
+/// A pointer may lose its mutability, but can never gain it.
+pub fn add_coerce_unsized_program_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    trait_ref: TraitRef<I>,
+    ty: TyKind<I>,
+) {
+    let interner = db.interner();
+
+    let unsize_trait_id = match db.well_known_trait_id(WellKnownTrait::Unsize) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let (source_mutability, source_lifetime, source_pointee) = match ty {
+        TyKind::Ref(mutability, lifetime, pointee) => (mutability, Some(lifetime), pointee),
+        TyKind::Raw(mutability, pointee) => (mutability, None, pointee),
+        _ => return,
+    };
+
+    let target_mutabilities: &[Mutability] = match source_mutability {
+        Mutability::Mut => &[Mutability::Not, Mutability::Mut],
+        Mutability::Not => &[Mutability::Not],
+    };
+
+    for &target_mutability in target_mutabilities {
+        if let Some(source_lifetime) = &source_lifetime {
+            builder.push_bound_lifetime(|builder, target_lifetime| {
+                let lifetime_outlives_goal: Goal<I> =
+                    WhereClause::LifetimeOutlives(LifetimeOutlives {
+                        a: source_lifetime.clone(),
+                        b: target_lifetime.clone(),
+                    })
+                    .cast(interner);
+
+                push_unsizing_clause(
+                    db,
+                    builder,
+                    &trait_ref,
+                    unsize_trait_id,
+                    &source_pointee,
+                    |target_pointee| {
+                        TyKind::Ref(target_mutability, target_lifetime, target_pointee)
+                            .intern(interner)
+                    },
+                    Some(lifetime_outlives_goal),
+                );
+            });
+        }
+
+        push_unsizing_clause(
+            db,
+            builder,
+            &trait_ref,
+            unsize_trait_id,
+            &source_pointee,
+            |target_pointee| TyKind::Raw(target_mutability, target_pointee).intern(interner),
+            None,
+        );
+    }
+}
+
+/// Pushes the `DispatchFromDyn` impls that libcore provides for references and
+/// raw pointers:
+///
+/// ```notrust
+/// &'a T     -> &'a U
+/// &'a mut T -> &'a mut U
+/// *const T  -> *const U
+/// *mut T    -> *mut U
+/// ```
+///
+/// each requiring `T: Unsize<U>`. Unlike `CoerceUnsized`, the kind of pointer
+/// stays the same.
+pub fn add_dispatch_from_dyn_program_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    trait_ref: TraitRef<I>,
+    ty: TyKind<I>,
+) {
+    let interner = db.interner();
+
+    let unsize_trait_id = match db.well_known_trait_id(WellKnownTrait::Unsize) {
+        Some(id) => id,
+        None => return,
+    };
+
+    match ty {
+        TyKind::Ref(mutability, lifetime, pointee) => push_unsizing_clause(
+            db,
+            builder,
+            &trait_ref,
+            unsize_trait_id,
+            &pointee,
+            |target_pointee| TyKind::Ref(mutability, lifetime, target_pointee).intern(interner),
+            None,
+        ),
+        TyKind::Raw(mutability, pointee) => push_unsizing_clause(
+            db,
+            builder,
+            &trait_ref,
+            unsize_trait_id,
+            &pointee,
+            |target_pointee| TyKind::Raw(mutability, target_pointee).intern(interner),
+            None,
+        ),
+        _ => {}
+    }
+}
@@ -0,0 +1,43 @@
+use crate::clauses::ClauseBuilder;
+use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
+use chalk_ir::{AliasTy, Normalize, ProjectionTy, Substitution, Ty, TyKind};
+
+/// References are the only types with a builtin `Deref` impl:
+///
+/// ```notrust
+/// Implemented(&'a T: Deref)
+/// Normalize(<&'a T as Deref>::Target -> T)
+/// ```
+///
+/// and the same for `&'a mut T`.
+pub fn add_deref_program_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    self_ty: Ty<I>,
+) {
+    let interner = db.interner();
+
+    let pointee = match self_ty.kind(interner) {
+        TyKind::Ref(_, _, pointee) => pointee.clone(),
+        _ => return,
+    };
+
+    let trait_id = db.well_known_trait_id(WellKnownTrait::Deref).unwrap();
+    let trait_datum = db.trait_datum(trait_id);
+    let substitution = Substitution::from1(interner, self_ty);
+
+    builder.push_fact(TraitRef {
+        trait_id,
+        substitution: substitution.clone(),
+    });
+
+    if let Some(&associated_ty_id) = trait_datum.associated_ty_ids.first() {
+        builder.push_fact(Normalize {
+            alias: AliasTy::Projection(ProjectionTy {
+                associated_ty_id,
+                substitution,
+            }),
+            ty: pointee,
+        });
+    }
+}
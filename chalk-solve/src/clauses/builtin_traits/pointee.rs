@@ -0,0 +1,175 @@
+use crate::clauses::{generalize, ClauseBuilder};
+use crate::rust_ir::{AdtKind, WellKnownAdt};
+use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
+use chalk_ir::{
+    AliasTy, AssocTypeId, Floundered, Normalize, ProjectionTy, Scalar, Substitution, Ty, TyKind,
+    TyVariableKind, UintTy,
+};
+
+fn metadata_projection<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    associated_ty_id: AssocTypeId<I>,
+    ty: Ty<I>,
+) -> AliasTy<I> {
+    AliasTy::Projection(ProjectionTy {
+        associated_ty_id,
+        substitution: Substitution::from1(db.interner(), ty),
+    })
+}
+
+/// Pushes `Normalize(<Self as Pointee>::Metadata -> M) :- Normalize(<Tail as Pointee>::Metadata -> M)`,
+/// for structs and tuples, whose metadata is the one of their last field.
+fn push_tail_metadata_clause<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    associated_ty_id: AssocTypeId<I>,
+    self_ty: Ty<I>,
+    tail_ty: Ty<I>,
+) {
+    builder.push_bound_ty(|builder, metadata| {
+        builder.push_clause(
+            Normalize {
+                alias: metadata_projection(db, associated_ty_id, self_ty),
+                ty: metadata.clone(),
+            },
+            Some(Normalize {
+                alias: metadata_projection(db, associated_ty_id, tail_ty),
+                ty: metadata,
+            }),
+        );
+    });
+}
+
+/// Pushes the builtin `Metadata` of `self_ty`, the metadata of a pointer to
+/// a value of that type:
+///
+/// ```notrust
+/// Normalize(<T as Pointee>::Metadata -> ())                    // if `T: Sized`
+/// Normalize(<[T] as Pointee>::Metadata -> usize)
+/// Normalize(<str as Pointee>::Metadata -> usize)
+/// Normalize(<dyn Trait as Pointee>::Metadata -> DynMetadata<dyn Trait>)
+/// ```
+///
+/// Structs and tuples have the metadata of their last field, and types we
+/// know nothing about (e.g. placeholders) have `()` only if they are `Sized`.
+/// `Pointee` itself is implemented for all types (in `add_builtin_program_clauses`).
+pub fn add_pointee_program_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    self_ty: Ty<I>,
+) -> Result<(), Floundered> {
+    let interner = db.interner();
+
+    // We can't enumerate the metadata of all types.
+    if let TyKind::BoundVar(_) | TyKind::InferenceVar(_, TyVariableKind::General) =
+        self_ty.kind(interner)
+    {
+        return Err(Floundered);
+    }
+
+    let trait_id = db.well_known_trait_id(WellKnownTrait::Pointee).unwrap();
+    let associated_ty_id = match db.trait_datum(trait_id).associated_ty_ids.first() {
+        Some(&id) => id,
+        None => return Ok(()),
+    };
+    let unit_ty = TyKind::Tuple(0, Substitution::empty(interner)).intern(interner);
+
+    // If `self_ty` contains bound vars, we want to universally quantify them.
+    // `Generalize` collects them for us.
+    let generalized = generalize::Generalize::apply(interner, self_ty);
+
+    builder.push_binders(generalized, |builder, self_ty| {
+        let metadata_ty = match self_ty.kind(interner) {
+            TyKind::Slice(_) | TyKind::Str => {
+                TyKind::Scalar(Scalar::Uint(UintTy::Usize)).intern(interner)
+            }
+            TyKind::Dyn(_) => match db.well_known_adt_id(WellKnownAdt::DynMetadata) {
+                Some(adt_id) => TyKind::Adt(adt_id, Substitution::from1(interner, self_ty.clone()))
+                    .intern(interner),
+                None => return Ok(()),
+            },
+            TyKind::Adt(adt_id, substitution) if db.adt_datum(*adt_id).kind == AdtKind::Struct => {
+                // We have a struct, so we're guaranteed one variant
+                let last_field_ty = db
+                    .adt_datum(*adt_id)
+                    .binders
+                    .map_ref(|b| b.variants.clone())
+                    .substitute(interner, substitution)
+                    .into_iter()
+                    .take(1)
+                    .flat_map(|mut v| v.fields.pop())
+                    .next();
+                match last_field_ty {
+                    Some(last_field_ty) => {
+                        push_tail_metadata_clause(
+                            db,
+                            builder,
+                            associated_ty_id,
+                            self_ty.clone(),
+                            last_field_ty,
+                        );
+                        return Ok(());
+                    }
+                    None => unit_ty,
+                }
+            }
+            TyKind::Tuple(arity, substitution) if *arity > 0 => {
+                let last_elem_ty = substitution
+                    .iter(interner)
+                    .last()
+                    .unwrap()
+                    .assert_ty_ref(interner)
+                    .clone();
+                push_tail_metadata_clause(
+                    db,
+                    builder,
+                    associated_ty_id,
+                    self_ty.clone(),
+                    last_elem_ty,
+                );
+                return Ok(());
+            }
+            TyKind::Alias(_)
+            | TyKind::Placeholder(_)
+            | TyKind::OpaqueType(..)
+            | TyKind::AssociatedType(..)
+            | TyKind::BoundVar(_)
+            | TyKind::InferenceVar(..)
+            | TyKind::Error => {
+                // These may or may not be `Sized`; if they are, their metadata is `()`.
+                if let Some(sized_trait_id) = db.well_known_trait_id(WellKnownTrait::Sized) {
+                    builder.push_clause(
+                        Normalize {
+                            alias: metadata_projection(db, associated_ty_id, self_ty.clone()),
+                            ty: unit_ty,
+                        },
+                        Some(TraitRef {
+                            trait_id: sized_trait_id,
+                            substitution: Substitution::from1(interner, self_ty.clone()),
+                        }),
+                    );
+                }
+                return Ok(());
+            }
+            TyKind::Adt(..)
+            | TyKind::Tuple(..)
+            | TyKind::Scalar(_)
+            | TyKind::Array(..)
+            | TyKind::Raw(..)
+            | TyKind::Ref(..)
+            | TyKind::Never
+            | TyKind::FnDef(..)
+            | TyKind::Closure(..)
+            | TyKind::Generator(..)
+            | TyKind::GeneratorWitness(..)
+            | TyKind::Foreign(_)
+            | TyKind::Function(..) => unit_ty,
+        };
+
+        builder.push_fact(Normalize {
+            alias: metadata_projection(db, associated_ty_id, self_ty.clone()),
+            ty: metadata_ty,
+        });
+        Ok(())
+    })
+}
@@ -1,15 +1,18 @@
 use std::collections::HashSet;
 use std::iter;
 
+use crate::clauses::dyn_ty::super_traits;
 use crate::clauses::ClauseBuilder;
 use crate::rust_ir::AdtKind;
+use crate::split::Split;
 use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
 use chalk_ir::{
     cast::Cast,
     interner::HasInterner,
     visit::{ControlFlow, SuperVisit, Visit, Visitor},
-    Binders, Const, ConstValue, DebruijnIndex, DomainGoal, DynTy, EqGoal, Goal, LifetimeOutlives,
-    QuantifiedWhereClauses, Substitution, TraitId, Ty, TyKind, TypeOutlives, WhereClause,
+    AliasEq, AliasTy, Binders, Const, ConstValue, DebruijnIndex, DomainGoal, DynTy, EqGoal, Goal,
+    LifetimeOutlives, QuantifiedWhereClause, QuantifiedWhereClauses, Substitution, TraitId, Ty,
+    TyKind, TypeOutlives, WhereClause,
 };
 
 struct UnsizeParameterCollector<'a, I: Interner> {
@@ -160,6 +163,143 @@ fn auto_trait_ids<'a, I: Interner>(
         .filter(move |&id| db.trait_datum(id).is_auto_trait())
 }
 
+/// Views the source trait object `dyn Sub + AutoX` (with bounds `bounds_a`)
+/// as an object of its supertrait `super_trait_id`, for upcasting it to the
+/// target `dyn Super + AutoY` (with bounds `bounds_b`).
+///
+/// Returns one set of bounds for every way `Super` appears among the
+/// supertraits of `Sub`. The bounds follow those of the target: the principal
+/// is the supertrait reference, auto traits are copied from the target (the
+/// caller checks that the source has them) and every associated type binding
+/// is taken from the source, either from its own bindings or from the where
+/// clauses of `Sub` and its supertraits (as in `trait Sub: Super<Assoc = u32>`).
+/// If the source has no value for one of the bindings of the target, that way
+/// of upcasting is skipped.
+fn upcast_bounds<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    bounds_a: &Binders<QuantifiedWhereClauses<I>>,
+    bounds_b: &Binders<QuantifiedWhereClauses<I>>,
+    super_trait_id: TraitId<I>,
+) -> Vec<Binders<QuantifiedWhereClauses<I>>> {
+    let interner = db.interner();
+
+    let principal_a =
+        bounds_a
+            .skip_binders()
+            .iter(interner)
+            .find(|bound| match bound.skip_binders() {
+                WhereClause::Implemented(trait_ref) => {
+                    !db.trait_datum(trait_ref.trait_id).is_auto_trait()
+                }
+                _ => false,
+            });
+    let principal_a = match principal_a {
+        Some(principal_a) => principal_a,
+        None => return vec![],
+    };
+
+    // All supertraits of the source principal, including itself. Each of them
+    // is bound by the binders of the principal, followed by the binders of
+    // the supertrait where clauses it was found through.
+    let (binders, super_trait_refs) = principal_a
+        .map_ref(|bound| match bound {
+            WhereClause::Implemented(trait_ref) => {
+                super_traits(db, trait_ref.trait_id).substitute(interner, &trait_ref.substitution)
+            }
+            _ => unreachable!(),
+        })
+        .into();
+    let super_trait_refs: Vec<Binders<TraitRef<I>>> = super_trait_refs
+        .into_iter()
+        .map(|super_trait_ref| {
+            Binders::new(binders.clone(), super_trait_ref).fuse_binders(interner)
+        })
+        .collect();
+
+    let is_alias_eq =
+        |bound: &QuantifiedWhereClause<I>| matches!(bound.skip_binders(), WhereClause::AliasEq(_));
+    let mut alias_eqs: Vec<QuantifiedWhereClause<I>> = bounds_a
+        .skip_binders()
+        .iter(interner)
+        .filter(|bound| is_alias_eq(bound))
+        .cloned()
+        .collect();
+    for super_trait_ref in &super_trait_refs {
+        let trait_datum = db.trait_datum(super_trait_ref.skip_binders().trait_id);
+        let (binders, where_clauses) = super_trait_ref
+            .map_ref(|super_trait_ref| {
+                trait_datum
+                    .binders
+                    .map_ref(|bound| {
+                        bound
+                            .where_clauses
+                            .iter()
+                            .filter(|where_clause| is_alias_eq(where_clause))
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    })
+                    .substitute(interner, &super_trait_ref.substitution)
+            })
+            .into();
+        alias_eqs.extend(where_clauses.into_iter().map(|where_clause| {
+            Binders::new(binders.clone(), where_clause).fuse_binders(interner)
+        }));
+    }
+    let associated_ty_id = |bound: &QuantifiedWhereClause<I>| match bound.skip_binders() {
+        WhereClause::AliasEq(AliasEq {
+            alias: AliasTy::Projection(projection),
+            ..
+        }) => Some(projection.associated_ty_id),
+        _ => None,
+    };
+    // The trait reference a binding is for, e.g. `Self: Trait<u32>` for
+    // `<Self as Trait<u32>>::Item = u8`. A trait may be a supertrait with
+    // different parameters, each with its own bindings.
+    let binding_trait_ref = |bound: &QuantifiedWhereClause<I>| match bound.skip_binders() {
+        WhereClause::AliasEq(AliasEq {
+            alias: AliasTy::Projection(projection),
+            ..
+        }) => Some(db.trait_ref_from_projection(projection)),
+        _ => None,
+    };
+
+    let mut upcast_bounds = vec![];
+    for super_trait_ref in &super_trait_refs {
+        if super_trait_ref.skip_binders().trait_id != super_trait_id {
+            continue;
+        }
+        let bounds: Option<Vec<_>> = bounds_b
+            .skip_binders()
+            .iter(interner)
+            .map(|bound_b| match bound_b.skip_binders() {
+                WhereClause::Implemented(trait_ref) if trait_ref.trait_id == super_trait_id => {
+                    Some(super_trait_ref.clone().map(WhereClause::Implemented))
+                }
+                WhereClause::AliasEq(_) => alias_eqs
+                    .iter()
+                    .find(|alias_eq| {
+                        associated_ty_id(alias_eq).is_some()
+                            && associated_ty_id(alias_eq) == associated_ty_id(bound_b)
+                            && binding_trait_ref(alias_eq).as_ref()
+                                == Some(super_trait_ref.skip_binders())
+                    })
+                    .cloned(),
+                _ => Some(bound_b.clone()),
+            })
+            .collect();
+        if let Some(bounds) = bounds {
+            let bounds = Binders::new(
+                bounds_a.binders.clone(),
+                QuantifiedWhereClauses::from_iter(interner, bounds),
+            );
+            if !upcast_bounds.contains(&bounds) {
+                upcast_bounds.push(bounds);
+            }
+        }
+    }
+    upcast_bounds
+}
+
 pub fn add_unsize_program_clauses<I: Interner>(
     db: &dyn RustIrDatabase<I>,
     builder: &mut ClauseBuilder<'_, I>,
@@ -205,10 +345,9 @@ pub fn add_unsize_program_clauses<I: Interner>(
             let auto_trait_ids_a: Vec<_> = auto_trait_ids(db, bounds_a).collect();
             let auto_trait_ids_b: Vec<_> = auto_trait_ids(db, bounds_b).collect();
 
-            let may_apply = principal_a == principal_b
-                && auto_trait_ids_b
-                    .iter()
-                    .all(|id_b| auto_trait_ids_a.iter().any(|id_a| id_a == id_b));
+            let may_apply = auto_trait_ids_b
+                .iter()
+                .all(|id_b| auto_trait_ids_a.iter().any(|id_a| id_a == id_b));
 
             if !may_apply {
                 return;
@@ -230,15 +369,16 @@ pub fn add_unsize_program_clauses<I: Interner>(
             // done for quite some time) before we are firmly comfortable
             // with what our behavior should be there. -nikomatsakis
             // ------------------
-
-            // Construct a new trait object type by taking the source ty,
-            // filtering out auto traits of source that are not present in target
-            // and changing source lifetime to target lifetime.
             //
-            // In order for the coercion to be valid, this new type
-            // should be equal to target type.
-            let new_source_ty = TyKind::Dyn(DynTy {
-                bounds: bounds_a.map_ref(|bounds| {
+            // The principals may still differ when the target principal is a
+            // supertrait of the source one (trait object upcasting); then we
+            // compare against the bounds of the source viewed as an object of
+            // that supertrait, see `upcast_bounds`.
+            let new_source_bounds = if principal_a == principal_b {
+                // Construct a new trait object type by taking the source ty,
+                // filtering out auto traits of source that are not present in target
+                // and changing source lifetime to target lifetime.
+                vec![bounds_a.map_ref(|bounds| {
                     QuantifiedWhereClauses::from_iter(
                         interner,
                         bounds.iter(interner).filter(|bound| {
@@ -253,26 +393,40 @@ pub fn add_unsize_program_clauses<I: Interner>(
                             auto_trait_ids_b.iter().any(|&id_b| id_b == trait_id)
                         }),
                     )
-                }),
-                lifetime: lifetime_b.clone(),
-            })
-            .intern(interner);
+                })]
+            } else {
+                match principal_b {
+                    Some(principal_b) => upcast_bounds(db, bounds_a, bounds_b, principal_b),
+                    None => return,
+                }
+            };
 
-            // Check that new source is equal to target
-            let eq_goal = EqGoal {
-                a: new_source_ty.cast(interner),
-                b: target_ty.clone().cast(interner),
-            }
-            .cast(interner);
+            // In order for the coercion to be valid, the new source type
+            // should be equal to target type.
+            for bounds in new_source_bounds {
+                let new_source_ty = TyKind::Dyn(DynTy {
+                    bounds,
+                    lifetime: lifetime_b.clone(),
+                })
+                .intern(interner);
+
+                // Check that new source is equal to target
+                let eq_goal = EqGoal {
+                    a: new_source_ty.cast(interner),
+                    b: target_ty.clone().cast(interner),
+                }
+                .cast(interner);
 
-            // Check that source lifetime outlives target lifetime
-            let lifetime_outlives_goal: Goal<I> = WhereClause::LifetimeOutlives(LifetimeOutlives {
-                a: lifetime_a.clone(),
-                b: lifetime_b.clone(),
-            })
-            .cast(interner);
+                // Check that source lifetime outlives target lifetime
+                let lifetime_outlives_goal: Goal<I> =
+                    WhereClause::LifetimeOutlives(LifetimeOutlives {
+                        a: lifetime_a.clone(),
+                        b: lifetime_b.clone(),
+                    })
+                    .cast(interner);
 
-            builder.push_clause(trait_ref, [eq_goal, lifetime_outlives_goal].iter());
+                builder.push_clause(trait_ref.clone(), [eq_goal, lifetime_outlives_goal].iter());
+            }
         }
 
         // T -> dyn Trait + 'a
@@ -74,6 +74,7 @@ pub(super) fn build_dyn_self_ty_clauses<I: Interner>(
                 }
                 // Associated item bindings are just taken as facts (?)
                 WhereClause::AliasEq(_) => builder.push_fact(wc),
+                WhereClause::NotImplemented(..) => {}
                 WhereClause::LifetimeOutlives(..) => {}
                 WhereClause::TypeOutlives(..) => {}
             });
@@ -164,6 +165,7 @@ pub fn super_traits<I: Interner>(
                                 }
                                 Some(tr.clone())
                             }
+                            WhereClause::NotImplemented(_) => None,
                             WhereClause::AliasEq(_) => None,
                             WhereClause::LifetimeOutlives(..) => None,
                             WhereClause::TypeOutlives(..) => None,
@@ -1,7 +1,10 @@
 use crate::clauses::builder::ClauseBuilder;
+use crate::coherence::SpecializationPriorities;
 use crate::rust_ir::*;
 use crate::split::Split;
+use crate::RustIrDatabase;
 use chalk_ir::cast::{Cast, Caster};
+use chalk_ir::fold::shift::Shift;
 use chalk_ir::interner::Interner;
 use chalk_ir::*;
 use std::iter;
@@ -24,28 +27,38 @@ impl<I: Interner> ToProgramClauses<I> for ImplDatum<I> {
     /// }
     /// ```
     ///
-    /// For a negative impl like `impl... !Clone for ...`, however, we
-    /// generate nothing -- this is just a way to *opt out* from the
-    /// default auto trait impls, it doesn't have any positive effect
-    /// on its own.
+    /// For a negative impl like `impl<T: Clone> !Send for Rc<T> { ... }`,
+    /// however, we generate no `Implemented` clause -- besides opting out
+    /// from the default auto trait impls, it is a promise that the type
+    /// doesn't implement the trait, which we record as:
+    ///
+    /// ```notrust
+    /// -- Rule NotImplemented-From-Impl
+    /// forall<T> {
+    ///     NotImplemented(Rc<T>: Send) :- Implemented(T: Clone).
+    /// }
+    /// ```
     fn to_program_clauses(
         &self,
         builder: &mut ClauseBuilder<'_, I>,
         _environment: &Environment<I>,
     ) {
-        if self.is_positive() {
-            let binders = self.binders.clone();
-            builder.push_binders(
-                binders,
-                |builder,
-                 ImplDatumBound {
-                     trait_ref,
-                     where_clauses,
-                 }| {
+        let is_positive = self.is_positive();
+        let binders = self.binders.clone();
+        builder.push_binders(
+            binders,
+            |builder,
+             ImplDatumBound {
+                 trait_ref,
+                 where_clauses,
+             }| {
+                if is_positive {
                     builder.push_clause(trait_ref, where_clauses);
-                },
-            );
-        }
+                } else {
+                    builder.push_clause(WhereClause::NotImplemented(trait_ref), where_clauses);
+                }
+            },
+        );
     }
 }
 
@@ -75,6 +88,32 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
     ///         Implemented(Iter<'a, T>: 'a).   // (2)
     /// }
     /// ```
+    ///
+    /// If the value is declared `default` and the impl is specialized by
+    /// other impls, then the value may be overridden, so we only normalize
+    /// to it when `Reveal` is in force. Moreover, the value only applies
+    /// when no more specialized impl that provides its own value applies:
+    ///
+    /// ```notrust
+    /// impl<T> Iterable for T {
+    ///     default type IntoIter<'a> = DefaultIter<'a, T>;
+    /// }
+    ///
+    /// impl Iterable for u32 {
+    ///     type IntoIter<'a> = Iter<'a, u32>;
+    /// }
+    /// ```
+    ///
+    /// generates, for the first value:
+    ///
+    /// ```notrust
+    /// forall<'a, T> {
+    ///     Normalize(<T as Iterable>::IntoIter<'a> -> DefaultIter<'a, T>>) :-
+    ///         Implemented(DefaultIter<'a, T>: 'a),
+    ///         Reveal,                      // (3)
+    ///         not { exists<> { T = u32 } }.  // (4)
+    /// }
+    /// ```
     fn to_program_clauses(
         &self,
         builder: &mut ClauseBuilder<'_, I>,
@@ -123,17 +162,184 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
             //         Implemented(Iter<'a, T>: 'a).   // (2)
             // }
             // ```
+            let conditions = impl_where_clauses
+                .chain(assoc_ty_where_clauses)
+                .casted(interner);
+
+            // A `default` value of a specialized impl additionally requires:
+            //
+            // 3. that the value is revealed, since it may be overridden
+            // 4. that no more specialized impl overrides it
+            let specialization_conditions = if self.default {
+                let priorities = builder.db.specialization_priorities(impl_datum.trait_id());
+                if priorities.is_final(self.impl_id) {
+                    vec![]
+                } else {
+                    let trait_ref = builder.db.trait_ref_from_projection(&projection);
+                    let mut overriding_impls = vec![];
+                    self.push_overriding_impls(
+                        builder.db,
+                        &priorities,
+                        self.impl_id,
+                        &mut overriding_impls,
+                    );
+                    iter::once(DomainGoal::Reveal.cast(interner))
+                        .chain(overriding_impls.into_iter().map(|impl_id| {
+                            impl_applies_goal(builder.db, impl_id, &trait_ref).negate(interner)
+                        }))
+                        .collect()
+                }
+            } else {
+                vec![]
+            };
+
             builder.push_clause(
                 Normalize {
                     alias: AliasTy::Projection(projection.clone()),
                     ty: assoc_ty_value.ty,
                 },
-                impl_where_clauses.chain(assoc_ty_where_clauses),
+                conditions.chain(specialization_conditions),
+            );
+        });
+    }
+}
+
+impl<I: Interner> ToProgramClauses<I> for AssociatedConstValue<I> {
+    /// Given the following trait:
+    ///
+    /// ```notrust
+    /// trait Sized {
+    ///     const SIZE: usize;
+    /// }
+    /// ```
+    ///
+    /// Then for the following impl:
+    /// ```notrust
+    /// impl<T> Sized for Vec<T> where T: Clone {
+    ///     const SIZE: usize = 3;
+    /// }
+    /// ```
+    ///
+    /// we generate:
+    ///
+    /// ```notrust
+    /// -- Rule NormalizeConst-From-Impl
+    /// forall<T> {
+    ///     NormalizeConst(<Vec<T> as Sized>::SIZE -> 3) :-
+    ///         Implemented(T: Clone).
+    /// }
+    /// ```
+    fn to_program_clauses(
+        &self,
+        builder: &mut ClauseBuilder<'_, I>,
+        _environment: &Environment<I>,
+    ) {
+        let impl_datum = builder.db.impl_datum(self.impl_id);
+
+        builder.push_binders(self.value.clone(), |builder, value| {
+            let interner = builder.db.interner();
+            let impl_params = builder.substitution_in_scope();
+
+            // The projection is `<Vec<!T> as Sized>::SIZE`: its parameters
+            // are those of the trait reference of the impl.
+            let alias = ProjectionConst {
+                associated_const_id: self.associated_const_id,
+                substitution: impl_datum
+                    .binders
+                    .map_ref(|b| &b.trait_ref.substitution)
+                    .cloned()
+                    .substitute(interner, &impl_params),
+            };
+
+            let impl_where_clauses = impl_datum
+                .binders
+                .map_ref(|b| &b.where_clauses)
+                .into_iter()
+                .map(|wc| wc.cloned().substitute(interner, &impl_params));
+
+            builder.push_clause(
+                NormalizeConst { alias, value },
+                impl_where_clauses.casted::<Goal<_>>(interner),
             );
         });
     }
 }
 
+impl<I: Interner> AssociatedTyValue<I> {
+    /// Collects the impls that specialize `impl_id` and provide their own
+    /// value for this associated type. Impls that specialize those are
+    /// already covered by them, and so are not collected.
+    fn push_overriding_impls(
+        &self,
+        db: &dyn RustIrDatabase<I>,
+        priorities: &SpecializationPriorities<I>,
+        impl_id: ImplId<I>,
+        overriding_impls: &mut Vec<ImplId<I>>,
+    ) {
+        for &specializing_impl in priorities.specializing_impls(impl_id) {
+            if overriding_impls.contains(&specializing_impl) {
+                continue;
+            }
+            let impl_datum = db.impl_datum(specializing_impl);
+            if !impl_datum.is_positive() {
+                continue;
+            }
+            let overrides = impl_datum
+                .associated_ty_value_ids
+                .iter()
+                .any(|&id| db.associated_ty_value(id).associated_ty_id == self.associated_ty_id);
+            if overrides {
+                overriding_impls.push(specializing_impl);
+            } else {
+                self.push_overriding_impls(db, priorities, specializing_impl, overriding_impls);
+            }
+        }
+    }
+}
+
+/// Creates a goal which holds if the impl `impl_id` applies to `trait_ref`:
+///
+/// ```notrust
+/// exists<P0..Pn> {
+///     T0 = U0, ..., Tm = Um,
+///     WC
+/// }
+/// ```
+///
+/// where `impl<P0..Pn> SomeTrait<T1..Tm> for T0 where WC` is the impl, and
+/// `trait_ref` is `U0: SomeTrait<U1..Um>`.
+fn impl_applies_goal<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    impl_id: ImplId<I>,
+    trait_ref: &TraitRef<I>,
+) -> Goal<I> {
+    let interner = db.interner();
+    let impl_datum = db.impl_datum(impl_id);
+    let (binders, bound) = impl_datum.binders.as_ref().into();
+
+    // The parameters of `trait_ref` are referenced from within the
+    // `exists` binder, so they must be shifted in.
+    let params_goals = bound
+        .trait_ref
+        .substitution
+        .iter(interner)
+        .cloned()
+        .zip(
+            trait_ref
+                .substitution
+                .iter(interner)
+                .map(|param| param.clone().shifted_in(interner)),
+        )
+        .map(|(a, b)| GoalData::EqGoal(EqGoal { a, b }).intern(interner));
+    let where_clause_goals = bound.where_clauses.iter().cloned().casted(interner);
+
+    Goal::all(interner, params_goals.chain(where_clause_goals)).quantify(
+        interner,
+        QuantifierKind::Exists,
+        binders.clone(),
+    )
+}
+
 impl<I: Interner> ToProgramClauses<I> for OpaqueTyDatum<I> {
     /// Given `opaque type T<U>: A + B = HiddenTy where U: C;`, we generate:
     ///
@@ -554,6 +760,7 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
     ///         IsUpstream(T),
     ///         IsUpstream(U),
     ///         IsUpstream(V),
+    ///         not { NotImplemented(Self: Foo<T, U, V>) }, // no negative impl
     ///         CannotProve.              // returns ambiguous
     /// }
     /// ```
@@ -617,6 +824,12 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
                 // for logical consistency. But really, it's not needed and could be
                 // removed.
 
+                // A negative impl is a promise that the impl will never be
+                // added, so no compatible world can contain it.
+                let not_implemented: Goal<_> = WhereClause::NotImplemented(trait_ref.clone())
+                    .cast::<Goal<_>>(interner)
+                    .negate(interner);
+
                 // Drop trait can't have downstream implementation because it can only
                 // be implemented with the same genericity as the struct definition,
                 // i.e. Drop implementation for `struct S<T: Eq> {}` is forced to be
@@ -640,6 +853,7 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
                                     DomainGoal::DownstreamType(type_parameters[i].clone())
                                         .cast(interner),
                                 ))
+                                .chain(iter::once(not_implemented.clone()))
                                 .chain(iter::once(GoalData::CannotProve.intern(interner))),
                         );
                     }
@@ -660,6 +874,7 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
                                     .type_parameters(interner)
                                     .map(|ty| DomainGoal::IsUpstream(ty).cast(interner)),
                             )
+                            .chain(iter::once(not_implemented))
                             .chain(iter::once(GoalData::CannotProve.intern(interner))),
                     );
                 }
@@ -681,6 +896,20 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
                 }
             }
 
+            // A trait alias has no impls: a type implements it exactly
+            // when its expansion holds, so given
+            // `trait Alias<T> = Foo<T> where T: Baz;` we generate
+            //
+            // ```
+            // Implemented(Self: Alias<T>) :- Implemented(Self: Foo<T>), Implemented(T: Baz)
+            // ```
+            if self.is_trait_alias() {
+                builder.push_clause(
+                    trait_ref.clone(),
+                    where_clauses.iter().cloned().casted::<Goal<_>>(interner),
+                );
+            }
+
             // Reverse implied bound rules: given (e.g.) `trait Foo: Bar + Baz`,
             // we create rules like:
             //
@@ -794,6 +1023,7 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyDatum<I> {
              AssociatedTyDatumBound {
                  where_clauses,
                  bounds,
+                 default: _,
              }| {
                 let substitution = builder.substitution_in_scope();
 
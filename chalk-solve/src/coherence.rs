@@ -43,12 +43,16 @@ impl<I: Interner> std::error::Error for CoherenceError<I> {}
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct SpecializationPriorities<I: Interner> {
     map: BTreeMap<ImplId<I>, SpecializationPriority>,
+
+    /// For each impl, the impls that directly specialize it.
+    specializations: BTreeMap<ImplId<I>, Vec<ImplId<I>>>,
 }
 
 impl<I: Interner> SpecializationPriorities<I> {
     pub fn new() -> Self {
         Self {
             map: BTreeMap::new(),
+            specializations: BTreeMap::new(),
         }
     }
 
@@ -57,11 +61,47 @@ impl<I: Interner> SpecializationPriorities<I> {
         self.map[&impl_id]
     }
 
-    /// Store the priority of an impl (used during construction).
-    /// Panics if we have already stored the priority for this impl.
+    /// Store the priority of an impl (used during construction). An impl
+    /// that specializes several impls is reached once through each of them,
+    /// and keeps the highest priority.
     fn insert(&mut self, impl_id: ImplId<I>, p: SpecializationPriority) {
-        let old_value = self.map.insert(impl_id, p);
-        assert!(old_value.is_none());
+        let priority = self.map.entry(impl_id).or_default();
+        *priority = (*priority).max(p);
+    }
+
+    /// The impls that specialize the given impl (empty if the impl is not
+    /// specialized, or not in the set).
+    pub fn specializing_impls(&self, impl_id: ImplId<I>) -> &[ImplId<I>] {
+        self.specializations
+            .get(&impl_id)
+            .map_or(&[], |impls| &impls[..])
+    }
+
+    /// An impl is final if no other impl specializes it.
+    pub fn is_final(&self, impl_id: ImplId<I>) -> bool {
+        self.specializing_impls(impl_id).is_empty()
+    }
+
+    /// Whether the impl specializes some other impl.
+    pub fn specializes_other_impl(&self, impl_id: ImplId<I>) -> bool {
+        self.specializations
+            .values()
+            .any(|impls| impls.contains(&impl_id))
+    }
+
+    /// Sorts impls so that impls with higher priority come first. Impls
+    /// that are not in the set come last, in their original order.
+    pub fn sort_by_priority(&self, impls: &mut [ImplId<I>]) {
+        impls.sort_by_key(|impl_id| std::cmp::Reverse(self.map.get(impl_id).copied()));
+    }
+
+    /// Record that `more_special` specializes `less_special` (used during
+    /// construction).
+    fn insert_specialization(&mut self, less_special: ImplId<I>, more_special: ImplId<I>) {
+        let impls = self.specializations.entry(less_special).or_default();
+        if !impls.contains(&more_special) {
+            impls.push(more_special);
+        }
     }
 }
 
@@ -129,15 +169,14 @@ where
         map: &mut SpecializationPriorities<I>,
     ) {
         // Get the impl datum recorded at this node and reset its priority
-        {
-            let impl_id = forest
-                .node_weight(idx)
-                .expect("index should be a valid index into graph");
-            map.insert(*impl_id, SpecializationPriority(p));
-        }
+        let impl_id = *forest
+            .node_weight(idx)
+            .expect("index should be a valid index into graph");
+        map.insert(impl_id, SpecializationPriority(p));
 
         // Visit all children of this node, setting their priority to this + 1
         for child_idx in forest.neighbors(idx) {
+            map.insert_specialization(impl_id, forest[child_idx]);
             self.set_priorities(child_idx, forest, p + 1, map);
         }
     }
@@ -137,7 +137,7 @@ impl<I: Interner> CoherenceSolver<'_, I> {
             // to overlap
             Some(Solution::Unique(_)) => true,
             // Goal was ambiguous, so there *may* be overlap
-            Some(Solution::Ambig(_)) |
+            Some(Solution::Ambig(..)) |
             // Goal cannot be proven, so there is some impl that causes overlap
             None => false,
         };
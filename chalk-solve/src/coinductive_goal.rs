@@ -23,6 +23,7 @@ impl<I: Interner> IsCoinductive<I> for Goal<I> {
                     db.trait_datum(tr.trait_id).is_auto_trait()
                         || db.trait_datum(tr.trait_id).is_coinductive_trait()
                 }
+                WhereClause::NotImplemented(..) => false,
                 WhereClause::AliasEq(..) => false,
                 WhereClause::LifetimeOutlives(..) => false,
                 WhereClause::TypeOutlives(..) => false,
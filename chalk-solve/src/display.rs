@@ -150,6 +150,15 @@ fn display_self_where_clauses_as_bounds<'a, I: Interner>(
                                 &trait_ref.substitution.as_slice(interner)[1..],
                             )
                             .fmt(f),
+                            WhereClause::NotImplemented(trait_ref) => write!(
+                                f,
+                                "!{}",
+                                display_type_with_generics(
+                                    s,
+                                    trait_ref.trait_id,
+                                    &trait_ref.substitution.as_slice(interner)[1..],
+                                )
+                            ),
                             WhereClause::AliasEq(alias_eq) => match &alias_eq.alias {
                                 AliasTy::Projection(projection_ty) => {
                                     let (assoc_ty_datum, trait_params, assoc_type_params) =
@@ -88,6 +88,11 @@ impl<I: Interner> RenderAsRust<I> for AdtDatum<I> {
             }
         );
 
+        // lang item
+        if s.db().well_known_adt_id(WellKnownAdt::DynMetadata) == Some(self.id) {
+            writeln!(f, "#[lang(dyn_metadata)]")?;
+        }
+
         // repr
         let repr = s.db().adt_repr(self.id);
 
@@ -107,7 +112,12 @@ impl<I: Interner> RenderAsRust<I> for AdtDatum<I> {
             AdtKind::Enum => write!(f, "enum {}", self.id.display(s),)?,
             AdtKind::Union => write!(f, "union {}", self.id.display(s),)?,
         }
-        write_joined_non_empty_list!(f, "<{}>", s.binder_var_display(&self.binders.binders), ", ")?;
+        write_joined_non_empty_list!(
+            f,
+            "<{}>",
+            s.binder_var_display_with_defaults(&self.binders.binders, &value.param_defaults),
+            ", "
+        )?;
 
         // where clauses
         if !value.where_clauses.is_empty() {
@@ -170,17 +180,21 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
         let s = &s.add_debrujin_index(Some(0));
         let value = self.binders.skip_binders();
 
-        // flags
+        // flags (trait aliases use their own syntax rather than an attribute)
         write_flags!(
             f,
-            self.flags,
+            TraitFlags {
+                alias: false,
+                ..self.flags
+            },
             TraitFlags {
                 auto,
                 marker,
                 upstream,
                 fundamental,
                 non_enumerable,
-                coinductive
+                coinductive,
+                alias
             }
         );
 
@@ -203,15 +217,33 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
                 WellKnownTrait::Unpin => "unpin",
                 WellKnownTrait::CoerceUnsized => "coerce_unsized",
                 WellKnownTrait::DiscriminantKind => "discriminant_kind",
+                WellKnownTrait::DispatchFromDyn => "dispatch_from_dyn",
+                WellKnownTrait::Deref => "deref",
+                WellKnownTrait::Pointee => "pointee",
+                WellKnownTrait::Generator => "generator",
             };
             writeln!(f, "#[lang({})]", name)?;
         }
 
         // trait declaration
-        let binders = s.binder_var_display(&self.binders.binders).skip(1);
+        let binders = s
+            .binder_var_display_with_defaults(&self.binders.binders, &value.param_defaults)
+            .skip(1);
         write!(f, "trait {}", self.id.display(s))?;
         write_joined_non_empty_list!(f, "<{}>", binders, ", ")?;
 
+        // The bounds of a trait alias were lowered to where clauses on
+        // `Self`, so we write them all as where clauses.
+        if self.is_trait_alias() {
+            write!(f, " =")?;
+            if !value.where_clauses.is_empty() {
+                let s = &s.add_indent();
+                write!(f, "\nwhere\n{}\n", value.where_clauses.display(s))?;
+            }
+            write!(f, ";")?;
+            return Ok(());
+        }
+
         // where clauses
         if !value.where_clauses.is_empty() {
             let s = &s.add_indent();
@@ -226,10 +258,16 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
         write_joined_non_empty_list!(
             f,
             "\n{}\n",
-            self.associated_ty_ids.iter().map(|assoc_ty_id| {
-                let assoc_ty_data = s.db().associated_ty_data(*assoc_ty_id);
-                format!("{}{}", s.indent(), (*assoc_ty_data).display(s))
-            }),
+            self.associated_ty_ids
+                .iter()
+                .map(|assoc_ty_id| {
+                    let assoc_ty_data = s.db().associated_ty_data(*assoc_ty_id);
+                    format!("{}{}", s.indent(), (*assoc_ty_data).display(s))
+                })
+                .chain(self.associated_const_ids.iter().map(|assoc_const_id| {
+                    let assoc_const_data = s.db().associated_const_data(*assoc_const_id);
+                    format!("{}{}", s.indent(), (*assoc_const_data).display(s))
+                })),
             "\n"
         )?;
         write!(f, "}}")?;
@@ -303,7 +341,21 @@ impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
                     .display(s)
                     .to_string()
             });
-            write_joined_non_empty_list!(f, "\n{}\n", assoc_ty_values, "\n")?;
+            let assoc_const_values =
+                self.associated_const_value_ids
+                    .iter()
+                    .map(|assoc_const_value| {
+                        s.db()
+                            .associated_const_value(*assoc_const_value)
+                            .display(s)
+                            .to_string()
+                    });
+            write_joined_non_empty_list!(
+                f,
+                "\n{}\n",
+                assoc_ty_values.chain(assoc_const_values),
+                "\n"
+            )?;
         }
         write!(f, "}}")?;
         Ok(())
@@ -392,6 +444,12 @@ impl<I: Interner> RenderAsRust<I> for AssociatedTyDatum<I> {
             .format(" + ");
         write!(f, "{}", bounds)?;
 
+        // default is 'V'
+        // type Foo<...>: ... = V;
+        if let Some(default) = &datum_bounds.default {
+            write!(f, " = {}", default.display(s))?;
+        }
+
         // where_clause is 'X: Y, Z: D'
         // type Foo<...>: ... where X: Y, Z: D;
 
@@ -440,13 +498,62 @@ impl<I: Interner> RenderAsRust<I> for AssociatedTyValue<I> {
             .db()
             .split_associated_ty_value_parameters(&display_params, self);
 
-        write!(f, "{}type {}", s.indent(), assoc_ty_data.id.display(s))?;
+        write!(f, "{}", s.indent())?;
+        if self.default {
+            write!(f, "default ")?;
+        }
+        write!(f, "type {}", assoc_ty_data.id.display(s))?;
         write_joined_non_empty_list!(f, "<{}>", &assoc_ty_value_display, ", ")?;
         write!(f, " = {};", value.ty.display(s))?;
         Ok(())
     }
 }
 
+impl<I: Interner> RenderAsRust<I> for AssociatedConstDatum<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        // The binders of the const's type are exactly the trait's
+        // parameters, so we map them back to the names used by the trait.
+        let trait_datum = s.db().trait_datum(self.trait_id);
+        let trait_param_names_in_trait_env = s.binder_var_indices(&trait_datum.binders.binders);
+        let s = &s.add_debrujin_index(None);
+        let param_names_in_assoc_const_env = s.binder_var_indices(&self.ty.binders);
+        let s = &s.add_parameter_mapping(
+            param_names_in_assoc_const_env,
+            trait_param_names_in_trait_env,
+        );
+        write!(
+            f,
+            "const {}: {};",
+            self.id.display(s),
+            self.ty.skip_binders().display(s)
+        )
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for AssociatedConstValue<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        // As for `AssociatedConstDatum`, the binders of the value are
+        // exactly the impl's parameters.
+        let impl_datum = s.db().impl_datum(self.impl_id);
+        let impl_param_names_in_impl_env = s.binder_var_indices(&impl_datum.binders.binders);
+        let s = &s.add_debrujin_index(None);
+        let param_names_in_assoc_const_value_env = s.binder_var_indices(&self.value.binders);
+        let s = &s.add_parameter_mapping(
+            param_names_in_assoc_const_value_env,
+            impl_param_names_in_impl_env,
+        );
+        let value = self.value.skip_binders();
+        write!(
+            f,
+            "{}const {}: {} = {};",
+            s.indent(),
+            self.associated_const_id.display(s),
+            value.data(s.db().interner()).ty.display(s),
+            value.display(s)
+        )
+    }
+}
+
 impl<I: Interner> RenderAsRust<I> for FnDefDatum<I> {
     fn fmt(&self, s: &InternalWriterState<'_, I>, f: &mut Formatter<'_>) -> Result {
         let s = &s.add_debrujin_index(None);
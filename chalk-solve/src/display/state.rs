@@ -10,6 +10,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use super::render_trait::RenderAsRust;
 use crate::RustIrDatabase;
 use chalk_ir::{interner::Interner, *};
 use itertools::Itertools;
@@ -344,7 +345,24 @@ impl<'a, I: Interner> InternalWriterState<'a, I> {
             .map(move |(parameter, var)| match parameter {
                 VariableKind::Ty(_) => format!("{}", self.apply_mappings(var)),
                 VariableKind::Lifetime => format!("'{}", self.apply_mappings(var)),
-                VariableKind::Const(_ty) => format!("const {}", self.apply_mappings(var)),
+                VariableKind::Const(ty) => {
+                    format!("const {}: {}", self.apply_mappings(var), ty.display(self))
+                }
+            })
+    }
+
+    /// Like `binder_var_display`, but also displays the default of each
+    /// parameter that has one, e.g. `T = u32`.
+    pub(super) fn binder_var_display_with_defaults<'b>(
+        &'b self,
+        binders: &'b VariableKinds<I>,
+        param_defaults: &'b [Option<GenericArg<I>>],
+    ) -> impl Iterator<Item = String> + 'b {
+        self.binder_var_display(binders)
+            .zip(param_defaults)
+            .map(move |(param, default)| match default {
+                Some(default) => format!("{} = {}", param, default.display(self)),
+                None => param,
             })
     }
 }
@@ -51,11 +51,19 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
             AssociatedTyDatumBound {
                 where_clauses: Vec::new(),
                 bounds: Vec::new(),
+                default: None,
             },
         );
         Arc::new(v)
     }
 
+    fn associated_const_data(
+        &self,
+        id: chalk_ir::AssocConstId<I>,
+    ) -> std::sync::Arc<crate::rust_ir::AssociatedConstDatum<I>> {
+        self.db.associated_const_data(id)
+    }
+
     fn trait_datum(
         &self,
         trait_id: chalk_ir::TraitId<I>,
@@ -65,6 +73,7 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
             v.binders.binders.clone(),
             TraitDatumBound {
                 where_clauses: Vec::new(),
+                param_defaults: vec![None; v.binders.len(self.db.interner())],
             },
         );
         Arc::new(v)
@@ -81,6 +90,7 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
             AdtDatumBound {
                 variants,
                 where_clauses: Vec::new(),
+                param_defaults: vec![None; v.binders.len(self.db.interner())],
             },
         );
         Arc::new(v)
@@ -119,6 +129,13 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         unreachable!("associated type values should never be stubbed")
     }
 
+    fn associated_const_value(
+        &self,
+        _id: crate::rust_ir::AssociatedConstValueId<I>,
+    ) -> std::sync::Arc<crate::rust_ir::AssociatedConstValue<I>> {
+        unreachable!("associated const values should never be stubbed")
+    }
+
     fn opaque_ty_data(
         &self,
         id: chalk_ir::OpaqueTyId<I>,
@@ -159,6 +176,13 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         self.db.local_impls_to_coherence_check(trait_id)
     }
 
+    fn specialization_priorities(
+        &self,
+        trait_id: chalk_ir::TraitId<I>,
+    ) -> std::sync::Arc<crate::coherence::SpecializationPriorities<I>> {
+        self.db.specialization_priorities(trait_id)
+    }
+
     fn impl_provided_for(
         &self,
         _auto_trait_id: chalk_ir::TraitId<I>,
@@ -176,6 +200,13 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         self.db.well_known_trait_id(well_known_trait)
     }
 
+    fn well_known_adt_id(
+        &self,
+        well_known_adt: crate::rust_ir::WellKnownAdt,
+    ) -> Option<chalk_ir::AdtId<I>> {
+        self.db.well_known_adt_id(well_known_adt)
+    }
+
     fn program_clauses_for_env(
         &self,
         environment: &chalk_ir::Environment<I>,
@@ -250,6 +281,10 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
         self.db.assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: chalk_ir::AssocConstId<I>) -> String {
+        self.db.assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: chalk_ir::OpaqueTyId<I>) -> String {
         self.db.opaque_type_name(opaque_ty_id)
     }
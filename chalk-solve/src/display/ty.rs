@@ -262,10 +262,41 @@ impl<I: Interner> RenderAsRust<I> for ConstValue<I> {
             ConstValue::InferenceVar(_) => write!(f, "_"),
             ConstValue::Placeholder(_) => write!(f, "<const placeholder>"),
             ConstValue::Concrete(value) => write!(f, "{:?}", value.interned),
+            ConstValue::Projection(projection) => write!(f, "{}", projection.display(s)),
+            ConstValue::Expr(expr) => write!(f, "{}", expr.display(s)),
         }
     }
 }
 
+impl<I: Interner> RenderAsRust<I> for ProjectionConst<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        // <X as Y<A1, A2, A3>>::N
+        let interner = s.db().interner();
+        let assoc_const_datum = s.db().associated_const_data(self.associated_const_id);
+        let trait_params = self.substitution.as_slice(interner);
+        write!(
+            f,
+            "<{} as {}>::{}",
+            trait_params[0].display(s),
+            display_type_with_generics(s, assoc_const_datum.trait_id, &trait_params[1..]),
+            self.associated_const_id.display(s),
+        )
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for ConstExpr<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        // {N + 1}
+        write!(
+            f,
+            "{{{} {} {}}}",
+            self.lhs.display(s),
+            self.op.as_str(),
+            self.rhs.display(s)
+        )
+    }
+}
+
 impl<I: Interner> RenderAsRust<I> for GenericArgData<I> {
     fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
         match self {
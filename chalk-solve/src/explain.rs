@@ -0,0 +1,346 @@
+//! Explanations of why a goal does *not* hold, meant for diagnostics.
+//!
+//! For a goal that the solver cannot prove, [`explain_failure`] looks at
+//! the program clauses that apply to it and, for each of them, finds the
+//! conditions that could not be proven, recursing into those. The result is
+//! a [`FailureTree`] whose leaves are the obligations that ultimately
+//! failed: for example, that `Rc<T>: Send` is ruled out by a negative impl,
+//! or that there is no impl of `Foo` for `u32` at all.
+//!
+//! The conditions of a clause are solved one at a time, so a clause whose
+//! conditions each hold on their own but conflict with each other shows up
+//! without any failed conditions.
+
+use crate::clauses::program_clauses_for_goal;
+use crate::infer::InferenceTable;
+use crate::solve::Solver;
+use crate::RustIrDatabase;
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use std::fmt;
+
+type UCanonicalGoal<I> = UCanonical<InEnvironment<Goal<I>>>;
+
+/// Why a goal could not be proven.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailureTree<I: Interner> {
+    /// The goal that failed, in canonical form.
+    pub goal: UCanonicalGoal<I>,
+
+    pub cause: FailureCause<I>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FailureCause<I: Interner> {
+    /// A domain goal for which every applicable program clause failed; this
+    /// includes the case where no clause applies at all. For trait goals,
+    /// `negative_impls` lists the negative impls that match the goal.
+    Clauses {
+        attempts: Vec<ClauseAttempt<I>>,
+        negative_impls: Vec<ImplId<I>>,
+    },
+
+    /// A compound goal (`forall`, `exists`, `if`, a conjunction, ...), which
+    /// failed because of the given subgoals.
+    Subgoals(Vec<FailureTree<I>>),
+
+    /// A `not { G }` goal, which failed because `G` holds (or may hold).
+    NegationHolds,
+
+    /// An equality or subtyping goal whose sides do not unify.
+    Unification,
+
+    /// Not enough is known about the goal to enumerate the clauses that
+    /// apply to it.
+    Floundered,
+
+    /// The goal was not looked into, because the maximum depth was reached.
+    Unexplored,
+}
+
+/// A program clause that applied to a failed goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClauseAttempt<I: Interner> {
+    pub clause: ProgramClause<I>,
+
+    /// The conditions of the clause (instantiated for the goal) that could
+    /// not be proven.
+    pub failed_conditions: Vec<FailureTree<I>>,
+}
+
+/// Explains why `goal` does not hold, descending through at most
+/// `max_depth` program clauses. Returns `None` if `solver` finds a
+/// solution (even an ambiguous one) for `goal`.
+pub fn explain_failure<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solver: &mut dyn Solver<I>,
+    goal: &UCanonicalGoal<I>,
+    max_depth: usize,
+) -> Option<FailureTree<I>> {
+    if solver.solve(db, goal).is_some() {
+        return None;
+    }
+    Some(Explainer { db, solver }.explain(goal, max_depth))
+}
+
+struct Explainer<'a, I: Interner> {
+    db: &'a dyn RustIrDatabase<I>,
+    solver: &'a mut dyn Solver<I>,
+}
+
+impl<'a, I: Interner> Explainer<'a, I> {
+    /// Explains the failure of `goal`, which the solver could not prove.
+    fn explain(&mut self, goal: &UCanonicalGoal<I>, depth: usize) -> FailureTree<I> {
+        let interner = self.db.interner();
+        let (
+            mut infer,
+            _,
+            InEnvironment {
+                environment,
+                goal: value,
+            },
+        ) = InferenceTable::from_canonical(interner, goal.universes, goal.canonical.clone());
+        let cause = match value.data(interner) {
+            GoalData::DomainGoal(_) if depth == 0 => FailureCause::Unexplored,
+            GoalData::DomainGoal(domain_goal) => self.explain_domain_goal(goal, domain_goal, depth),
+            GoalData::Quantified(QuantifierKind::ForAll, binders) => {
+                let subgoal = infer.instantiate_binders_universally(interner, binders.clone());
+                self.explain_subgoals(&mut infer, &environment, vec![subgoal], depth)
+            }
+            GoalData::Quantified(QuantifierKind::Exists, binders) => {
+                let subgoal = infer.instantiate_binders_existentially(interner, binders.clone());
+                self.explain_subgoals(&mut infer, &environment, vec![subgoal], depth)
+            }
+            GoalData::Implies(clauses, subgoal) => {
+                let environment =
+                    environment.add_clauses(interner, clauses.iter(interner).cloned());
+                self.explain_subgoals(&mut infer, &environment, vec![subgoal.clone()], depth)
+            }
+            GoalData::All(subgoals) => {
+                let subgoals = subgoals.iter(interner).cloned().collect();
+                self.explain_subgoals(&mut infer, &environment, subgoals, depth)
+            }
+            GoalData::Not(_) => FailureCause::NegationHolds,
+            GoalData::EqGoal(_) | GoalData::SubtypeGoal(_) => FailureCause::Unification,
+            // `CannotProve` is ambiguous, so it never fails.
+            GoalData::CannotProve => FailureCause::Unexplored,
+        };
+        FailureTree {
+            goal: goal.clone(),
+            cause,
+        }
+    }
+
+    fn explain_domain_goal(
+        &mut self,
+        goal: &UCanonicalGoal<I>,
+        domain_goal: &DomainGoal<I>,
+        depth: usize,
+    ) -> FailureCause<I> {
+        let interner = self.db.interner();
+        let canonical_domain_goal = UCanonical {
+            canonical: Canonical {
+                binders: goal.canonical.binders.clone(),
+                value: InEnvironment::new(&goal.canonical.value.environment, domain_goal.clone()),
+            },
+            universes: goal.universes,
+        };
+        let clauses = match program_clauses_for_goal(self.db, &canonical_domain_goal) {
+            Ok(clauses) => clauses,
+            Err(_) => return FailureCause::Floundered,
+        };
+
+        let mut attempts = vec![];
+        for clause in clauses {
+            let (
+                mut infer,
+                _,
+                InEnvironment {
+                    environment,
+                    goal: domain_goal,
+                },
+            ) = InferenceTable::from_canonical(
+                interner,
+                goal.universes,
+                canonical_domain_goal.canonical.clone(),
+            );
+            let ProgramClauseImplication {
+                consequence,
+                conditions,
+                constraints: _,
+                priority: _,
+            } = infer.instantiate_binders_existentially(interner, clause.data(interner).0.clone());
+            let relation = match infer.relate(
+                interner,
+                self.db.unification_database(),
+                &environment,
+                Variance::Invariant,
+                &domain_goal,
+                &consequence,
+            ) {
+                Ok(relation) => relation,
+                // Clauses are only filtered approximately; this one does not
+                // actually apply.
+                Err(_) => continue,
+            };
+            let subgoals: Vec<_> = conditions
+                .iter(interner)
+                .map(|condition| InEnvironment::new(&environment, condition.clone()))
+                .chain(relation.goals)
+                .collect();
+            let failed_conditions = subgoals
+                .into_iter()
+                .filter_map(|subgoal| self.explain_if_failing(&mut infer, subgoal, depth - 1))
+                .collect();
+            attempts.push(ClauseAttempt {
+                clause,
+                failed_conditions,
+            });
+        }
+
+        FailureCause::Clauses {
+            attempts,
+            negative_impls: self.negative_impls(&canonical_domain_goal),
+        }
+    }
+
+    /// The negative impls that match `goal`, if it is a trait goal.
+    fn negative_impls(&self, goal: &UCanonical<InEnvironment<DomainGoal<I>>>) -> Vec<ImplId<I>> {
+        let interner = self.db.interner();
+        let trait_ref = match &goal.canonical.value.goal {
+            DomainGoal::Holds(WhereClause::Implemented(trait_ref)) => trait_ref,
+            _ => return vec![],
+        };
+        self.db
+            .impls_for_trait(
+                trait_ref.trait_id,
+                trait_ref.substitution.as_slice(interner),
+                &goal.canonical.binders,
+            )
+            .into_iter()
+            .filter(|&impl_id| {
+                let impl_datum = self.db.impl_datum(impl_id);
+                if impl_datum.is_positive() {
+                    return false;
+                }
+                let (mut infer, _, InEnvironment { environment, goal }) =
+                    InferenceTable::from_canonical(
+                        interner,
+                        goal.universes,
+                        goal.canonical.clone(),
+                    );
+                let impl_trait_ref = infer
+                    .instantiate_binders_existentially(interner, impl_datum.binders.clone())
+                    .trait_ref;
+                infer
+                    .relate(
+                        interner,
+                        self.db.unification_database(),
+                        &environment,
+                        Variance::Invariant,
+                        &goal,
+                        &DomainGoal::Holds(WhereClause::Implemented(impl_trait_ref)),
+                    )
+                    .is_ok()
+            })
+            .collect()
+    }
+
+    fn explain_subgoals(
+        &mut self,
+        infer: &mut InferenceTable<I>,
+        environment: &Environment<I>,
+        subgoals: Vec<Goal<I>>,
+        depth: usize,
+    ) -> FailureCause<I> {
+        FailureCause::Subgoals(
+            subgoals
+                .into_iter()
+                .filter_map(|subgoal| {
+                    let subgoal = InEnvironment::new(environment, subgoal);
+                    self.explain_if_failing(infer, subgoal, depth)
+                })
+                .collect(),
+        )
+    }
+
+    /// Explains `subgoal` if the solver cannot prove it.
+    fn explain_if_failing(
+        &mut self,
+        infer: &mut InferenceTable<I>,
+        subgoal: InEnvironment<Goal<I>>,
+        depth: usize,
+    ) -> Option<FailureTree<I>> {
+        let interner = self.db.interner();
+        let canonical = infer.canonicalize(interner, subgoal).quantified;
+        let subgoal = infer.u_canonicalize(interner, &canonical).quantified;
+        match self.solver.solve(self.db, &subgoal) {
+            Some(_) => None,
+            None => Some(self.explain(&subgoal, depth)),
+        }
+    }
+}
+
+impl<I: Interner> FailureTree<I> {
+    fn fmt_node(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{:?}",
+            "",
+            self.goal.canonical.value.goal,
+            indent = depth * 2
+        )?;
+        match &self.cause {
+            FailureCause::Clauses {
+                attempts,
+                negative_impls,
+            } => {
+                if attempts.is_empty() {
+                    write!(f, ": no clause applies")?;
+                } else {
+                    write!(f, ": every clause failed")?;
+                }
+                for impl_id in negative_impls {
+                    write!(f, ", ruled out by negative impl {:?}", impl_id)?;
+                }
+                writeln!(f)?;
+                for attempt in attempts {
+                    write!(
+                        f,
+                        "{:indent$}clause `{:?}`",
+                        "",
+                        attempt.clause,
+                        indent = depth * 2 + 2
+                    )?;
+                    if attempt.failed_conditions.is_empty() {
+                        writeln!(f, ": conditions conflict")?;
+                    } else {
+                        writeln!(f)?;
+                    }
+                    for condition in &attempt.failed_conditions {
+                        condition.fmt_node(f, depth + 2)?;
+                    }
+                }
+            }
+            FailureCause::Subgoals(subgoals) => {
+                writeln!(f)?;
+                for subgoal in subgoals {
+                    subgoal.fmt_node(f, depth + 1)?;
+                }
+            }
+            FailureCause::NegationHolds => writeln!(f, ": negated goal holds")?,
+            FailureCause::Unification => writeln!(f, ": does not unify")?,
+            FailureCause::Floundered => writeln!(f, ": floundered")?,
+            FailureCause::Unexplored => writeln!(f, ": not explored")?,
+        }
+        Ok(())
+    }
+}
+
+/// Displays the tree, one goal or clause per line, with the failed
+/// conditions of a clause indented below it.
+impl<I: Interner> fmt::Display for FailureTree<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_node(f, 0)
+    }
+}
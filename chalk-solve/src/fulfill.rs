@@ -0,0 +1,203 @@
+//! A fulfillment context, for type checkers that want to prove goals
+//! mentioning their own inference variables.
+//!
+//! A type checker typically keeps an [`InferenceTable`] for the body it is
+//! checking, and accumulates obligations like `?0: Clone` as it goes. It
+//! cannot hand those goals to a [`Solver`] directly, since solvers only
+//! accept u-canonical goals, and it must feed whatever the solver learns
+//! about `?0` back into its table. [`FulfillmentContext`] does this
+//! bookkeeping: register goals with [`FulfillmentContext::register`], then
+//! call [`FulfillmentContext::select_where_possible`] to solve what can be
+//! solved, unifying the caller's variables as a side effect. Obligations that
+//! are still ambiguous are kept around for the next call, since later
+//! unifications may settle them; [`FulfillmentContext::select_all`] returns
+//! them once the caller has nothing more to add.
+
+use crate::infer::ucanonicalize::UniverseMapExt;
+use crate::infer::{InferenceTable, ParameterEnaVariableExt};
+use crate::solve::{AmbiguityCause, Solution, Solver};
+use crate::RustIrDatabase;
+use chalk_ir::cast::Cast;
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use std::mem;
+use tracing::{debug, instrument};
+
+/// An obligation that could not be proven or disproven (yet).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingObligation<I: Interner> {
+    /// The goal, which may mention the caller's inference variables.
+    pub goal: InEnvironment<Goal<I>>,
+
+    /// Why the goal could not be decided.
+    pub cause: AmbiguityCause<I>,
+}
+
+/// A set of obligations, mentioning the inference variables of some
+/// [`InferenceTable`], that are solved together using any [`Solver`].
+#[derive(Clone, Debug)]
+pub struct FulfillmentContext<I: Interner> {
+    /// Obligations that have not been proven yet.
+    obligations: Vec<InEnvironment<Goal<I>>>,
+
+    /// Lifetime constraints required by the obligations proven so far.
+    constraints: Vec<InEnvironment<Constraint<I>>>,
+}
+
+impl<I: Interner> Default for FulfillmentContext<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Interner> FulfillmentContext<I> {
+    pub fn new() -> Self {
+        FulfillmentContext {
+            obligations: vec![],
+            constraints: vec![],
+        }
+    }
+
+    /// Adds an obligation; it is only looked at by the next call to
+    /// `select_where_possible` or `select_all`.
+    pub fn register(&mut self, goal: InEnvironment<Goal<I>>) {
+        self.obligations.push(goal);
+    }
+
+    /// The obligations that have not been proven yet.
+    pub fn pending_obligations(&self) -> &[InEnvironment<Goal<I>>] {
+        &self.obligations
+    }
+
+    /// Takes the lifetime constraints required by the obligations proven so
+    /// far, which the caller is responsible for checking.
+    pub fn take_constraints(&mut self) -> Vec<InEnvironment<Constraint<I>>> {
+        mem::take(&mut self.constraints)
+    }
+
+    /// Solves the pending obligations, repeatedly, for as long as doing so
+    /// teaches us something about the inference variables in `infer`.
+    /// Obligations that are proven are dropped; ambiguous ones are kept.
+    ///
+    /// Returns `Err(NoSolution)` if some obligation cannot hold. The pending
+    /// obligations are then left in an unspecified state, and the context
+    /// should be discarded.
+    pub fn select_where_possible(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        solver: &mut dyn Solver<I>,
+        infer: &mut InferenceTable<I>,
+    ) -> Fallible<()> {
+        self.fulfill(program, solver, infer).map(|_| ())
+    }
+
+    /// Like `select_where_possible`, but then also returns all obligations
+    /// that remain ambiguous, together with why they are ambiguous. The
+    /// context is left without pending obligations.
+    pub fn select_all(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        solver: &mut dyn Solver<I>,
+        infer: &mut InferenceTable<I>,
+    ) -> Fallible<Vec<PendingObligation<I>>> {
+        let causes = self.fulfill(program, solver, infer)?;
+        Ok(mem::take(&mut self.obligations)
+            .into_iter()
+            .zip(causes)
+            .map(|(goal, cause)| PendingObligation { goal, cause })
+            .collect())
+    }
+
+    /// The fixed-point loop shared by `select_where_possible` and
+    /// `select_all`. Returns why each of the remaining obligations is
+    /// ambiguous, in the same order as `self.obligations`.
+    #[instrument(level = "debug", skip(self, program, solver, infer))]
+    fn fulfill(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        solver: &mut dyn Solver<I>,
+        infer: &mut InferenceTable<I>,
+    ) -> Fallible<Vec<AmbiguityCause<I>>> {
+        let interner = program.interner();
+        let mut causes = vec![];
+        let mut progress = true;
+        while progress {
+            progress = false;
+            causes.clear();
+            debug!("start of round, {} obligations", self.obligations.len());
+
+            // Goals that come out of unification, to be looked at next round.
+            let mut new_goals = vec![];
+
+            for goal in mem::take(&mut self.obligations) {
+                let canonicalized = infer.canonicalize(interner, goal.clone());
+                let ucanonicalized = infer.u_canonicalize(interner, &canonicalized.quantified);
+                let solution = match solver.solve(program, &ucanonicalized.quantified) {
+                    Some(solution) => solution,
+                    None => {
+                        debug!(?goal, "obligation cannot hold");
+                        return Err(NoSolution);
+                    }
+                };
+
+                if let Some(subst) = solution.definite_subst(interner) {
+                    // Applying an identity substitution would teach us
+                    // nothing, and would loop forever on an ambiguous goal.
+                    if !subst.value.subst.is_identity_subst(interner) {
+                        progress = true;
+                    }
+                    let free_vars = canonicalized
+                        .free_vars
+                        .into_iter()
+                        .map(|free_var| free_var.to_generic_arg(interner))
+                        .collect::<Vec<_>>();
+                    let subst = ucanonicalized
+                        .universes
+                        .map_from_canonical(interner, &subst);
+                    let ConstrainedSubst { subst, constraints } =
+                        infer.instantiate_canonical(interner, subst);
+                    self.constraints
+                        .extend(constraints.as_slice(interner).iter().cloned());
+
+                    // The free variables are unconstrained in the solution,
+                    // so this is really just a substitution and can't fail.
+                    for (i, free_var) in free_vars.iter().enumerate() {
+                        let result = infer
+                            .relate(
+                                interner,
+                                program.unification_database(),
+                                &goal.environment,
+                                Variance::Invariant,
+                                free_var,
+                                subst.at(interner, i),
+                            )
+                            .unwrap_or_else(|err| {
+                                panic!(
+                                    "applying solution failed with free_var={:?}: {:?}",
+                                    free_var, err
+                                )
+                            });
+                        new_goals.extend(result.goals.into_iter().map(|goal| goal.cast(interner)));
+                    }
+                }
+
+                match solution {
+                    Solution::Unique(_) => {}
+                    Solution::Ambig(_, cause) => {
+                        debug!(?goal, %cause, "ambiguous obligation");
+                        self.obligations.push(goal);
+                        causes.push(cause);
+                    }
+                }
+            }
+
+            if !new_goals.is_empty() {
+                self.obligations.extend(new_goals);
+                progress = true;
+            }
+            debug!("end of round, {} obligations left", self.obligations.len());
+        }
+
+        Ok(causes)
+    }
+}
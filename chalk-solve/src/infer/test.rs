@@ -242,6 +242,51 @@ fn projection_eq() {
         .unwrap_err();
 }
 
+#[test]
+fn dyn_with_negative_bound() {
+    // exists(A -> A = dyn !Item0 + 'static)
+    let interner = &ChalkIr;
+    let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+    let environment0 = Environment::new(interner);
+    let a = table.new_variable(U0).to_ty(interner);
+
+    let self_ty = TyKind::BoundVar(BoundVar::new(DebruijnIndex::ONE, 0)).intern(interner);
+    let bound = WhereClause::NotImplemented(TraitRef {
+        trait_id: TraitId(chalk_integration::interner::RawId { index: 0 }),
+        substitution: Substitution::from1(interner, self_ty),
+    });
+    let dyn_ty = TyKind::Dyn(DynTy {
+        bounds: Binders::new(
+            VariableKinds::from1(interner, VariableKind::Ty(TyVariableKind::General)),
+            QuantifiedWhereClauses::from1(interner, Binders::empty(interner, bound)),
+        ),
+        lifetime: LifetimeData::Static.intern(interner),
+    })
+    .intern(interner);
+
+    table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &a,
+            &dyn_ty,
+        )
+        .unwrap();
+    let a = table.normalize_ty_shallow(interner, &a).unwrap();
+    match a.kind(interner) {
+        TyKind::Dyn(dyn_ty) => {
+            let bounds = dyn_ty.bounds.skip_binders().as_slice(interner);
+            assert!(matches!(
+                bounds[0].skip_binders(),
+                WhereClause::NotImplemented(_)
+            ));
+        }
+        _ => panic!("expected a `dyn` type, got {:?}", a),
+    }
+}
+
 const U0: UniverseIndex = UniverseIndex { counter: 0 };
 const U1: UniverseIndex = UniverseIndex { counter: 1 };
 const U2: UniverseIndex = UniverseIndex { counter: 2 };
@@ -343,6 +343,20 @@ where
         .to_lifetime(self.interner()))
     }
 
+    fn fold_free_placeholder_const(
+        &mut self,
+        ty: Ty<I>,
+        universe0: PlaceholderIndex,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Const<I>> {
+        let universe = self.universes.map_universe_from_canonical(universe0.ui);
+        Ok(PlaceholderIndex {
+            ui: universe,
+            idx: universe0.idx,
+        }
+        .to_const(self.interner(), ty))
+    }
+
     fn forbid_inference_vars(&self) -> bool {
         true
     }
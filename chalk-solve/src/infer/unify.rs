@@ -567,6 +567,22 @@ impl<'t, I: Interner> Unifier<'t, I> {
                                         trait_id,
                                     })
                                 }
+                                WhereClause::NotImplemented(trait_ref) => {
+                                    // Negative bounds are generalized like
+                                    // positive ones.
+                                    let TraitRef {
+                                        ref substitution,
+                                        trait_id,
+                                    } = *trait_ref;
+                                    let substitution = self.generalize_substitution_skip_self(
+                                        substitution,
+                                        universe_index,
+                                    );
+                                    WhereClause::NotImplemented(TraitRef {
+                                        substitution,
+                                        trait_id,
+                                    })
+                                }
                                 WhereClause::AliasEq(alias_eq) => {
                                     let AliasEq { alias, ty: _ } = alias_eq;
                                     let alias = match alias {
@@ -974,6 +990,12 @@ impl<'t, I: Interner> Unifier<'t, I> {
         let a = n_a.as_ref().unwrap_or(a);
         let b = n_b.as_ref().unwrap_or(b);
 
+        // Closed const expressions are replaced by their value.
+        let e_a = self.evaluate_const_expr(a)?;
+        let e_b = self.evaluate_const_expr(b)?;
+        let a = e_a.as_ref().unwrap_or(a);
+        let b = e_b.as_ref().unwrap_or(b);
+
         debug_span!("relate_const_const", ?variance, ?a, ?b);
 
         let ConstData {
@@ -1029,6 +1051,72 @@ impl<'t, I: Interner> Unifier<'t, I> {
             (&ConstValue::Concrete(_), &ConstValue::Placeholder(_))
             | (&ConstValue::Placeholder(_), &ConstValue::Concrete(_)) => Err(NoSolution),
 
+            // Unifying an inference variable with a projection binds the
+            // variable to the (unnormalized) projection, as otherwise we
+            // would have no way to relate it to a projection that cannot
+            // be normalized further.
+            (&ConstValue::InferenceVar(var), &ConstValue::Projection(_)) => {
+                debug!(?var, ty=?b, "unify_var_ty");
+                self.unify_var_const(var, b)
+            }
+
+            (&ConstValue::Projection(_), &ConstValue::InferenceVar(var)) => {
+                debug!(?var, ty=?a, "unify_var_ty");
+                self.unify_var_const(var, a)
+            }
+
+            // Projections of the same associated const are equal if their
+            // parameters are, but may also normalize to the same value.
+            (ConstValue::Projection(p1), ConstValue::Projection(p2))
+                if p1.associated_const_id == p2.associated_const_id =>
+            {
+                let snapshot = self.table.snapshot();
+                let goals_len = self.goals.len();
+                match Zip::zip_with(self, variance, p1, p2) {
+                    Ok(()) => {
+                        self.table.commit(snapshot);
+                        Ok(())
+                    }
+                    Err(NoSolution) => {
+                        self.table.rollback_to(snapshot);
+                        self.goals.truncate(goals_len);
+                        let var = self
+                            .table
+                            .new_variable(self.table.max_universe)
+                            .to_const(interner, a_ty.clone());
+                        self.relate_projection_const(p1, &var)?;
+                        self.relate_projection_const(p2, &var)
+                    }
+                }
+            }
+
+            // Otherwise, the projection has to be normalized first.
+            (ConstValue::Projection(p), _) => self.relate_projection_const(p, b),
+            (_, ConstValue::Projection(p)) => self.relate_projection_const(p, a),
+
+            // As with projections, an inference variable can stand for an
+            // unevaluated expression.
+            (&ConstValue::InferenceVar(var), &ConstValue::Expr(_)) => {
+                debug!(?var, ty=?b, "unify_var_ty");
+                self.unify_var_const_expr(var, b)
+            }
+
+            (&ConstValue::Expr(_), &ConstValue::InferenceVar(var)) => {
+                debug!(?var, ty=?a, "unify_var_ty");
+                self.unify_var_const_expr(var, a)
+            }
+
+            (ConstValue::Expr(e1), ConstValue::Expr(e2)) if e1.op == e2.op => {
+                self.relate_const_expr_const_expr(variance, a, b, e1, e2)
+            }
+
+            // Any other expression cannot be evaluated yet, and may or may
+            // not turn out to be equal to the other side.
+            (ConstValue::Expr(_), _) | (_, ConstValue::Expr(_)) => {
+                self.relate_unevaluated_consts(a, b);
+                Ok(())
+            }
+
             (ConstValue::BoundVar(_), _) | (_, ConstValue::BoundVar(_)) => panic!(
                 "unification encountered bound variable: a={:?} b={:?}",
                 a, b
@@ -1036,6 +1124,249 @@ impl<'t, I: Interner> Unifier<'t, I> {
         }
     }
 
+    /// If `c` is a const expression that is closed once the inference
+    /// variables bound so far are resolved, evaluates it. A closed
+    /// expression whose value is not defined (e.g. because it overflows)
+    /// equals nothing.
+    fn evaluate_const_expr(&mut self, c: &Const<I>) -> Fallible<Option<Const<I>>> {
+        let interner = self.interner;
+        let data = c.data(interner);
+        let expr = match &data.value {
+            ConstValue::Expr(expr) => expr,
+            _ => return Ok(None),
+        };
+        match expr.evaluate_with(interner, &data.ty, |operand| self.evaluate_const(operand)) {
+            Ok(value) => Ok(Some(
+                ConstData {
+                    ty: data.ty.clone(),
+                    value: ConstValue::Concrete(value),
+                }
+                .intern(interner),
+            )),
+            Err(ConstEvalError::Undefined) => Err(NoSolution),
+            Err(ConstEvalError::TooGeneric) | Err(ConstEvalError::Unsupported) => Ok(None),
+        }
+    }
+
+    fn evaluate_const(&mut self, c: &Const<I>) -> Result<ConcreteConst<I>, ConstEvalError> {
+        let interner = self.interner;
+        let n_c = self.table.normalize_const_shallow(interner, c);
+        let c = n_c.as_ref().unwrap_or(c);
+        let data = c.data(interner);
+        match &data.value {
+            ConstValue::Concrete(value) => Ok(value.clone()),
+            ConstValue::Expr(expr) => {
+                expr.evaluate_with(interner, &data.ty, |operand| self.evaluate_const(operand))
+            }
+            _ => Err(ConstEvalError::TooGeneric),
+        }
+    }
+
+    /// Relates `a` and `b`, at least one of which is an expression that
+    /// cannot be evaluated yet. If some of its operands are projections,
+    /// these are normalized first and the consts related again afterwards;
+    /// otherwise the result is ambiguous.
+    fn relate_unevaluated_consts(&mut self, a: &Const<I>, b: &Const<I>) {
+        let interner = self.interner;
+        let mut normalize_goals = vec![];
+        let r_a = self.replace_projection_operands(a, &mut normalize_goals);
+        let r_b = self.replace_projection_operands(b, &mut normalize_goals);
+        if normalize_goals.is_empty() {
+            self.push_cannot_prove();
+            return;
+        }
+
+        let a = r_a.unwrap_or_else(|| a.clone());
+        let b = r_b.unwrap_or_else(|| b.clone());
+        self.goals.push(InEnvironment::new(
+            self.environment,
+            EqGoal {
+                a: a.cast(interner),
+                b: b.cast(interner),
+            }
+            .cast(interner),
+        ));
+        // The SLG solver proves the last subgoal first, and the equality
+        // can only be decided once the projections are normalized.
+        self.goals.extend(normalize_goals);
+    }
+
+    /// If `c` is an expression, replaces the projections among its operands
+    /// by fresh inference variables, pushing the goals that normalize them
+    /// onto `normalize_goals`. Returns `None` if there are no such operands.
+    ///
+    /// Projections on a placeholder (e.g. `<T as Trait>::N` under a
+    /// `forall<T>`) are left alone: they may well not normalize, yet the
+    /// expression can still have a known value (e.g. `{ <T as Trait>::N * 0 }`).
+    fn replace_projection_operands(
+        &mut self,
+        c: &Const<I>,
+        normalize_goals: &mut Vec<InEnvironment<Goal<I>>>,
+    ) -> Option<Const<I>> {
+        let interner = self.interner;
+        let n_c = self.table.normalize_const_shallow(interner, c);
+        let c = n_c.as_ref().unwrap_or(c);
+        let data = c.data(interner);
+        let expr = match &data.value {
+            ConstValue::Expr(expr) => expr,
+            _ => return None,
+        };
+
+        let mut replace_operand = |this: &mut Self, operand: &Const<I>| {
+            let n_operand = this.table.normalize_const_shallow(interner, operand);
+            let operand = n_operand.as_ref().unwrap_or(operand);
+            let operand_data = operand.data(interner);
+            match &operand_data.value {
+                ConstValue::Projection(alias)
+                    if !matches!(
+                        alias.self_type_parameter(interner).kind(interner),
+                        TyKind::Placeholder(_)
+                    ) =>
+                {
+                    let var = this
+                        .table
+                        .new_variable(this.table.max_universe)
+                        .to_const(interner, operand_data.ty.clone());
+                    normalize_goals.push(InEnvironment::new(
+                        this.environment,
+                        NormalizeConst {
+                            alias: alias.clone(),
+                            value: var.clone(),
+                        }
+                        .cast(interner),
+                    ));
+                    Some(var)
+                }
+                _ => this.replace_projection_operands(operand, normalize_goals),
+            }
+        };
+        let lhs = replace_operand(self, &expr.lhs);
+        let rhs = replace_operand(self, &expr.rhs);
+        if lhs.is_none() && rhs.is_none() {
+            return None;
+        }
+
+        Some(
+            ConstData {
+                ty: data.ty.clone(),
+                value: ConstValue::Expr(ConstExpr {
+                    op: expr.op,
+                    lhs: lhs.unwrap_or_else(|| expr.lhs.clone()),
+                    rhs: rhs.unwrap_or_else(|| expr.rhs.clone()),
+                }),
+            }
+            .intern(interner),
+        )
+    }
+
+    /// Relates the consts `a` and `b`, which are the expressions `e_a` and
+    /// `e_b` with the same operator. Expressions with equal operands are
+    /// equal, but ones whose operands differ may still evaluate to the same
+    /// value (e.g. `{ N * 0 }` and `{ M * 0 }`), so making the operands equal
+    /// is only one of the ways to make the expressions equal. Unless it is
+    /// the only one, the result is ambiguous.
+    fn relate_const_expr_const_expr(
+        &mut self,
+        variance: Variance,
+        a: &Const<I>,
+        b: &Const<I>,
+        e_a: &ConstExpr<I>,
+        e_b: &ConstExpr<I>,
+    ) -> Fallible<()> {
+        if self.relate_if_unconstrained(variance, e_a, e_b) {
+            return Ok(());
+        }
+
+        // `+` and `-` are injective in each operand, so if one pair of
+        // operands is equal, the other has to be as well.
+        if matches!(e_a.op, ConstBinOp::Add | ConstBinOp::Sub) {
+            if self.relate_if_unconstrained(variance, &e_a.lhs, &e_b.lhs) {
+                return Zip::zip_with(self, variance, &e_a.rhs, &e_b.rhs);
+            }
+            if self.relate_if_unconstrained(variance, &e_a.rhs, &e_b.rhs) {
+                return Zip::zip_with(self, variance, &e_a.lhs, &e_b.lhs);
+            }
+        }
+
+        self.relate_unevaluated_consts(a, b);
+        Ok(())
+    }
+
+    /// Relates `a` and `b` if they are equal without constraining any
+    /// inference variable, and returns whether they are.
+    fn relate_if_unconstrained<T>(&mut self, variance: Variance, a: &T, b: &T) -> bool
+    where
+        T: ?Sized + Zip<I>,
+    {
+        let snapshot = self.table.snapshot();
+        let goals_len = self.goals.len();
+        let var_states = self.var_states();
+        let related = Zip::zip_with(self, variance, a, b).is_ok()
+            && self.goals.len() == goals_len
+            && self.var_states() == var_states;
+        if related {
+            self.table.commit(snapshot);
+        } else {
+            self.table.rollback_to(snapshot);
+            self.goals.truncate(goals_len);
+        }
+        related
+    }
+
+    /// The root of each variable of the table and whether it is bound, to
+    /// find out whether relating two values constrained any of them.
+    fn var_states(&mut self) -> Vec<(EnaVariable<I>, bool)> {
+        let vars = self.table.vars.clone();
+        vars.into_iter()
+            .map(|var| {
+                let bound = matches!(self.table.unify.probe_value(var), InferenceValue::Bound(_));
+                (self.table.unify.find(var), bound)
+            })
+            .collect()
+    }
+
+    fn push_cannot_prove(&mut self) {
+        let interner = self.interner;
+        self.goals.push(InEnvironment::new(
+            self.environment,
+            GoalData::CannotProve.intern(interner),
+        ));
+    }
+
+    fn relate_projection_const(
+        &mut self,
+        alias: &ProjectionConst<I>,
+        value: &Const<I>,
+    ) -> Fallible<()> {
+        let interner = self.interner;
+        self.goals.push(InEnvironment::new(
+            self.environment,
+            NormalizeConst {
+                alias: alias.clone(),
+                value: value.clone(),
+            }
+            .cast(interner),
+        ));
+        Ok(())
+    }
+
+    /// Like `unify_var_const`, for an expression `c`. Even if `var` occurs
+    /// in `c`, the expression may evaluate to `var` (e.g. `{ N * 1 }`), so
+    /// the result is then ambiguous rather than an error.
+    fn unify_var_const_expr(&mut self, var: InferenceVar, c: &Const<I>) -> Fallible<()> {
+        let snapshot = self.table.snapshot();
+        match self.unify_var_const(var, c) {
+            Ok(()) => {
+                self.table.commit(snapshot);
+            }
+            Err(NoSolution) => {
+                self.table.rollback_to(snapshot);
+                self.push_cannot_prove();
+            }
+        }
+        Ok(())
+    }
+
     #[instrument(level = "debug", skip(self))]
     fn unify_var_const(&mut self, var: InferenceVar, c: &Const<I>) -> Fallible<()> {
         let interner = self.interner;
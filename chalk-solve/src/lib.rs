@@ -1,5 +1,6 @@
 #![deny(rust_2018_idioms)]
 
+use crate::coherence::SpecializationPriorities;
 use crate::display::sanitize_debug_name;
 use crate::rust_ir::*;
 use chalk_ir::interner::Interner;
@@ -8,15 +9,20 @@ use chalk_ir::*;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+pub mod autoderef;
 pub mod clauses;
 pub mod coherence;
 pub mod coinductive_goal;
 pub mod display;
+pub mod explain;
 pub mod ext;
+pub mod fulfill;
 pub mod goal_builder;
 pub mod infer;
 pub mod logging;
 pub mod logging_db;
+pub mod object_safety;
+pub mod proof;
 pub mod rust_ir;
 pub mod solve;
 pub mod split;
@@ -49,6 +55,9 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns the datum for the associated type with the given id.
     fn associated_ty_data(&self, ty: AssocTypeId<I>) -> Arc<AssociatedTyDatum<I>>;
 
+    /// Returns the datum for the associated const with the given id.
+    fn associated_const_data(&self, id: AssocConstId<I>) -> Arc<AssociatedConstDatum<I>>;
+
     /// Returns the datum for the definition with the given id.
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>>;
 
@@ -76,6 +85,10 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns the `AssociatedTyValue` with the given id.
     fn associated_ty_value(&self, id: AssociatedTyValueId<I>) -> Arc<AssociatedTyValue<I>>;
 
+    /// Returns the `AssociatedConstValue` with the given id.
+    fn associated_const_value(&self, id: AssociatedConstValueId<I>)
+        -> Arc<AssociatedConstValue<I>>;
+
     /// Returns the `OpaqueTyDatum` with the given id.
     fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>>;
 
@@ -109,6 +122,19 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     ///   impls actually written by users need to be checked.
     fn local_impls_to_coherence_check(&self, trait_id: TraitId<I>) -> Vec<ImplId<I>>;
 
+    /// Returns which impls of the given trait specialize one another, as
+    /// computed by [`coherence::CoherenceSolver::specialization_priorities`].
+    /// This is used to decide which impl provides the value of an associated
+    /// type, and to try more specialized impls first. The default
+    /// implementation says that no impl is specialized.
+    ///
+    /// The coherence solver generates clauses too, so it must be run against
+    /// a database that does not compute the priorities with it (such as one
+    /// using the default implementation).
+    fn specialization_priorities(&self, _trait_id: TraitId<I>) -> Arc<SpecializationPriorities<I>> {
+        Arc::new(SpecializationPriorities::new())
+    }
+
     /// Returns true if there is an explicit impl of the auto trait
     /// `auto_trait_id` for the type `ty`. This is part of
     /// the auto trait handling -- if there is no explicit impl given
@@ -119,13 +145,19 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns id of a trait lang item, if found
     fn well_known_trait_id(&self, well_known_trait: WellKnownTrait) -> Option<TraitId<I>>;
 
+    /// Returns id of an ADT lang item, if found
+    fn well_known_adt_id(&self, well_known_adt: WellKnownAdt) -> Option<AdtId<I>>;
+
     /// Calculates program clauses from an env. This is intended to call the
     /// `program_clauses_for_env` function and then possibly cache the clauses.
     fn program_clauses_for_env(&self, environment: &Environment<I>) -> ProgramClauses<I>;
 
     fn interner(&self) -> &I;
 
-    /// Check if a trait is object safe
+    /// Check if a trait is object safe. This can only rule traits out: a
+    /// trait for which this returns `true` is still not object safe if chalk
+    /// finds violations of its own (see
+    /// `object_safety::object_safety_violations`).
     fn is_object_safe(&self, trait_id: TraitId<I>) -> bool;
 
     /// Gets the `ClosureKind` for a given closure and substitution.
@@ -181,6 +213,12 @@ pub trait RustIrDatabase<I: Interner>: Debug {
         sanitize_debug_name(|f| I::debug_assoc_type_id(assoc_ty_id, f))
     }
 
+    /// Retrieves the name of an associated const. No uniqueness guarantees, but must
+    /// a valid Rust identifier.
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<I>) -> String {
+        sanitize_debug_name(|f| I::debug_assoc_const_id(assoc_const_id, f))
+    }
+
     /// Retrieves the name of an opaque type. No uniqueness guarantees, but must
     /// a valid Rust identifier.
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
@@ -197,11 +235,18 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     fn discriminant_type(&self, ty: Ty<I>) -> Ty<I>;
 }
 
+pub use autoderef::autoderef_steps;
+pub use autoderef::AutoderefSteps;
 pub use clauses::program_clauses_for_env;
 
+pub use fulfill::FulfillmentContext;
+pub use fulfill::PendingObligation;
+pub use solve::AmbiguityCause;
+pub use solve::FuelExhausted;
 pub use solve::Guidance;
 pub use solve::Solution;
 pub use solve::Solver;
+pub use solve::SolverStats;
 pub use solve::SubstitutionResult;
 
 #[macro_use]
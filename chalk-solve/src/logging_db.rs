@@ -13,6 +13,7 @@ use std::{
 
 use crate::rust_ir::*;
 use crate::{
+    coherence::SpecializationPriorities,
     display::{self, WriterState},
     RustIrDatabase,
 };
@@ -128,6 +129,12 @@ where
         ty_datum
     }
 
+    fn associated_const_data(&self, id: AssocConstId<I>) -> Arc<AssociatedConstDatum<I>> {
+        let const_datum = self.ws.db().associated_const_data(id);
+        self.record(const_datum.trait_id);
+        const_datum
+    }
+
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>> {
         self.record(trait_id);
         self.ws.db().trait_datum(trait_id)
@@ -175,6 +182,15 @@ where
         value
     }
 
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<I>,
+    ) -> Arc<AssociatedConstValue<I>> {
+        let value = self.ws.db().associated_const_value(id);
+        self.record(value.impl_id);
+        value
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>> {
         self.record(id);
         self.ws.db().opaque_ty_data(id)
@@ -197,6 +213,11 @@ where
         self.ws.db().local_impls_to_coherence_check(trait_id)
     }
 
+    fn specialization_priorities(&self, trait_id: TraitId<I>) -> Arc<SpecializationPriorities<I>> {
+        self.record(trait_id);
+        self.ws.db().specialization_priorities(trait_id)
+    }
+
     fn impl_provided_for(&self, auto_trait_id: TraitId<I>, ty: &TyKind<I>) -> bool {
         self.record(auto_trait_id);
         if let TyKind::Adt(adt_id, _) = ty {
@@ -214,6 +235,14 @@ where
         trait_id
     }
 
+    fn well_known_adt_id(&self, well_known_adt: crate::rust_ir::WellKnownAdt) -> Option<AdtId<I>> {
+        let adt_id = self.ws.db().well_known_adt_id(well_known_adt);
+        if let Some(id) = adt_id {
+            self.record(id);
+        }
+        adt_id
+    }
+
     fn program_clauses_for_env(
         &self,
         environment: &chalk_ir::Environment<I>,
@@ -237,6 +266,10 @@ where
         self.ws.db().assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<I>) -> String {
+        self.ws.db().assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
         self.ws.db().opaque_type_name(opaque_ty_id)
     }
@@ -395,6 +428,10 @@ where
         self.db.associated_ty_data(ty)
     }
 
+    fn associated_const_data(&self, id: AssocConstId<I>) -> Arc<AssociatedConstDatum<I>> {
+        self.db.associated_const_data(id)
+    }
+
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>> {
         self.db.trait_datum(trait_id)
     }
@@ -430,6 +467,13 @@ where
         self.db.associated_ty_value(id)
     }
 
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<I>,
+    ) -> Arc<AssociatedConstValue<I>> {
+        self.db.associated_const_value(id)
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>> {
         self.db.opaque_ty_data(id)
     }
@@ -451,6 +495,10 @@ where
         self.db.local_impls_to_coherence_check(trait_id)
     }
 
+    fn specialization_priorities(&self, trait_id: TraitId<I>) -> Arc<SpecializationPriorities<I>> {
+        self.db.specialization_priorities(trait_id)
+    }
+
     fn impl_provided_for(&self, auto_trait_id: TraitId<I>, ty: &TyKind<I>) -> bool {
         self.db.impl_provided_for(auto_trait_id, ty)
     }
@@ -462,6 +510,10 @@ where
         self.db.well_known_trait_id(well_known_trait)
     }
 
+    fn well_known_adt_id(&self, well_known_adt: crate::rust_ir::WellKnownAdt) -> Option<AdtId<I>> {
+        self.db.well_known_adt_id(well_known_adt)
+    }
+
     fn program_clauses_for_env(
         &self,
         environment: &chalk_ir::Environment<I>,
@@ -493,6 +545,10 @@ where
         self.db.assoc_type_name(assoc_ty_id)
     }
 
+    fn assoc_const_name(&self, assoc_const_id: AssocConstId<I>) -> String {
+        self.db.assoc_const_name(assoc_const_id)
+    }
+
     fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
         self.db.opaque_type_name(opaque_ty_id)
     }
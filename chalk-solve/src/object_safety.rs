@@ -0,0 +1,159 @@
+//! Object safety: whether a trait can be used to build a trait object
+//! `dyn Trait`, which is what the `ObjectSafe(Trait)` goal asks.
+//!
+//! This is computed from the trait's own data, following the rules rustc uses
+//! for the parts of a trait chalk knows about. Chalk doesn't model trait
+//! methods, so violations coming from them (e.g. a method with a receiver that
+//! isn't `self`, `&self`, `&mut self`, ...) are not detected; those are left
+//! to `RustIrDatabase::is_object_safe`, which has to agree as well.
+
+use std::fmt;
+
+use crate::clauses::super_traits;
+use crate::{RustIrDatabase, WellKnownTrait};
+use chalk_ir::interner::Interner;
+use chalk_ir::visit::{ControlFlow, SuperVisit, Visit, Visitor};
+use chalk_ir::*;
+
+/// A reason why a trait is not object safe.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectSafetyViolation<I: Interner> {
+    /// The trait, or one of its supertraits, requires `Self: Sized`.
+    SizedSelf,
+
+    /// A supertrait of the trait mentions `Self` other than as its own self
+    /// type, as in `trait Foo where Self: PartialEq<Self> {}`.
+    SupertraitSelf(TraitId<I>),
+
+    /// The trait has an associated type with generic parameters of its own.
+    GenericAssocTy(AssocTypeId<I>),
+}
+
+impl<I: Interner> fmt::Display for ObjectSafetyViolation<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectSafetyViolation::SizedSelf => {
+                write!(f, "it requires `Self: Sized`")
+            }
+            ObjectSafetyViolation::SupertraitSelf(id) => write!(
+                f,
+                "its supertrait `{:?}` uses `Self` as a type parameter",
+                id
+            ),
+            ObjectSafetyViolation::GenericAssocTy(id) => {
+                write!(f, "its associated type `{:?}` has generic parameters", id)
+            }
+        }
+    }
+}
+
+/// Returns the reasons why the trait `trait_id` is not object safe, in no
+/// particular order. The trait is object safe if there are none.
+pub fn object_safety_violations<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    trait_id: TraitId<I>,
+) -> Vec<ObjectSafetyViolation<I>> {
+    let interner = db.interner();
+    let trait_datum = db.trait_datum(trait_id);
+    let mut violations = Vec::new();
+
+    // The trait itself comes first, followed by all of its supertraits, with
+    // the parameters of the trait bound by the outer binder.
+    let super_trait_refs = super_traits(db, trait_id);
+    let super_trait_refs = super_trait_refs.skip_binders();
+
+    let sized_trait_id = db.well_known_trait_id(WellKnownTrait::Sized);
+    if super_trait_refs
+        .iter()
+        .any(|trait_ref| Some(trait_ref.skip_binders().trait_id) == sized_trait_id)
+    {
+        violations.push(ObjectSafetyViolation::SizedSelf);
+    }
+
+    for trait_ref in super_trait_refs.iter().skip(1) {
+        let trait_ref = trait_ref.skip_binders();
+        let mentions_self = trait_ref
+            .substitution
+            .iter(interner)
+            .skip(1)
+            .any(|arg| mentions_self(interner, arg));
+        if mentions_self {
+            violations.push(ObjectSafetyViolation::SupertraitSelf(trait_ref.trait_id));
+        }
+    }
+
+    let num_trait_params = trait_datum.binders.len(interner);
+    for &associated_ty_id in &trait_datum.associated_ty_ids {
+        let associated_ty_datum = db.associated_ty_data(associated_ty_id);
+        if associated_ty_datum.binders.len(interner) > num_trait_params {
+            violations.push(ObjectSafetyViolation::GenericAssocTy(associated_ty_id));
+        }
+    }
+
+    violations
+}
+
+/// Checks whether the trait `trait_id` is object safe.
+pub fn is_object_safe<I: Interner>(db: &dyn RustIrDatabase<I>, trait_id: TraitId<I>) -> bool {
+    object_safety_violations(db, trait_id).is_empty()
+}
+
+/// Whether `arg`, from a supertrait ref as returned by `super_traits` with
+/// both binders skipped, mentions the `Self` of the subtrait. Projections on
+/// `Self` (e.g. `<Self as Iterator>::Item`) don't count.
+fn mentions_self<I: Interner>(interner: &I, arg: &GenericArg<I>) -> bool {
+    arg.visit_with(
+        &mut SelfVisitor { interner },
+        // Skip the binder of the supertrait ref.
+        DebruijnIndex::ONE,
+    )
+    .is_break()
+}
+
+struct SelfVisitor<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner> SelfVisitor<'i, I> {
+    fn is_self(&self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> bool {
+        ty.bound_var(self.interner)
+            .and_then(|bound_var| bound_var.shifted_out_to(outer_binder))
+            == Some(BoundVar::new(DebruijnIndex::INNERMOST, 0))
+    }
+}
+
+impl<'i, I: Interner> Visitor<'i, I> for SelfVisitor<'i, I> {
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn visit_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> ControlFlow<()> {
+        match ty.kind(self.interner) {
+            TyKind::Alias(AliasTy::Projection(projection)) => {
+                let mut args = projection.substitution.iter(self.interner);
+                match args.next() {
+                    Some(self_arg)
+                        if matches!(
+                            self_arg.ty(self.interner),
+                            Some(ty) if self.is_self(ty, outer_binder)
+                        ) =>
+                    {
+                        for arg in args {
+                            try_break!(arg.visit_with(self.as_dyn(), outer_binder));
+                        }
+                        ControlFlow::CONTINUE
+                    }
+                    _ => ty.super_visit_with(self.as_dyn(), outer_binder),
+                }
+            }
+            _ if self.is_self(ty, outer_binder) => ControlFlow::BREAK,
+            _ => ty.super_visit_with(self.as_dyn(), outer_binder),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Proof trees, i.e. records of *how* a solver arrived at the solution for
+//! a goal.
+//!
+//! Solvers only record proofs when asked to, since doing so keeps every
+//! intermediate result alive. See `RecursiveSolver::solve_with_proof` for an
+//! example. Proofs can be checked independently of the solver that produced
+//! them with [`check::check_proof`].
+
+use crate::solve::Solution;
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use std::fmt;
+
+pub mod check;
+
+/// The derivation of a single goal. The children of a node are the
+/// derivations of the subgoals that its [`ProofStep`] gave rise to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofTree<I: Interner> {
+    /// The goal this node derives, in the canonical form it was posed to the
+    /// solver.
+    pub goal: UCanonical<InEnvironment<Goal<I>>>,
+
+    /// The solution that was found for `goal`, or `None` if the goal could
+    /// not be proven. For unique solutions this carries the substitution
+    /// (and lifetime constraints) under which the goal holds.
+    pub solution: Option<Solution<I>>,
+
+    /// How the solution was reached.
+    pub step: ProofStep<I>,
+
+    /// The derivations of the subgoals introduced by `step`.
+    pub subgoals: Vec<ProofTree<I>>,
+}
+
+/// A single step in a [`ProofTree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofStep<I: Interner> {
+    /// A domain goal was proven by applying the given program clause (which
+    /// may come from an impl, a builtin rule or the environment). The
+    /// subgoals are the conditions of the clause.
+    Clause(ProgramClause<I>),
+
+    /// A compound goal (`forall`, `exists`, `if`, a conjunction, ...) was
+    /// broken down into the subgoals.
+    Simplification,
+
+    /// More than one clause applied to a domain goal, and their solutions were
+    /// combined. Each subgoal is the derivation of the *same* goal through
+    /// one of those clauses. If the goal could not be proven, the subgoals
+    /// are the failed attempts.
+    Alternatives,
+
+    /// A `not { G }` goal; the only subgoal is the attempt to prove `G`.
+    Negation,
+
+    /// The goal is already being proven further up in the tree. For
+    /// coinductive goals, this is what closes the proof; otherwise the
+    /// solution is the one computed so far for the enclosing goal.
+    Cycle,
+
+    /// The goal was too large and was truncated, which makes it ambiguous.
+    Truncated,
+
+    /// The goal was nested too deeply, and the solver gave up on it, which
+    /// makes it ambiguous.
+    Overflow,
+
+    /// Not enough was known about the goal to enumerate the relevant
+    /// clauses, which makes it ambiguous.
+    Floundered,
+
+    /// An explicit `CannotProve` goal, which is always ambiguous.
+    CannotProve,
+
+    /// The solution of the goal was taken from the solver's cache, and the
+    /// derivation it was found with is not known.
+    Cached,
+}
+
+impl<I: Interner> ProofTree<I> {
+    /// Displays the tree, one goal per line, with subgoals indented below
+    /// the goal they belong to.
+    pub fn display<'a>(&'a self, interner: &'a I) -> ProofTreeDisplay<'a, I> {
+        ProofTreeDisplay {
+            tree: self,
+            interner,
+        }
+    }
+}
+
+pub struct ProofTreeDisplay<'a, I: Interner> {
+    tree: &'a ProofTree<I>,
+    interner: &'a I,
+}
+
+impl<'a, I: Interner> ProofTreeDisplay<'a, I> {
+    fn fmt_node(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        tree: &ProofTree<I>,
+        depth: usize,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{:?}",
+            "",
+            tree.goal.canonical.value.goal,
+            indent = depth * 2
+        )?;
+        match &tree.step {
+            ProofStep::Clause(clause) => write!(f, " by clause `{:?}`", clause)?,
+            ProofStep::Simplification => {}
+            ProofStep::Alternatives => write!(f, " by alternatives")?,
+            ProofStep::Negation => write!(f, " by negation")?,
+            ProofStep::Cycle => write!(f, " by cycle")?,
+            ProofStep::Truncated => write!(f, " (truncated)")?,
+            ProofStep::Overflow => write!(f, " (overflow)")?,
+            ProofStep::Floundered => write!(f, " (floundered)")?,
+            ProofStep::CannotProve => write!(f, " (cannot prove)")?,
+            ProofStep::Cached => write!(f, " (cached)")?,
+        }
+        match &tree.solution {
+            Some(solution) => writeln!(f, ": {}", solution.display(self.interner))?,
+            None => writeln!(f, ": No possible solution")?,
+        }
+        for subgoal in &tree.subgoals {
+            self.fmt_node(f, subgoal, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, I: Interner> fmt::Display for ProofTreeDisplay<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_node(f, self.tree, 0)
+    }
+}
@@ -0,0 +1,654 @@
+//! A checker for proof trees that does not rely on the internals of either
+//! solver.
+//!
+//! Every step of the proof is replayed: clauses must be among the
+//! `program_clauses_for_goal` of the goal they are applied to, their
+//! consequence is unified with that goal, and the subproofs must discharge
+//! all of the resulting subgoals. Solutions are applied through a fresh
+//! `InferenceTable`, which also makes sure they respect the binders and
+//! universes of the goals they belong to.
+//!
+//! Only proofs of *unique* solutions are checked, since ambiguous
+//! solutions do not claim that anything holds. There are a few things the
+//! checker takes on trust:
+//!
+//! - region constraints are not checked, since solving them is up to the
+//!   caller of the solver, and regions in solutions are compared loosely;
+//! - the subproof of a `not { G }` goal is not searched for a proof of `G`;
+//!   instead, the checker verifies that `G` is ground, and that the solver it
+//!   is given finds `G` to fail (checking the proof of one solver with the
+//!   other makes this independent of the solver that gave the proof);
+//! - a cycle is taken to hold with the solution it claims, as long as it
+//!   goes through coinductive goals only.
+//!
+//! The checker errs on the side of rejecting proofs: for instance, answers
+//! of the SLG solver whose variables are only fixed when refining delayed
+//! coinductive subgoals are rejected, since the refinement is not part of
+//! the proof tree.
+
+use super::{ProofStep, ProofTree};
+use crate::clauses::program_clauses_for_goal;
+use crate::coinductive_goal::IsCoinductive;
+use crate::infer::ucanonicalize::UniverseMapExt;
+use crate::infer::{InferenceTable, ParameterEnaVariableExt};
+use crate::solve::{Solution, Solver};
+use crate::RustIrDatabase;
+use chalk_ir::fold::{Fold, Folder};
+use chalk_ir::interner::Interner;
+use chalk_ir::zip::Zip;
+use chalk_ir::*;
+use std::fmt;
+use tracing::debug;
+
+type UCanonicalGoal<I> = UCanonical<InEnvironment<Goal<I>>>;
+
+/// The reasons a proof tree can be rejected. Each error carries the goal
+/// whose step was found to be invalid.
+#[derive(Debug)]
+pub enum ProofError<I: Interner> {
+    /// The proof is for a different goal than the one it was meant to prove.
+    GoalMismatch(UCanonicalGoal<I>),
+
+    /// The proof (or one of its subproofs) does not claim a unique solution,
+    /// e.g. because it relies on a truncated or floundered goal, or its
+    /// derivation is not known.
+    NotProven(UCanonicalGoal<I>),
+
+    /// The solution does not fit the binders and universes of the goal, or
+    /// cannot be applied to it.
+    InvalidSolution(UCanonicalGoal<I>),
+
+    /// The step cannot be used to prove a goal of this form, e.g. a clause
+    /// applied to a goal that is not a domain goal.
+    InvalidStep(UCanonicalGoal<I>),
+
+    /// The clause is not one of the program clauses for the goal.
+    UnknownClause(UCanonicalGoal<I>, ProgramClause<I>),
+
+    /// The consequence of the clause does not unify with the goal.
+    ClauseMismatch(UCanonicalGoal<I>, ProgramClause<I>),
+
+    /// A subproof does not match any of the remaining subgoals.
+    UnexpectedSubproof(UCanonicalGoal<I>, UCanonicalGoal<I>),
+
+    /// A subgoal is not discharged by any subproof.
+    UnprovenSubgoal(UCanonicalGoal<I>, Canonical<InEnvironment<Goal<I>>>),
+
+    /// None of the alternatives prove the goal.
+    NoValidAlternative(UCanonicalGoal<I>),
+
+    /// A cycle was used to prove a goal that is not being proven further
+    /// up, or that is not coinductive all the way up.
+    InvalidCycle(UCanonicalGoal<I>),
+
+    /// A negated goal has free variables, so its failure says nothing about
+    /// its instances.
+    NonGroundNegation(UCanonicalGoal<I>),
+
+    /// The goal negated by a `not { G }` goal does not fail.
+    NegatedGoalNotRefuted(UCanonicalGoal<I>),
+}
+
+impl<I: Interner> fmt::Display for ProofError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::GoalMismatch(goal) => {
+                write!(f, "proof is for the wrong goal `{:?}`", goal)
+            }
+            ProofError::NotProven(goal) => {
+                write!(f, "no unique solution is claimed for `{:?}`", goal)
+            }
+            ProofError::InvalidSolution(goal) => {
+                write!(f, "invalid solution for `{:?}`", goal)
+            }
+            ProofError::InvalidStep(goal) => {
+                write!(f, "invalid proof step for `{:?}`", goal)
+            }
+            ProofError::UnknownClause(goal, clause) => {
+                write!(f, "`{:?}` is not a program clause for `{:?}`", clause, goal)
+            }
+            ProofError::ClauseMismatch(goal, clause) => {
+                write!(f, "`{:?}` does not apply to `{:?}`", clause, goal)
+            }
+            ProofError::UnexpectedSubproof(goal, subgoal) => write!(
+                f,
+                "proof of `{:?}` does not match any subgoal of `{:?}`",
+                subgoal, goal
+            ),
+            ProofError::UnprovenSubgoal(goal, subgoal) => {
+                write!(f, "subgoal `{:?}` of `{:?}` is not proven", subgoal, goal)
+            }
+            ProofError::NoValidAlternative(goal) => {
+                write!(f, "no alternative proves `{:?}`", goal)
+            }
+            ProofError::InvalidCycle(goal) => {
+                write!(f, "invalid cycle on `{:?}`", goal)
+            }
+            ProofError::NonGroundNegation(goal) => {
+                write!(f, "negated goal `{:?}` is not ground", goal)
+            }
+            ProofError::NegatedGoalNotRefuted(goal) => {
+                write!(f, "the goal negated by `{:?}` does not fail", goal)
+            }
+        }
+    }
+}
+
+impl<I: Interner> std::error::Error for ProofError<I> {}
+
+/// Checks that `proof` proves `goal` with the unique solution it claims.
+/// The goals negated by `not { G }` goals are solved again with `solver`,
+/// which must find them to fail.
+pub fn check_proof<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solver: &mut dyn Solver<I>,
+    goal: &UCanonicalGoal<I>,
+    proof: &ProofTree<I>,
+) -> Result<(), ProofError<I>> {
+    if proof.goal != *goal {
+        return Err(ProofError::GoalMismatch(proof.goal.clone()));
+    }
+    ProofChecker {
+        db,
+        solver,
+        stack: vec![],
+    }
+    .check_tree(proof)
+}
+
+struct ProofChecker<'db, I: Interner> {
+    db: &'db dyn RustIrDatabase<I>,
+
+    /// The solver used to refute negated goals.
+    solver: &'db mut dyn Solver<I>,
+
+    /// The goals whose proofs are being checked, used to validate cycles.
+    stack: Vec<UCanonicalGoal<I>>,
+}
+
+impl<'db, I: Interner> ProofChecker<'db, I> {
+    fn interner(&self) -> &'db I {
+        self.db.interner()
+    }
+
+    fn check_tree(&mut self, tree: &ProofTree<I>) -> Result<(), ProofError<I>> {
+        debug!(goal = ?tree.goal, step = ?tree.step, "check_tree");
+        let solution = match &tree.solution {
+            Some(Solution::Unique(solution)) => solution,
+            _ => return Err(ProofError::NotProven(tree.goal.clone())),
+        };
+        self.check_solution_binders(&tree.goal, solution)?;
+
+        self.stack.push(tree.goal.clone());
+        let result = self.check_step(tree, solution);
+        self.stack.pop();
+        result
+    }
+
+    /// Checks that `solution` provides a value of the right kind for each
+    /// variable of `goal`, and only mentions universes of `goal` outside
+    /// of regions.
+    fn check_solution_binders(
+        &self,
+        goal: &UCanonicalGoal<I>,
+        solution: &Canonical<ConstrainedSubst<I>>,
+    ) -> Result<(), ProofError<I>> {
+        let interner = self.interner();
+        let invalid = || ProofError::InvalidSolution(goal.clone());
+        let values = solution.value.subst.as_slice(interner);
+        let binders = goal.canonical.binders.as_slice(interner);
+        if values.len() != binders.len() {
+            return Err(invalid());
+        }
+        let kinds_match = binders.iter().zip(values).all(|(binder, value)| {
+            matches!(
+                (&binder.kind, value.data(interner)),
+                (VariableKind::Ty(_), GenericArgData::Ty(_))
+                    | (VariableKind::Lifetime, GenericArgData::Lifetime(_))
+                    | (VariableKind::Const(_), GenericArgData::Const(_))
+            )
+        });
+        // Region variables that only show up in the (unchecked) region
+        // constraints may live in universes created while solving.
+        let universes_valid = solution
+            .binders
+            .iter(interner)
+            .chain(binders)
+            .filter(|binder| binder.kind != VariableKind::Lifetime)
+            .all(|binder| binder.skip_kind().counter < goal.universes);
+        if kinds_match && universes_valid {
+            Ok(())
+        } else {
+            Err(invalid())
+        }
+    }
+
+    fn check_step(
+        &mut self,
+        tree: &ProofTree<I>,
+        solution: &Canonical<ConstrainedSubst<I>>,
+    ) -> Result<(), ProofError<I>> {
+        let interner = self.interner();
+        let goal = &tree.goal;
+        match &tree.step {
+            ProofStep::Clause(clause) => {
+                let domain_goal = match goal.canonical.value.goal.data(interner) {
+                    GoalData::DomainGoal(domain_goal) => domain_goal.clone(),
+                    _ => return Err(ProofError::InvalidStep(goal.clone())),
+                };
+                let canonical_domain_goal = UCanonical {
+                    canonical: Canonical {
+                        binders: goal.canonical.binders.clone(),
+                        value: InEnvironment::new(
+                            &goal.canonical.value.environment,
+                            domain_goal.clone(),
+                        ),
+                    },
+                    universes: goal.universes,
+                };
+                let clauses = program_clauses_for_goal(self.db, &canonical_domain_goal)
+                    .map_err(|_| ProofError::NotProven(goal.clone()))?;
+                if !clauses.contains(clause) {
+                    return Err(ProofError::UnknownClause(goal.clone(), clause.clone()));
+                }
+
+                let mut subgoals = Subgoals::new(self, goal);
+                let ProgramClauseImplication {
+                    consequence,
+                    conditions,
+                    constraints: _,
+                    priority: _,
+                } = subgoals
+                    .infer
+                    .instantiate_binders_existentially(interner, clause.data(interner).0.clone());
+                let environment = goal.canonical.value.environment.clone();
+                let domain_goal = subgoals.subst.apply(domain_goal, interner);
+                subgoals
+                    .unify(
+                        self,
+                        &environment,
+                        Variance::Invariant,
+                        &domain_goal,
+                        &consequence,
+                    )
+                    .map_err(|_| ProofError::ClauseMismatch(goal.clone(), clause.clone()))?;
+                for condition in conditions.iter(interner) {
+                    subgoals
+                        .pending
+                        .push(InEnvironment::new(&environment, condition.clone()));
+                }
+                subgoals.discharge(self, goal, solution, &tree.subgoals)
+            }
+
+            ProofStep::Simplification => {
+                if let GoalData::DomainGoal(_) = goal.canonical.value.goal.data(interner) {
+                    return Err(ProofError::InvalidStep(goal.clone()));
+                }
+                let mut subgoals = Subgoals::new(self, goal);
+                let value = subgoals.subst.apply(goal.canonical.value.clone(), interner);
+                subgoals.pending.push(value);
+                subgoals.discharge(self, goal, solution, &tree.subgoals)
+            }
+
+            ProofStep::Alternatives => {
+                let valid = tree.subgoals.iter().any(|alternative| {
+                    alternative.goal == *goal
+                        && alternative.solution == tree.solution
+                        && self.check_step(alternative, solution).is_ok()
+                });
+                if valid {
+                    Ok(())
+                } else {
+                    Err(ProofError::NoValidAlternative(goal.clone()))
+                }
+            }
+
+            ProofStep::Negation => {
+                if !goal.canonical.binders.is_empty(interner) {
+                    return Err(ProofError::NonGroundNegation(goal.clone()));
+                }
+                let (
+                    mut infer,
+                    _,
+                    InEnvironment {
+                        environment,
+                        goal: negation,
+                    },
+                ) = InferenceTable::from_canonical(
+                    interner,
+                    goal.universes,
+                    goal.canonical.clone(),
+                );
+                let negated = match negation.data(interner) {
+                    GoalData::Not(negated) => negated.clone(),
+                    _ => return Err(ProofError::InvalidStep(goal.clone())),
+                };
+                // What fails is the inverted goal, in which the placeholders
+                // have become existential variables.
+                let inverted = infer
+                    .invert_then_canonicalize(interner, InEnvironment::new(&environment, negated))
+                    .ok_or_else(|| ProofError::NonGroundNegation(goal.clone()))?;
+                let inverted = infer.u_canonicalize(interner, &inverted).quantified;
+                match &tree.subgoals[..] {
+                    [failed] if failed.solution.is_none() && failed.goal == inverted => {}
+                    _ => return Err(ProofError::InvalidStep(goal.clone())),
+                }
+                match self.solver.solve(self.db, &inverted) {
+                    None => Ok(()),
+                    Some(_) => Err(ProofError::NegatedGoalNotRefuted(goal.clone())),
+                }
+            }
+
+            ProofStep::Cycle => {
+                // The goal itself is on top of the stack.
+                let ancestors = &self.stack[..self.stack.len() - 1];
+                let valid = match ancestors.iter().rposition(|ancestor| ancestor == goal) {
+                    Some(depth) => self.stack[depth..]
+                        .iter()
+                        .all(|goal| goal.is_coinductive(self.db)),
+                    None => false,
+                };
+                if valid {
+                    Ok(())
+                } else {
+                    Err(ProofError::InvalidCycle(goal.clone()))
+                }
+            }
+
+            ProofStep::Truncated
+            | ProofStep::Overflow
+            | ProofStep::Floundered
+            | ProofStep::CannotProve
+            | ProofStep::Cached => Err(ProofError::NotProven(goal.clone())),
+        }
+    }
+}
+
+/// The subgoals that the subproofs of a single step have to discharge,
+/// along with the inference table they live in.
+struct Subgoals<I: Interner> {
+    infer: InferenceTable<I>,
+
+    /// Maps the variables of the goal to their values in `infer`.
+    subst: Substitution<I>,
+
+    pending: Vec<InEnvironment<Goal<I>>>,
+}
+
+impl<I: Interner> Subgoals<I> {
+    /// Instantiates `goal`.
+    fn new(checker: &ProofChecker<'_, I>, goal: &UCanonicalGoal<I>) -> Self {
+        let (infer, subst, _) = InferenceTable::from_canonical(
+            checker.interner(),
+            goal.universes,
+            goal.canonical.clone(),
+        );
+        Subgoals {
+            infer,
+            subst,
+            pending: vec![],
+        }
+    }
+
+    /// Unifies `a` and `b`, adding any goals that come out of that to the
+    /// pending subgoals.
+    fn unify<T>(
+        &mut self,
+        checker: &ProofChecker<'_, I>,
+        environment: &Environment<I>,
+        variance: Variance,
+        a: &T,
+        b: &T,
+    ) -> Fallible<()>
+    where
+        T: ?Sized + Zip<I>,
+    {
+        let result = self.infer.relate(
+            checker.interner(),
+            checker.db.unification_database(),
+            environment,
+            variance,
+            a,
+            b,
+        )?;
+        self.pending.extend(result.goals);
+        Ok(())
+    }
+
+    /// Matches each subproof against a pending subgoal, breaking compound
+    /// subgoals down as needed, and checks it. All subgoals must be
+    /// discharged in the end, and the variables of the goal must have
+    /// ended up with the values given by `solution`.
+    fn discharge(
+        mut self,
+        checker: &mut ProofChecker<'_, I>,
+        goal: &UCanonicalGoal<I>,
+        solution: &Canonical<ConstrainedSubst<I>>,
+        subproofs: &[ProofTree<I>],
+    ) -> Result<(), ProofError<I>> {
+        let interner = checker.interner();
+        for subproof in subproofs {
+            // Solvers may keep the proofs of attempts that only gave
+            // ambiguous results; those did not contribute to the solution.
+            let subsolution = match &subproof.solution {
+                Some(Solution::Unique(subsolution)) => subsolution,
+                _ => continue,
+            };
+            // Subproofs are given in the order in which they were solved,
+            // so each one must match a subgoal as it was at that point.
+            // Unifications are only done when nothing else matches, since
+            // solvers may have postponed them.
+            loop {
+                if let Some(index) = self.find_pending(interner, &subproof.goal) {
+                    let subgoal = self.pending.remove(index);
+                    self.apply_solution(checker, subgoal, subsolution)
+                        .map_err(|_| ProofError::InvalidSolution(subproof.goal.clone()))?;
+                    checker.check_tree(subproof)?;
+                    break;
+                }
+                if !self.simplify_pending(checker, goal, false)?
+                    && !self.simplify_pending(checker, goal, true)?
+                {
+                    return Err(ProofError::UnexpectedSubproof(
+                        goal.clone(),
+                        subproof.goal.clone(),
+                    ));
+                }
+            }
+        }
+        while self.simplify_pending(checker, goal, true)? {}
+        if let Some(subgoal) = self.pending.pop() {
+            return Err(ProofError::UnprovenSubgoal(
+                goal.clone(),
+                self.infer.canonicalize(interner, subgoal).quantified,
+            ));
+        }
+
+        // Regions are compared loosely, since their constraints are not
+        // checked anyway.
+        let proven = self.infer.canonicalize(interner, self.subst).quantified;
+        let claimed = Canonical {
+            binders: solution.binders.clone(),
+            value: solution.value.subst.clone(),
+        };
+        if erase_regions(interner, goal.universes, proven)
+            == erase_regions(interner, goal.universes, claimed)
+        {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidSolution(goal.clone()))
+        }
+    }
+
+    /// Finds the pending subgoal whose canonical form is `goal`.
+    fn find_pending(&mut self, interner: &I, goal: &UCanonicalGoal<I>) -> Option<usize> {
+        let infer = &mut self.infer;
+        self.pending.iter().position(|subgoal| {
+            let canonical = infer.canonicalize(interner, subgoal.clone()).quantified;
+            infer.u_canonicalize(interner, &canonical).quantified == *goal
+        })
+    }
+
+    /// Unifies the variables of `subgoal` with the values given to them by
+    /// the (canonical) solution of its subproof.
+    fn apply_solution(
+        &mut self,
+        checker: &ProofChecker<'_, I>,
+        subgoal: InEnvironment<Goal<I>>,
+        solution: &Canonical<ConstrainedSubst<I>>,
+    ) -> Fallible<()> {
+        let interner = checker.interner();
+        let canonicalized = self.infer.canonicalize(interner, subgoal);
+        let universes = self
+            .infer
+            .u_canonicalize(interner, &canonicalized.quantified)
+            .universes;
+        let solution = universes.map_from_canonical(interner, solution);
+        let ConstrainedSubst {
+            subst: values,
+            constraints: _,
+        } = self.infer.instantiate_canonical(interner, solution);
+        let variables = canonicalized
+            .free_vars
+            .iter()
+            .map(|free_var| free_var.to_generic_arg(interner))
+            .collect();
+        self.unify_values(checker, variables, &values)
+    }
+
+    /// Unifies each of `variables` with the corresponding entry of
+    /// `values`.
+    fn unify_values(
+        &mut self,
+        checker: &ProofChecker<'_, I>,
+        variables: Vec<GenericArg<I>>,
+        values: &Substitution<I>,
+    ) -> Fallible<()> {
+        let interner = checker.interner();
+        // Like in the solvers, the empty environment suffices, since we
+        // are only giving values to unconstrained variables.
+        let environment = Environment::new(interner);
+        for (variable, value) in variables.iter().zip(values.iter(interner)) {
+            self.unify(checker, &environment, Variance::Invariant, variable, value)?;
+        }
+        Ok(())
+    }
+
+    /// Breaks down the first pending subgoal that is a `forall`, `exists`,
+    /// `if` or conjunction, or, if `unify` is set, that is any goal besides
+    /// a domain goal or a negation (solving unification goals). Returns
+    /// `false` if there is no such subgoal.
+    fn simplify_pending(
+        &mut self,
+        checker: &ProofChecker<'_, I>,
+        goal: &UCanonicalGoal<I>,
+        unify: bool,
+    ) -> Result<bool, ProofError<I>> {
+        let interner = checker.interner();
+        let index = self
+            .pending
+            .iter()
+            .position(|subgoal| match subgoal.goal.data(interner) {
+                GoalData::Quantified(..) | GoalData::Implies(..) | GoalData::All(..) => true,
+                GoalData::EqGoal(..) | GoalData::SubtypeGoal(..) => unify,
+                GoalData::DomainGoal(_) | GoalData::Not(_) | GoalData::CannotProve => false,
+            });
+        let InEnvironment {
+            environment,
+            goal: subgoal,
+        } = match index {
+            Some(index) => self.pending.remove(index),
+            None => return Ok(false),
+        };
+        let unproven = |infer: &mut InferenceTable<I>, subgoal: &Goal<I>| {
+            ProofError::UnprovenSubgoal(
+                goal.clone(),
+                infer
+                    .canonicalize(interner, InEnvironment::new(&environment, subgoal.clone()))
+                    .quantified,
+            )
+        };
+        match subgoal.data(interner) {
+            GoalData::Quantified(QuantifierKind::ForAll, binders) => {
+                let subgoal = self
+                    .infer
+                    .instantiate_binders_universally(interner, binders.clone());
+                self.pending.push(InEnvironment::new(&environment, subgoal));
+            }
+            GoalData::Quantified(QuantifierKind::Exists, binders) => {
+                let subgoal = self
+                    .infer
+                    .instantiate_binders_existentially(interner, binders.clone());
+                self.pending.push(InEnvironment::new(&environment, subgoal));
+            }
+            GoalData::Implies(clauses, subgoal) => {
+                let environment =
+                    environment.add_clauses(interner, clauses.iter(interner).cloned());
+                self.pending
+                    .push(InEnvironment::new(&environment, subgoal.clone()));
+            }
+            GoalData::All(subgoals) => {
+                for subgoal in subgoals.iter(interner) {
+                    self.pending
+                        .push(InEnvironment::new(&environment, subgoal.clone()));
+                }
+            }
+            GoalData::EqGoal(EqGoal { a, b }) => {
+                self.unify(checker, &environment, Variance::Invariant, a, b)
+                    .map_err(|_| unproven(&mut self.infer, &subgoal))?;
+            }
+            GoalData::SubtypeGoal(SubtypeGoal { a, b }) => {
+                self.unify(checker, &environment, Variance::Covariant, a, b)
+                    .map_err(|_| unproven(&mut self.infer, &subgoal))?;
+            }
+            GoalData::DomainGoal(_) | GoalData::Not(_) | GoalData::CannotProve => {
+                unreachable!()
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Replaces all free regions of a canonical substitution with `'static`,
+/// dropping the variables that stood for them.
+fn erase_regions<I: Interner>(
+    interner: &I,
+    universes: usize,
+    subst: Canonical<Substitution<I>>,
+) -> Canonical<Substitution<I>> {
+    let (mut infer, _, subst) = InferenceTable::from_canonical(interner, universes, subst);
+    let subst = subst
+        .fold_with(&mut RegionEraser { interner }, DebruijnIndex::INNERMOST)
+        .unwrap();
+    infer.canonicalize(interner, subst).quantified
+}
+
+struct RegionEraser<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner> Folder<'i, I> for RegionEraser<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_placeholder_lifetime(
+        &mut self,
+        _universe: PlaceholderIndex,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        Ok(LifetimeData::Static.intern(self.interner))
+    }
+
+    fn fold_inference_lifetime(
+        &mut self,
+        _var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        Ok(LifetimeData::Static.intern(self.interner))
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
@@ -9,9 +9,9 @@ use chalk_ir::interner::Interner;
 use chalk_ir::{
     try_break,
     visit::{ControlFlow, Visit},
-    AdtId, AliasEq, AliasTy, AssocTypeId, Binders, DebruijnIndex, FnDefId, GenericArg, ImplId,
-    OpaqueTyId, ProjectionTy, QuantifiedWhereClause, Substitution, ToGenericArg, TraitId, TraitRef,
-    Ty, TyKind, VariableKind, WhereClause, WithKind,
+    AdtId, AliasEq, AliasTy, AssocConstId, AssocTypeId, Binders, Const, DebruijnIndex, FnDefId,
+    GenericArg, ImplId, OpaqueTyId, ProjectionTy, QuantifiedWhereClause, Substitution,
+    ToGenericArg, TraitId, TraitRef, Ty, TyKind, VariableKind, WhereClause, WithKind,
 };
 use std::iter;
 
@@ -22,12 +22,20 @@ pub struct AssociatedTyValueId<I: Interner>(pub I::DefId);
 chalk_ir::id_visit!(AssociatedTyValueId);
 chalk_ir::id_fold!(AssociatedTyValueId);
 
+/// Identifier for an "associated const value" found in some impl.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssociatedConstValueId<I: Interner>(pub I::DefId);
+
+chalk_ir::id_visit!(AssociatedConstValueId);
+chalk_ir::id_fold!(AssociatedConstValueId);
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Visit)]
 pub struct ImplDatum<I: Interner> {
     pub polarity: Polarity,
     pub binders: Binders<ImplDatumBound<I>>,
     pub impl_type: ImplType,
     pub associated_ty_value_ids: Vec<AssociatedTyValueId<I>>,
+    pub associated_const_value_ids: Vec<AssociatedConstValueId<I>>,
 }
 
 impl<I: Interner> ImplDatum<I> {
@@ -99,6 +107,16 @@ chalk_ir::const_visit!(AdtKind);
 pub struct AdtDatumBound<I: Interner> {
     pub variants: Vec<AdtVariantDatum<I>>,
     pub where_clauses: Vec<QuantifiedWhereClause<I>>,
+    /// The default of each parameter of the ADT, if it has one:
+    ///
+    /// ```ignore
+    /// struct HashMap<K, V, S = RandomState> { }
+    ///                          ^^^^^^^^^^^
+    /// ```
+    ///
+    /// There is one entry per binder. A default may only refer to the
+    /// parameters that come before it.
+    pub param_defaults: Vec<Option<GenericArg<I>>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner, Visit)]
@@ -115,6 +133,17 @@ pub struct AdtFlags {
 
 chalk_ir::const_visit!(AdtFlags);
 
+/// A list of the ADTs that are "well known" to chalk, which means that the
+/// builtin impls of some well-known traits refer to them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum WellKnownAdt {
+    /// The struct `DynMetadata<Dyn>`, the `Metadata` of the trait object
+    /// type `Dyn`.
+    DynMetadata,
+}
+
+chalk_ir::const_visit!(WellKnownAdt);
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AdtRepr<I: Interner> {
     pub c: bool,
@@ -239,6 +268,8 @@ pub struct TraitDatum<I: Interner> {
 
     pub associated_ty_ids: Vec<AssocTypeId<I>>,
 
+    pub associated_const_ids: Vec<AssocConstId<I>>,
+
     /// If this is a well-known trait, which one? If `None`, this is a regular,
     /// user-defined trait.
     pub well_known: Option<WellKnownTrait>,
@@ -262,6 +293,17 @@ pub enum WellKnownTrait {
     Unpin,
     CoerceUnsized,
     DiscriminantKind,
+    /// The trait `DispatchFromDyn<T>`, for the types that may be used as the
+    /// receiver of a method called on a trait object, e.g. `self: Rc<Self>`.
+    DispatchFromDyn,
+    /// The trait `Deref`, whose only associated type is `Target`.
+    Deref,
+    /// The trait `Pointee`, whose only associated type is `Metadata`, the
+    /// metadata of a (possibly wide) pointer to the type.
+    Pointee,
+    /// The trait `Generator<R>`, implemented by generators whose resume type
+    /// is `R`. Its associated types are `Yield` and `Return`, in that order.
+    Generator,
 }
 
 chalk_ir::const_visit!(WellKnownTrait);
@@ -279,6 +321,10 @@ impl<I: Interner> TraitDatum<I> {
         self.flags.coinductive
     }
 
+    pub fn is_trait_alias(&self) -> bool {
+        self.flags.alias
+    }
+
     /// Gives access to the where clauses of the trait, quantified over the type parameters of the trait:
     ///
     /// ```ignore
@@ -299,6 +345,18 @@ pub struct TraitDatumBound<I: Interner> {
     ///              ^^^^^^^^^^^^^^
     /// ```
     pub where_clauses: Vec<QuantifiedWhereClause<I>>,
+
+    /// The default of each parameter of the trait, if it has one:
+    ///
+    /// ```ignore
+    /// trait Add<Rhs = Self> { }
+    ///               ^^^^
+    /// ```
+    ///
+    /// There is one entry per binder, including `Self` (which never has a
+    /// default). A default may only refer to `Self` and the parameters that
+    /// come before it.
+    pub param_defaults: Vec<Option<GenericArg<I>>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -332,6 +390,13 @@ pub struct TraitFlags {
     pub non_enumerable: bool,
 
     pub coinductive: bool,
+
+    /// A trait alias, e.g. `trait Alias<T> = Foo<T> + Bar where T: Baz;`.
+    /// Its where clauses are the bounds on `Self` (here `Self: Foo<T>` and
+    /// `Self: Bar`) followed by the where clauses of the alias, and a type
+    /// implements the alias exactly when all of them hold. Trait aliases
+    /// have no associated types and cannot be implemented directly.
+    pub alias: bool,
 }
 
 chalk_ir::const_visit!(TraitFlags);
@@ -506,7 +571,7 @@ impl<I: Interner> Visit<I> for AssociatedTyDatum<I> {
 }
 
 /// Encodes the parts of `AssociatedTyDatum` where the parameters
-/// `P0..Pm` are in scope (`bounds` and `where_clauses`).
+/// `P0..Pm` are in scope (`bounds`, `where_clauses` and `default`).
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, Visit, HasInterner)]
 pub struct AssociatedTyDatumBound<I: Interner> {
     /// Bounds on the associated type itself.
@@ -517,6 +582,19 @@ pub struct AssociatedTyDatumBound<I: Interner> {
 
     /// Where clauses that must hold for the projection to be well-formed.
     pub where_clauses: Vec<QuantifiedWhereClause<I>>,
+
+    /// The default value of the associated type, which is its value in impls
+    /// that do not provide their own:
+    ///
+    /// ```ignore
+    /// trait Add<Rhs = Self> {
+    ///     type Output = Self;
+    /// }
+    /// ```
+    ///
+    /// As the value may be overridden by any impl, the projection is never
+    /// normalized to the default from within the trait itself.
+    pub default: Option<Ty<I>>,
 }
 
 impl<I: Interner> AssociatedTyDatum<I> {
@@ -606,6 +684,16 @@ pub struct AssociatedTyValue<I: Interner> {
     /// }
     /// ```
     pub value: Binders<AssociatedTyValueBound<I>>,
+
+    /// Whether the value is declared `default`, and so may be overridden by
+    /// impls that specialize this one.
+    ///
+    /// ```ignore
+    /// impl<T> Iterator for Vec<T> {
+    ///     default type Item = XXX; // <-- `default` is `true`
+    /// }
+    /// ```
+    pub default: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, Visit, HasInterner)]
@@ -614,6 +702,66 @@ pub struct AssociatedTyValueBound<I: Interner> {
     pub ty: Ty<I>,
 }
 
+/// Represents an associated const declaration found inside of a trait:
+///
+/// ```notrust
+/// trait Foo<P1..Pn> { // P0 is Self
+///     const BAR: Ty;
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssociatedConstDatum<I: Interner> {
+    /// The trait this associated const is defined in.
+    pub trait_id: TraitId<I>,
+
+    /// The ID of this associated const
+    pub id: AssocConstId<I>,
+
+    /// Name of this associated const.
+    pub name: I::Identifier,
+
+    /// The type of the const. The binders represent the `P0...Pn`
+    /// variables of the trait.
+    pub ty: Binders<Ty<I>>,
+}
+
+// Manual implementation to avoid I::Identifier type.
+impl<I: Interner> Visit<I> for AssociatedConstDatum<I> {
+    fn visit_with<'i, B>(
+        &self,
+        visitor: &mut dyn chalk_ir::visit::Visitor<'i, I, BreakTy = B>,
+        outer_binder: DebruijnIndex,
+    ) -> ControlFlow<B>
+    where
+        I: 'i,
+    {
+        try_break!(self.trait_id.visit_with(visitor, outer_binder));
+        try_break!(self.id.visit_with(visitor, outer_binder));
+        self.ty.visit_with(visitor, outer_binder)
+    }
+}
+
+/// Represents the *value* of an associated const that is assigned
+/// from within some impl.
+///
+/// ```ignore
+/// impl Zero for u32 {
+///     const ZERO: u32 = 0; // <-- represents this line!
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, Visit)]
+pub struct AssociatedConstValue<I: Interner> {
+    /// Impl in which this associated const value is found.
+    pub impl_id: ImplId<I>,
+
+    /// Associated const being defined.
+    pub associated_const_id: AssocConstId<I>,
+
+    /// The value that projections of the associated const normalize to.
+    /// The binders are the generic parameters of the impl.
+    pub value: Binders<Const<I>>,
+}
+
 /// Represents the bounds for an `impl Trait` type.
 ///
 /// ```ignore
@@ -641,6 +789,9 @@ pub struct OpaqueTyDatumBound<I: Interner> {
 /// Represents a generator type.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
 pub struct GeneratorDatum<I: Interner> {
+    /// Whether this generator is movable (or `static`, in which case it
+    /// may hold references into itself across yields).
+    pub movability: Movability,
     /// All of the nested types for this generator. The `Binder`
     /// represents the types and lifetimes that this generator is generic over -
     /// this behaves in the same way as `AdtDatun.binders`
@@ -652,15 +803,15 @@ pub struct GeneratorDatum<I: Interner> {
 pub struct GeneratorInputOutputDatum<I: Interner> {
     /// The generator resume type - a value of this type
     /// is supplied by the caller when resuming the generator.
-    /// Currently, this plays no rule in goal resolution.
+    /// The generator implements `Generator<R>` for this type.
     pub resume_type: Ty<I>,
     /// The generator yield type - a value of this type
     /// is supplied by the generator during a yield.
-    /// Currently, this plays no role in goal resolution.
+    /// This is the value of `<G as Generator<R>>::Yield`.
     pub yield_type: Ty<I>,
     /// The generator return type - a value of this type
     /// is supplied by the generator when it returns.
-    /// Currently, this plays no role in goal resolution
+    /// This is the value of `<G as Generator<R>>::Return`.
     pub return_type: Ty<I>,
     /// The upvars stored by the generator. These represent
     /// types captured from the generator's environment,
@@ -671,6 +822,18 @@ pub struct GeneratorInputOutputDatum<I: Interner> {
     pub upvars: Vec<Ty<I>>,
 }
 
+/// Whether a generator can be moved after it has been resumed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Movability {
+    /// A `static` generator, which is never `Unpin`.
+    Static,
+    /// A movable generator, which is always `Unpin`.
+    Movable,
+}
+
+chalk_ir::copy_fold!(Movability);
+chalk_ir::const_visit!(Movability);
+
 /// The generator witness data. Each `GeneratorId` has both a `GeneratorDatum`
 /// and a `GeneratorWitnessDatum` - these represent two distinct types in Rust.
 /// `GeneratorWitnessDatum` is logically 'inside' a generator - this only
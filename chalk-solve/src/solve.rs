@@ -1,9 +1,14 @@
 use crate::RustIrDatabase;
 use chalk_derive::HasInterner;
+use chalk_ir::fold::{Fold, Folder};
 use chalk_ir::interner::Interner;
+use chalk_ir::visit::{ControlFlow, Visit, Visitor};
 use chalk_ir::*;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::time::Duration;
 
+pub mod regions;
 pub mod truncate;
 
 /// A (possible) solution for a proposed goal.
@@ -17,8 +22,9 @@ pub enum Solution<I: Interner> {
     /// The goal may be provable in multiple ways, but regardless we may have some guidance
     /// for type inference. In this case, we don't return any lifetime
     /// constraints, since we have not "committed" to any particular solution
-    /// yet.
-    Ambig(Guidance<I>),
+    /// yet. The cause says whether the goal is really ambiguous, or whether
+    /// the solver gave up on it.
+    Ambig(Guidance<I>, AmbiguityCause<I>),
 }
 
 /// When a goal holds ambiguously (e.g., because there are multiple possible
@@ -39,6 +45,119 @@ pub enum Guidance<I: Interner> {
     Unknown,
 }
 
+/// Why a goal holds only ambiguously.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, HasInterner)]
+pub enum AmbiguityCause<I: Interner> {
+    /// The goal may hold in several ways, or whether it holds depends on
+    /// inference variables that are not known yet (or on an explicit
+    /// `CannotProve` goal).
+    Ambiguity,
+
+    /// Not enough is known about some goal to enumerate the clauses that
+    /// apply to it, or to solve its negation.
+    Floundered,
+
+    /// Some goal or answer exceeded the maximum size, and the solver gave
+    /// up on it.
+    Truncation,
+
+    /// Goals were nested more deeply than the maximum depth, and the solver
+    /// gave up on the innermost one. Contains the stack of goals being
+    /// solved at the time, from the outermost to the one that overflowed.
+    Overflow(Vec<UCanonical<InEnvironment<Goal<I>>>>),
+
+    /// The query was given a fuel budget (see `Solver::solve_with_fuel`),
+    /// which ran out before the goal was solved.
+    FuelExhausted,
+}
+
+impl<I: Interner> AmbiguityCause<I> {
+    /// The cause for a goal that is ambiguous because either of two
+    /// alternatives (e.g. two clauses) is: if one of them is really
+    /// ambiguous, so is the goal.
+    pub fn or(self, other: AmbiguityCause<I>) -> AmbiguityCause<I> {
+        if other.severity() < self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The cause for a goal that is ambiguous because both of two subgoals
+    /// are: if the solver gave up on either of them, it gave up on the goal.
+    pub fn and(self, other: AmbiguityCause<I>) -> AmbiguityCause<I> {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Orders the causes from "really ambiguous" to "the solver gave up".
+    fn severity(&self) -> usize {
+        match self {
+            AmbiguityCause::Ambiguity => 0,
+            AmbiguityCause::Floundered => 1,
+            AmbiguityCause::Truncation => 2,
+            AmbiguityCause::Overflow(_) => 3,
+            AmbiguityCause::FuelExhausted => 4,
+        }
+    }
+
+    /// For an overflow, the goal that overflowed.
+    pub fn overflowed_goal(&self) -> Option<&UCanonical<InEnvironment<Goal<I>>>> {
+        match self {
+            AmbiguityCause::Overflow(stack) => stack.last(),
+            _ => None,
+        }
+    }
+}
+
+// The overflow stack consists of canonical goals, which are closed, so there
+// is nothing to fold or visit.
+impl<I: Interner> Fold<I> for AmbiguityCause<I> {
+    type Result = Self;
+
+    fn fold_with<'i>(
+        self,
+        _folder: &mut dyn Folder<'i, I>,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Self::Result>
+    where
+        I: 'i,
+    {
+        Ok(self)
+    }
+}
+
+impl<I: Interner> Visit<I> for AmbiguityCause<I> {
+    fn visit_with<'i, B>(
+        &self,
+        _visitor: &mut dyn Visitor<'i, I, BreakTy = B>,
+        _outer_binder: DebruijnIndex,
+    ) -> ControlFlow<B>
+    where
+        I: 'i,
+    {
+        ControlFlow::CONTINUE
+    }
+}
+
+impl<I: Interner> fmt::Display for AmbiguityCause<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmbiguityCause::Ambiguity => write!(f, "ambiguity"),
+            AmbiguityCause::Floundered => write!(f, "floundering"),
+            AmbiguityCause::Truncation => write!(f, "truncation"),
+            AmbiguityCause::Overflow(stack) => match stack.last() {
+                Some(goal) => write!(f, "overflow evaluating `{:?}`", goal.canonical.value.goal),
+                None => write!(f, "overflow"),
+            },
+            AmbiguityCause::FuelExhausted => write!(f, "fuel exhaustion"),
+        }
+    }
+}
+
 impl<I: Interner> Solution<I> {
     pub fn is_unique(&self) -> bool {
         match *self {
@@ -47,6 +166,33 @@ impl<I: Interner> Solution<I> {
         }
     }
 
+    /// Determine whether this solution contains type information that *must*
+    /// hold, and returns the subst in that case.
+    pub fn definite_subst(&self, interner: &I) -> Option<Canonical<ConstrainedSubst<I>>> {
+        match self {
+            Solution::Unique(constrained) => Some(constrained.clone()),
+            Solution::Ambig(Guidance::Definite(canonical), _) => {
+                let value = ConstrainedSubst {
+                    subst: canonical.value.clone(),
+                    constraints: Constraints::empty(interner),
+                };
+                Some(Canonical {
+                    value,
+                    binders: canonical.binders.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Why the solution is ambiguous, if it is.
+    pub fn ambiguity_cause(&self) -> Option<&AmbiguityCause<I>> {
+        match self {
+            Solution::Unique(_) => None,
+            Solution::Ambig(_, cause) => Some(cause),
+        }
+    }
+
     pub fn display<'a>(&'a self, interner: &'a I) -> SolutionDisplay<'a, I> {
         SolutionDisplay {
             solution: self,
@@ -65,17 +211,29 @@ impl<'a, I: Interner> fmt::Display for SolutionDisplay<'a, I> {
         let SolutionDisplay { solution, interner } = self;
         match solution {
             Solution::Unique(constrained) => write!(f, "Unique; {}", constrained.display(interner)),
-            Solution::Ambig(Guidance::Definite(subst)) => write!(
-                f,
-                "Ambiguous; definite substitution {}",
-                subst.display(interner)
-            ),
-            Solution::Ambig(Guidance::Suggested(subst)) => write!(
-                f,
-                "Ambiguous; suggested substitution {}",
-                subst.display(interner)
-            ),
-            Solution::Ambig(Guidance::Unknown) => write!(f, "Ambiguous; no inference guidance"),
+            Solution::Ambig(guidance, cause) => {
+                match guidance {
+                    Guidance::Definite(subst) => write!(
+                        f,
+                        "Ambiguous; definite substitution {}",
+                        subst.display(interner)
+                    )?,
+                    Guidance::Suggested(subst) => write!(
+                        f,
+                        "Ambiguous; suggested substitution {}",
+                        subst.display(interner)
+                    )?,
+                    Guidance::Unknown => write!(f, "Ambiguous; no inference guidance")?,
+                }
+                // Real ambiguity is the common case, so it is left implicit.
+                match cause {
+                    AmbiguityCause::Ambiguity => Ok(()),
+                    AmbiguityCause::Floundered => write!(f, " (floundered)"),
+                    AmbiguityCause::Truncation => write!(f, " (truncated)"),
+                    AmbiguityCause::Overflow(_) => write!(f, " (overflow)"),
+                    AmbiguityCause::FuelExhausted => write!(f, " (fuel exhausted)"),
+                }
+            }
         }
     }
 }
@@ -114,6 +272,90 @@ impl<S: fmt::Display> fmt::Display for SubstitutionResult<S> {
     }
 }
 
+/// Returned by `Solver::solve_with_fuel` when the solver ran out of fuel
+/// before it could decide the goal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FuelExhausted;
+
+impl fmt::Display for FuelExhausted {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "fuel exhausted")
+    }
+}
+
+impl std::error::Error for FuelExhausted {}
+
+/// Statistics about the work a solver did for its most recent query; see
+/// `Solver::stats`. Counters that do not apply to a solver are left at zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolverStats {
+    /// The number of tables created (in the recursive solver, the number
+    /// of goals added to the search graph).
+    pub tables_created: usize,
+
+    /// The number of times a strand was pursued (SLG solver only).
+    pub strands_pursued: usize,
+
+    /// The number of answers added to tables (in the recursive solver, the
+    /// number of goals that were found to have a solution).
+    pub answers_produced: usize,
+
+    /// The number of goals whose table already existed (in the recursive
+    /// solver, whose result was cached).
+    pub cache_hits: usize,
+
+    /// The number of goals for which a table had to be created (in the
+    /// recursive solver, whose result was not cached).
+    pub cache_misses: usize,
+
+    /// The number of goals and answers that were truncated, or given up on,
+    /// because they exceeded the maximum size.
+    pub truncations: usize,
+
+    /// The number of goals that were given up on because they were nested
+    /// more deeply than the maximum depth (recursive solver only).
+    pub overflows: usize,
+
+    /// The number of program clauses generated for domain goals, by the kind
+    /// of domain goal (`Implemented`, `Normalize`, `WellFormed`, ...).
+    pub program_clauses: BTreeMap<&'static str, usize>,
+
+    /// The time the query took.
+    pub wall_time: Duration,
+}
+
+impl SolverStats {
+    /// Records that `clauses` program clauses were generated for `goal`.
+    pub fn record_program_clauses<I: Interner>(&mut self, goal: &DomainGoal<I>, clauses: usize) {
+        *self
+            .program_clauses
+            .entry(domain_goal_kind(goal))
+            .or_default() += clauses;
+    }
+}
+
+fn domain_goal_kind<I: Interner>(goal: &DomainGoal<I>) -> &'static str {
+    match goal {
+        DomainGoal::Holds(WhereClause::Implemented(_)) => "Implemented",
+        DomainGoal::Holds(WhereClause::NotImplemented(_)) => "NotImplemented",
+        DomainGoal::Holds(WhereClause::AliasEq(_)) => "AliasEq",
+        DomainGoal::Holds(WhereClause::LifetimeOutlives(_)) => "LifetimeOutlives",
+        DomainGoal::Holds(WhereClause::TypeOutlives(_)) => "TypeOutlives",
+        DomainGoal::WellFormed(_) => "WellFormed",
+        DomainGoal::FromEnv(_) => "FromEnv",
+        DomainGoal::Normalize(_) => "Normalize",
+        DomainGoal::NormalizeConst(_) => "NormalizeConst",
+        DomainGoal::IsLocal(_) => "IsLocal",
+        DomainGoal::IsUpstream(_) => "IsUpstream",
+        DomainGoal::IsFullyVisible(_) => "IsFullyVisible",
+        DomainGoal::LocalImplAllowed(_) => "LocalImplAllowed",
+        DomainGoal::Compatible => "Compatible",
+        DomainGoal::DownstreamType(_) => "DownstreamType",
+        DomainGoal::Reveal => "Reveal",
+        DomainGoal::ObjectSafe(_) => "ObjectSafe",
+    }
+}
+
 /// Finds the solution to "goals", or trait queries -- i.e., figures
 /// out what sets of types implement which traits. Also, between
 /// queries, this struct stores the cached state from previous solver
@@ -175,6 +417,50 @@ where
         should_continue: &dyn std::ops::Fn() -> bool,
     ) -> Option<Solution<I>>;
 
+    /// Attempts to solve the given goal like `solve`, but gives up once
+    /// `fuel` units of work have been done. A unit is a strand step in the
+    /// SLG solver and a solve iteration in the recursive solver. Unlike
+    /// `solve_limited`, the limit does not depend on timing, so the same
+    /// sequence of queries always gives the same results.
+    ///
+    /// The budget is checked at the points where the solver can safely
+    /// stop, so a query may overshoot it slightly (by the same amount
+    /// every time).
+    ///
+    /// The default implementation calls `solve_limited`, counting each call
+    /// of `should_continue` as a unit of work.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(None)` if the goal cannot be proven.
+    /// - `Ok(Some(solution))` if we succeeded in finding *some* answers,
+    ///   like `solve`.
+    /// - `Err(FuelExhausted)` if the budget ran out first.
+    fn solve_with_fuel(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+        fuel: usize,
+    ) -> Result<Option<Solution<I>>, FuelExhausted> {
+        let remaining = std::cell::Cell::new(fuel);
+        let exhausted = std::cell::Cell::new(false);
+        let solution = self.solve_limited(program, goal, &|| match remaining.get() {
+            0 => {
+                exhausted.set(true);
+                false
+            }
+            left => {
+                remaining.set(left - 1);
+                true
+            }
+        });
+        if exhausted.get() {
+            Err(FuelExhausted)
+        } else {
+            Ok(solution)
+        }
+    }
+
     /// Attempts to solve the given goal, which must be in canonical
     /// form. Provides multiple solutions to function `f`.  This will do
     /// only as much work towards `goal` as it has to (and that work
@@ -204,6 +490,12 @@ where
         f: &mut dyn FnMut(SubstitutionResult<Canonical<ConstrainedSubst<I>>>, bool) -> bool,
     ) -> bool;
 
+    /// Returns statistics about the work done for the most recent query, if
+    /// the solver keeps track of them.
+    fn stats(&self) -> Option<&SolverStats> {
+        None
+    }
+
     /// A convenience method for when one doesn't need the actual solution,
     /// only whether or not one exists.
     fn has_unique_solution(
@@ -0,0 +1,498 @@
+//! Lexical region resolution: checking the lifetime constraints of a
+//! solution.
+//!
+//! Solvers don't reason about lifetimes themselves; a `Unique` solution
+//! instead carries the outlives constraints that must hold for it to be
+//! valid, and it is up to the caller to check them. This module provides an
+//! (optional) check for those constraints, which can also reduce them to a
+//! minimal form.
+//!
+//! Regions are classified as *rigid* -- `'static`, `'empty`, and
+//! placeholders (from `forall` binders in the goal) -- or as *variables*,
+//! i.e. the canonical variables of the solution, whose values the caller is
+//! still free to choose. A constraint between rigid regions holds if it
+//! follows from the outlives clauses in its environment; if it doesn't, it
+//! never will, and the solution is an error. A constraint involving
+//! variables may or may not hold depending on the values picked for them,
+//! but the constraints on a variable can still conflict with each other: if
+//! `'p: '?0` and `'?0: 'q` then `'p: 'q` must hold. Furthermore, a variable
+//! can only be assigned regions from its own universe, so `'?0: 'p` for a
+//! placeholder `'p` the variable cannot name forces `'?0` to be `'static`.
+//!
+//! Erased lifetimes are assumed to satisfy any constraint.
+
+use crate::clauses::program_clauses_for_env;
+use crate::solve::Solution;
+use crate::RustIrDatabase;
+use chalk_ir::fold::shift::Shift;
+use chalk_ir::interner::Interner;
+use chalk_ir::visit::{ControlFlow, SuperVisit, Visit, Visitor};
+use chalk_ir::*;
+use std::fmt;
+
+/// A lifetime constraint that can never hold. This is either one of the
+/// constraints that was checked, or a constraint implied by several of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionError<I: Interner> {
+    pub constraint: InEnvironment<Constraint<I>>,
+}
+
+impl<I: Interner> fmt::Display for RegionError<I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "lifetime constraint `{:?}` can never hold",
+            self.constraint.goal
+        )
+    }
+}
+
+/// Checks the lifetime constraints of a solution, returning `NoSolution`
+/// if they can never hold. Otherwise, the constraints of a unique solution
+/// are reduced to a minimal form (see [`simplify_region_constraints`]).
+pub fn resolve_regions<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solution: Solution<I>,
+) -> Fallible<Solution<I>> {
+    match solution {
+        Solution::Unique(subst) => {
+            let subst = simplify_region_constraints(db, subst).map_err(|_| NoSolution)?;
+            Ok(Solution::Unique(subst))
+        }
+        // Ambiguous solutions carry no constraints.
+        solution @ Solution::Ambig(..) => Ok(solution),
+    }
+}
+
+/// Checks that the lifetime constraints of `subst` can be satisfied by
+/// some choice of values for its canonical variables.
+pub fn check_region_constraints<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    subst: &Canonical<ConstrainedSubst<I>>,
+) -> Result<(), RegionError<I>> {
+    simplify_region_constraints(db, subst.clone()).map(|_| ())
+}
+
+/// Checks the lifetime constraints of `subst` (see
+/// [`check_region_constraints`]), and replaces them by an equivalent,
+/// minimal set: constraints that always hold are dropped, constraints on
+/// types are broken down into constraints on their components, and
+/// constraints that follow from the others are removed. What remains only
+/// involves the canonical variables of `subst`.
+pub fn simplify_region_constraints<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    subst: Canonical<ConstrainedSubst<I>>,
+) -> Result<Canonical<ConstrainedSubst<I>>, RegionError<I>> {
+    let interner = db.interner();
+    let Canonical { value, binders } = subst;
+
+    // Constraints are only comparable within the same environment.
+    let mut groups: Vec<(Environment<I>, Vec<Constraint<I>>)> = vec![];
+    for constraint in value.constraints.iter(interner) {
+        match groups
+            .iter_mut()
+            .find(|(environment, _)| *environment == constraint.environment)
+        {
+            Some((_, constraints)) => constraints.push(constraint.goal.clone()),
+            None => groups.push((
+                constraint.environment.clone(),
+                vec![constraint.goal.clone()],
+            )),
+        }
+    }
+
+    let mut constraints = vec![];
+    for (environment, group) in groups {
+        let resolver = RegionResolver::new(db, &binders, &environment);
+        let residual = resolver.resolve(&group).map_err(|constraint| RegionError {
+            constraint: InEnvironment::new(&environment, constraint),
+        })?;
+        constraints.extend(
+            residual
+                .into_iter()
+                .map(|constraint| InEnvironment::new(&environment, constraint)),
+        );
+    }
+
+    Ok(Canonical {
+        value: ConstrainedSubst {
+            subst: value.subst,
+            constraints: Constraints::from_iter(interner, constraints),
+        },
+        binders,
+    })
+}
+
+enum RegionKind {
+    Static,
+    Empty,
+    Erased,
+    Placeholder(PlaceholderIndex),
+    /// A canonical variable, in the given universe (if known).
+    Var(Option<UniverseIndex>),
+}
+
+/// An outlives constraint that can't be broken down any further.
+#[derive(Clone, PartialEq, Eq)]
+enum Atom<I: Interner> {
+    /// `'a: 'b`
+    Region(Lifetime<I>, Lifetime<I>),
+    /// `T: 'b`, where `T` is a type variable, a placeholder or an alias.
+    Type(Ty<I>, Lifetime<I>),
+}
+
+struct RegionResolver<'a, I: Interner> {
+    interner: &'a I,
+    binders: &'a CanonicalVarKinds<I>,
+    /// The `'a: 'b` clauses of the environment.
+    region_facts: Vec<(Lifetime<I>, Lifetime<I>)>,
+    /// The `T: 'b` clauses of the environment.
+    type_facts: Vec<(Ty<I>, Lifetime<I>)>,
+}
+
+impl<'a, I: Interner> RegionResolver<'a, I> {
+    fn new(
+        db: &'a dyn RustIrDatabase<I>,
+        binders: &'a CanonicalVarKinds<I>,
+        environment: &Environment<I>,
+    ) -> Self {
+        let interner = db.interner();
+        let mut region_facts = vec![];
+        let mut type_facts = vec![];
+        for clause in program_clauses_for_env(db, environment).iter(interner) {
+            let implication = &clause.data(interner).0;
+            if !implication.binders.is_empty(interner)
+                || !implication.skip_binders().conditions.is_empty(interner)
+            {
+                continue;
+            }
+            match &implication.skip_binders().consequence {
+                DomainGoal::Holds(WhereClause::LifetimeOutlives(outlives)) => {
+                    region_facts.push((outlives.a.clone(), outlives.b.clone()))
+                }
+                DomainGoal::Holds(WhereClause::TypeOutlives(outlives)) => {
+                    type_facts.push((outlives.ty.clone(), outlives.lifetime.clone()))
+                }
+                _ => {}
+            }
+        }
+        RegionResolver {
+            interner,
+            binders,
+            region_facts,
+            type_facts,
+        }
+    }
+
+    fn kind(&self, lifetime: &Lifetime<I>) -> RegionKind {
+        match lifetime.data(self.interner) {
+            LifetimeData::Static => RegionKind::Static,
+            LifetimeData::Empty(_) => RegionKind::Empty,
+            LifetimeData::Erased => RegionKind::Erased,
+            LifetimeData::Placeholder(placeholder) => RegionKind::Placeholder(*placeholder),
+            LifetimeData::BoundVar(bound_var) => RegionKind::Var(
+                bound_var
+                    .index_if_innermost()
+                    .map(|index| *self.binders.at(self.interner, index).skip_kind()),
+            ),
+            LifetimeData::InferenceVar(_) => RegionKind::Var(None),
+            LifetimeData::Phantom(..) => unreachable!(),
+        }
+    }
+
+    fn is_var(&self, lifetime: &Lifetime<I>) -> bool {
+        matches!(self.kind(lifetime), RegionKind::Var(_))
+    }
+
+    /// Whether `'a: 'b` follows from `edges` (which are `'x: 'y` pairs), no
+    /// matter the values of any variables.
+    fn implied_by(
+        &self,
+        edges: &[(Lifetime<I>, Lifetime<I>)],
+        a: &Lifetime<I>,
+        b: &Lifetime<I>,
+    ) -> bool {
+        match (self.kind(a), self.kind(b)) {
+            (RegionKind::Static, _) | (_, RegionKind::Empty) => return true,
+            (RegionKind::Erased, _) | (_, RegionKind::Erased) => return true,
+            _ => {}
+        }
+        self.reachable(edges, a)
+            .iter()
+            .any(|region| region == b || matches!(self.kind(region), RegionKind::Static))
+    }
+
+    /// All regions that `start` outlives by following `edges`.
+    fn reachable(
+        &self,
+        edges: &[(Lifetime<I>, Lifetime<I>)],
+        start: &Lifetime<I>,
+    ) -> Vec<Lifetime<I>> {
+        let mut reached = vec![start.clone()];
+        let mut index = 0;
+        while index < reached.len() {
+            for (a, b) in edges {
+                if *a == reached[index] && !reached.contains(b) {
+                    reached.push(b.clone());
+                }
+            }
+            index += 1;
+        }
+        reached
+    }
+
+    /// Checks the constraints, returning the minimal set of atoms that
+    /// still need to hold, or a constraint that can never hold.
+    fn resolve(&self, constraints: &[Constraint<I>]) -> Result<Vec<Constraint<I>>, Constraint<I>> {
+        // Break the constraints down, and drop the atoms that hold; atoms
+        // that can't hold are errors.
+        let mut atoms: Vec<Atom<I>> = vec![];
+        for constraint in constraints {
+            for atom in self.atoms(constraint)? {
+                if !self.atom_holds(&self.region_facts, &atom)? && !atoms.contains(&atom) {
+                    atoms.push(atom);
+                }
+            }
+        }
+
+        self.check_variables(&atoms)?;
+
+        // Drop the atoms implied by the others, one at a time so that we
+        // never drop both halves of a cycle.
+        let mut index = 0;
+        while index < atoms.len() {
+            let atom = atoms.remove(index);
+            let mut edges = self.region_facts.clone();
+            edges.extend(self.region_edges(&atoms));
+            let implied = match &atom {
+                Atom::Region(a, b) => self.implied_by(&edges, a, b),
+                Atom::Type(ty, b) => atoms.iter().any(|other| match other {
+                    Atom::Type(other_ty, other_b) => {
+                        other_ty == ty && self.implied_by(&edges, other_b, b)
+                    }
+                    Atom::Region(..) => false,
+                }),
+            };
+            if !implied {
+                atoms.insert(index, atom);
+                index += 1;
+            }
+        }
+
+        Ok(atoms
+            .into_iter()
+            .map(|atom| match atom {
+                Atom::Region(a, b) => Constraint::LifetimeOutlives(a, b),
+                Atom::Type(ty, b) => Constraint::TypeOutlives(ty, b),
+            })
+            .collect())
+    }
+
+    fn region_edges(&self, atoms: &[Atom<I>]) -> Vec<(Lifetime<I>, Lifetime<I>)> {
+        atoms
+            .iter()
+            .filter_map(|atom| match atom {
+                Atom::Region(a, b) => Some((a.clone(), b.clone())),
+                Atom::Type(..) => None,
+            })
+            .collect()
+    }
+
+    /// Breaks `constraint` down into atoms.
+    fn atoms(&self, constraint: &Constraint<I>) -> Result<Vec<Atom<I>>, Constraint<I>> {
+        match constraint {
+            Constraint::LifetimeOutlives(a, b) => Ok(vec![Atom::Region(a.clone(), b.clone())]),
+            Constraint::TypeOutlives(ty, b) => {
+                let mut components = Components {
+                    interner: self.interner,
+                    components: vec![],
+                    opaque: false,
+                };
+                let _ = ty.visit_with(&mut components, DebruijnIndex::INNERMOST);
+                if components.opaque {
+                    // Some alias mentions regions bound within the type, so
+                    // we can't express its constraint separately.
+                    return Ok(vec![Atom::Type(ty.clone(), b.clone())]);
+                }
+                Ok(components
+                    .components
+                    .into_iter()
+                    .map(|component| match component {
+                        Component::Region(a) => Atom::Region(a, b.clone()),
+                        Component::Type(ty) => Atom::Type(ty, b.clone()),
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Whether `atom` holds no matter the values of any variables; errors
+    /// if it can never hold.
+    fn atom_holds(
+        &self,
+        edges: &[(Lifetime<I>, Lifetime<I>)],
+        atom: &Atom<I>,
+    ) -> Result<bool, Constraint<I>> {
+        match atom {
+            Atom::Region(a, b) => {
+                if self.implied_by(edges, a, b) {
+                    Ok(true)
+                } else if self.is_var(a) || self.is_var(b) {
+                    Ok(false)
+                } else {
+                    Err(Constraint::LifetimeOutlives(a.clone(), b.clone()))
+                }
+            }
+            Atom::Type(ty, b) => {
+                if matches!(self.kind(b), RegionKind::Empty | RegionKind::Erased)
+                    || self
+                        .type_facts
+                        .iter()
+                        .any(|(fact_ty, fact_b)| fact_ty == ty && self.implied_by(edges, fact_b, b))
+                {
+                    Ok(true)
+                } else if self.is_var(b)
+                    || !matches!(ty.kind(self.interner), TyKind::Placeholder(_))
+                {
+                    Ok(false)
+                } else {
+                    // A placeholder type only outlives what the environment
+                    // says it does.
+                    Err(Constraint::TypeOutlives(ty.clone(), b.clone()))
+                }
+            }
+        }
+    }
+
+    /// Checks that the bounds on each variable are compatible: everything
+    /// that a variable must outlive must be outlived by everything that
+    /// must outlive the variable, and the variable must be able to name a
+    /// region between the two.
+    fn check_variables(&self, atoms: &[Atom<I>]) -> Result<(), Constraint<I>> {
+        let mut edges = self.region_facts.clone();
+        edges.extend(self.region_edges(atoms));
+        let vars = edges
+            .iter()
+            .flat_map(|(a, b)| vec![a, b])
+            .filter(|region| self.is_var(region))
+            .fold(vec![], |mut vars: Vec<&Lifetime<I>>, var| {
+                if !vars.contains(&var) {
+                    vars.push(var);
+                }
+                vars
+            });
+
+        let is_rigid = |region: &Lifetime<I>| !self.is_var(region);
+        for var in vars {
+            let lower: Vec<_> = self
+                .reachable(&edges, var)
+                .into_iter()
+                .filter(is_rigid)
+                .collect();
+            let upper: Vec<_> = edges
+                .iter()
+                .map(|(a, _)| a)
+                .filter(|region| is_rigid(region))
+                .filter(|region| self.reachable(&edges, region).contains(var))
+                .collect();
+            for p in &upper {
+                for q in &lower {
+                    if !self.implied_by(&self.region_facts, p, q) {
+                        return Err(Constraint::LifetimeOutlives((*p).clone(), q.clone()));
+                    }
+                }
+            }
+
+            // A variable outliving a placeholder it can't name has to be
+            // `'static`, and one outlived by such a placeholder `'empty`.
+            let universe = match self.kind(var) {
+                RegionKind::Var(Some(universe)) => universe,
+                _ => continue,
+            };
+            let unnameable = |region: &Lifetime<I>| match self.kind(region) {
+                RegionKind::Placeholder(placeholder) => placeholder.ui > universe,
+                _ => false,
+            };
+            if lower.iter().any(&unnameable) {
+                let static_ = LifetimeData::Static.intern(self.interner);
+                for p in &upper {
+                    if !self.implied_by(&self.region_facts, p, &static_) {
+                        return Err(Constraint::LifetimeOutlives((*p).clone(), static_));
+                    }
+                }
+            }
+            if upper.iter().copied().any(&unnameable) {
+                let empty = LifetimeData::Empty(UniverseIndex::root()).intern(self.interner);
+                for q in &lower {
+                    if !self.implied_by(&self.region_facts, &empty, q) {
+                        return Err(Constraint::LifetimeOutlives(empty, q.clone()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+enum Component<I: Interner> {
+    Region(Lifetime<I>),
+    Type(Ty<I>),
+}
+
+/// Collects the regions and types that a type outlives a region through:
+/// `Vec<&'a T>: 'b` holds if `'a: 'b` and `T: 'b`.
+struct Components<'i, I: Interner> {
+    interner: &'i I,
+    components: Vec<Component<I>>,
+    /// Set if the type has a component we can't express on its own.
+    opaque: bool,
+}
+
+impl<'i, I: Interner> Visitor<'i, I> for Components<'i, I> {
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn visit_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> ControlFlow<()> {
+        match ty.kind(self.interner) {
+            TyKind::Placeholder(_) | TyKind::InferenceVar(..) => {
+                self.components.push(Component::Type(ty.clone()));
+            }
+            TyKind::BoundVar(bound_var) => {
+                if let Some(bound_var) = bound_var.shifted_out_to(outer_binder) {
+                    self.components
+                        .push(Component::Type(bound_var.to_ty(self.interner)));
+                }
+            }
+            TyKind::Alias(_) => match ty.clone().shifted_out_to(self.interner, outer_binder) {
+                Ok(ty) => self.components.push(Component::Type(ty)),
+                Err(_) => self.opaque = true,
+            },
+            _ => return ty.super_visit_with(self.as_dyn(), outer_binder),
+        }
+        ControlFlow::CONTINUE
+    }
+
+    fn visit_lifetime(
+        &mut self,
+        lifetime: &Lifetime<I>,
+        outer_binder: DebruijnIndex,
+    ) -> ControlFlow<()> {
+        match lifetime.data(self.interner) {
+            LifetimeData::BoundVar(bound_var) => {
+                if let Some(bound_var) = bound_var.shifted_out_to(outer_binder) {
+                    self.components
+                        .push(Component::Region(bound_var.to_lifetime(self.interner)));
+                }
+            }
+            _ => self.components.push(Component::Region(lifetime.clone())),
+        }
+        ControlFlow::CONTINUE
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 };
 use chalk_ir::{
     cast::*,
-    fold::shift::Shift,
+    fold::{shift::Shift, Subst},
     interner::Interner,
     visit::{ControlFlow, Visit, Visitor},
     *,
@@ -17,6 +17,7 @@ pub enum WfError<I: Interner> {
     IllFormedTypeDecl(chalk_ir::AdtId<I>),
     IllFormedOpaqueTypeDecl(chalk_ir::OpaqueTyId<I>),
     IllFormedTraitImpl(chalk_ir::TraitId<I>),
+    IllFormedTraitDecl(chalk_ir::TraitId<I>),
 }
 
 impl<I: Interner> fmt::Display for WfError<I> {
@@ -37,6 +38,11 @@ impl<I: Interner> fmt::Display for WfError<I> {
                 "trait impl for `{:?}` does not meet well-formedness requirements",
                 id
             ),
+            WfError::IllFormedTraitDecl(id) => write!(
+                f,
+                "trait declaration `{:?}` does not meet well-formedness requirements",
+                id
+            ),
         }
     }
 }
@@ -89,7 +95,9 @@ impl<'i, I: Interner> Visitor<'i, I> for InputTypeCollector<'i, I> {
                 .clone()
                 .intern(self.interner)
                 .visit_with(self, outer_binder),
-            WhereClause::Implemented(trait_ref) => trait_ref.visit_with(self, outer_binder),
+            WhereClause::Implemented(trait_ref) | WhereClause::NotImplemented(trait_ref) => {
+                trait_ref.visit_with(self, outer_binder)
+            }
             WhereClause::TypeOutlives(TypeOutlives { ty, .. }) => ty.visit_with(self, outer_binder),
             WhereClause::LifetimeOutlives(..) => ControlFlow::CONTINUE,
         }
@@ -244,18 +252,16 @@ where
         let is_enum = adt_datum.kind == AdtKind::Enum;
 
         let mut gb = GoalBuilder::new(self.db);
-        let adt_data = adt_datum
-            .binders
-            .map_ref(|b| (&b.variants, &b.where_clauses));
 
         // We make a goal like...
         //
         // forall<T> { ... }
         let wg_goal = gb.forall(
-            &adt_data,
+            &adt_datum.binders,
             is_enum,
-            |gb, _, (variants, where_clauses), is_enum| {
+            |gb, substitution, adt_data, is_enum| {
                 let interner = gb.interner();
+                let where_clauses = &adt_data.where_clauses;
 
                 // (FromEnv(T: Eq) => ...)
                 gb.implies(
@@ -264,7 +270,8 @@ where
                         .cloned()
                         .map(|wc| wc.into_from_env_goal(interner)),
                     |gb| {
-                        let sub_goals: Vec<_> = variants
+                        let sub_goals: Vec<_> = adt_data
+                            .variants
                             .iter()
                             .flat_map(|variant| {
                                 let fields = &variant.fields;
@@ -290,6 +297,12 @@ where
                                     .map(|ty| ty.well_formed().cast(interner))
                                     .chain(sized_constraint_goal.into_iter())
                             })
+                            .chain(param_defaults_wf_goals(
+                                interner,
+                                &substitution,
+                                &adt_data.param_defaults,
+                                where_clauses,
+                            ))
                             .collect();
 
                         gb.all(sub_goals)
@@ -309,6 +322,81 @@ where
         }
     }
 
+    pub fn verify_trait_decl(&self, trait_id: TraitId<I>) -> Result<(), WfError<I>> {
+        let interner = self.db.interner();
+
+        // Given a trait like
+        //
+        // ```rust
+        // trait Foo<T = Vec<Self>> where T: Eq { }
+        // ```
+        let trait_datum = self.db.trait_datum(trait_id);
+
+        // Only the parameter defaults of a trait, and the defaults of its
+        // associated types, need to be checked.
+        let mut goals: Vec<_> = trait_datum
+            .associated_ty_ids
+            .iter()
+            .filter_map(|&assoc_ty_id| assoc_ty_default_wf_goal(self.db, assoc_ty_id))
+            .collect();
+
+        if trait_datum
+            .binders
+            .skip_binders()
+            .param_defaults
+            .iter()
+            .any(Option::is_some)
+        {
+            let mut gb = GoalBuilder::new(self.db);
+
+            // We make a goal like...
+            //
+            // forall<Self, T> { ... }
+            goals.push(gb.forall(
+                &trait_datum.binders,
+                (),
+                |gb, substitution, trait_data, ()| {
+                    let interner = gb.interner();
+                    let where_clauses = &trait_data.where_clauses;
+
+                    // (FromEnv(T: Eq) => ...)
+                    gb.implies(
+                        where_clauses
+                            .iter()
+                            .cloned()
+                            .map(|wc| wc.into_from_env_goal(interner)),
+                        |gb| {
+                            // WellFormed(Vec<Self>), Vec<Self>: Eq
+                            gb.all(param_defaults_wf_goals(
+                                interner,
+                                &substitution,
+                                &trait_data.param_defaults,
+                                where_clauses,
+                            ))
+                        },
+                    )
+                },
+            ));
+        }
+
+        if goals.is_empty() {
+            return Ok(());
+        }
+
+        let goal = Goal::all(interner, goals);
+
+        debug!("WF trait goal: {:#?}", goal);
+
+        let mut fresh_solver = (self.solver_builder)();
+        let is_legal = fresh_solver.has_unique_solution(self.db, &goal.into_closed_goal(interner));
+
+        if is_legal {
+            Ok(())
+        } else {
+            Err(WfError::IllFormedTraitDecl(trait_id))
+        }
+    }
+
     pub fn verify_trait_impl(&self, impl_id: ImplId<I>) -> Result<(), WfError<I>> {
         let interner = self.db.interner();
 
@@ -325,7 +413,14 @@ where
             ),
         );
 
-        if let Some(well_known) = self.db.trait_datum(trait_id).well_known {
+        let trait_datum = self.db.trait_datum(trait_id);
+
+        // A trait alias is implemented through its expansion only.
+        if trait_datum.is_trait_alias() {
+            return Err(WfError::IllFormedTraitImpl(trait_id));
+        }
+
+        if let Some(well_known) = trait_datum.well_known {
             self.verify_well_known_impl(impl_id, well_known)?
         }
 
@@ -419,14 +514,23 @@ where
                     &impl_datum,
                 )
             }
-            WellKnownTrait::Clone | WellKnownTrait::Unpin => true,
+            WellKnownTrait::DispatchFromDyn => {
+                WfWellKnownConstraints::dispatch_from_dyn_impl_constraint(
+                    &mut *solver,
+                    self.db,
+                    &impl_datum,
+                )
+            }
+            WellKnownTrait::Clone | WellKnownTrait::Unpin | WellKnownTrait::Deref => true,
             // You can't add a manual implementation for the following traits:
             WellKnownTrait::Fn
             | WellKnownTrait::FnOnce
             | WellKnownTrait::FnMut
             | WellKnownTrait::Unsize
             | WellKnownTrait::Sized
-            | WellKnownTrait::DiscriminantKind => false,
+            | WellKnownTrait::DiscriminantKind
+            | WellKnownTrait::Pointee
+            | WellKnownTrait::Generator => false,
         };
 
         if is_legal {
@@ -437,6 +541,108 @@ where
     }
 }
 
+/// Creates the goals checking that the parameter defaults of an ADT or a
+/// trait are well-formed, given the identity `substitution` for its
+/// parameters. This is the case if the types in the defaults are
+/// well-formed, and the where clauses of the item hold when the defaulted
+/// parameters take their defaults.
+fn param_defaults_wf_goals<I: Interner>(
+    interner: &I,
+    substitution: &Substitution<I>,
+    param_defaults: &[Option<GenericArg<I>>],
+    where_clauses: &[QuantifiedWhereClause<I>],
+) -> Vec<Goal<I>> {
+    let defaulted: Vec<_> = substitution
+        .iter(interner)
+        .zip(param_defaults)
+        .map(|(param, default)| default.as_ref().unwrap_or(param).clone())
+        .collect();
+
+    let well_formed_goals = InputTypeCollector::types_in(interner, param_defaults)
+        .into_iter()
+        .map(|ty| ty.well_formed().cast(interner));
+
+    // Only the where clauses that mention a defaulted parameter change.
+    let where_clause_goals = where_clauses.iter().filter_map(|wc| {
+        let defaulted_wc = Subst::apply(interner, &defaulted, wc.clone());
+        if defaulted_wc == *wc {
+            None
+        } else {
+            Some(defaulted_wc.cast(interner))
+        }
+    });
+
+    well_formed_goals.chain(where_clause_goals).collect()
+}
+
+/// Creates the goal checking that the default of an associated type is
+/// well-formed, if it has one. Given
+///
+/// ```ignore
+/// trait Foo {
+///     type Assoc<'a>: Clone = Box<&'a Self> where Self: 'a;
+/// }
+/// ```
+///
+/// we make a goal like
+///
+/// ```notrust
+/// forall<'a, Self> {
+///     if (FromEnv(Self: Foo), FromEnv(Self: 'a)) {
+///         WellFormed(Box<&'a Self>), WellFormed(Box<&'a Self>: Clone)
+///     }
+/// }
+/// ```
+fn assoc_ty_default_wf_goal<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    assoc_ty_id: AssocTypeId<I>,
+) -> Option<Goal<I>> {
+    let assoc_ty_datum = db.associated_ty_data(assoc_ty_id);
+    assoc_ty_datum.binders.skip_binders().default.as_ref()?;
+
+    let mut gb = GoalBuilder::new(db);
+    Some(gb.forall(
+        &assoc_ty_datum.binders,
+        assoc_ty_id,
+        |gb, substitution, assoc_ty_data, assoc_ty_id| {
+            let interner = gb.interner();
+            let default = assoc_ty_data.default.as_ref().unwrap();
+            let trait_ref = gb.db().trait_ref_from_projection(&ProjectionTy {
+                associated_ty_id: assoc_ty_id,
+                substitution,
+            });
+
+            // if (FromEnv(Self: Foo), FromEnv(Self: 'a)) { ... }
+            let from_env_clauses =
+                iter::once(trait_ref.from_env().cast::<ProgramClause<I>>(interner)).chain(
+                    assoc_ty_data
+                        .where_clauses
+                        .iter()
+                        .cloned()
+                        .map(|qwc| qwc.into_from_env_goal(interner))
+                        .casted(interner),
+                );
+            gb.implies(from_env_clauses, |gb| {
+                let wf_goals = InputTypeCollector::types_in(interner, default)
+                    .into_iter()
+                    .map(|ty| ty.well_formed())
+                    .casted(interner);
+
+                // The default must meet the bounds of the associated type.
+                let bound_goals = assoc_ty_data
+                    .bounds
+                    .iter()
+                    .cloned()
+                    .flat_map(|qb| qb.into_where_clauses(interner, default.clone()))
+                    .map(|qwc| qwc.into_well_formed_goal(interner))
+                    .casted(interner);
+
+                gb.all::<_, Goal<I>>(wf_goals.chain(bound_goals))
+            })
+        },
+    ))
+}
+
 fn impl_header_wf_goal<I: Interner>(
     db: &dyn RustIrDatabase<I>,
     impl_id: ImplId<I>,
@@ -606,6 +812,7 @@ fn compute_assoc_ty_goal<I: Interner>(
                 let AssociatedTyDatumBound {
                     bounds: defn_bounds,
                     where_clauses: defn_where_clauses,
+                    default: _,
                 } = assoc_ty_datum
                     .binders
                     .clone()
@@ -1059,4 +1266,175 @@ impl WfWellKnownConstraints {
             _ => false,
         }
     }
+
+    /// Verify constraints of a DispatchFromDyn impl.
+    /// Rules for DispatchFromDyn impl to be considered well-formed:
+    /// a) pointer conversions: &T -> &U, &mut T -> &mut U, *const T -> *const U and
+    ///    *mut T -> *mut U are considered valid if T: Unsize<U>. Unlike for
+    ///    CoerceUnsized, the kind of pointer and its mutability can't change.
+    /// b) struct conversions of structures with the same definition, `S<P0...Pn>` -> `S<Q0...Qn>`.
+    ///    The struct must use the default representation (no `#[repr(C)]` or
+    ///    `#[repr(packed)]`), so that it has the same representation as the one
+    ///    field that is not zero-sized. That field must be the only one that
+    ///    changes its type (from T to U), and we expect T: DispatchFromDyn<U>.
+    ///
+    ///    E.g. for `struct Rc<T> { ptr: *const T, phantom: PhantomData<T> }`
+    ///    the impl `impl<T, U> DispatchFromDyn<Rc<U>> for Rc<T> where T: Unsize<U> {}`
+    ///    is legal, as `*const T: DispatchFromDyn<*const U>`.
+    fn dispatch_from_dyn_impl_constraint<I: Interner>(
+        solver: &mut dyn Solver<I>,
+        db: &dyn RustIrDatabase<I>,
+        impl_datum: &ImplDatum<I>,
+    ) -> bool {
+        let interner = db.interner();
+        let mut gb = GoalBuilder::new(db);
+
+        let (binders, impl_datum) = impl_datum.binders.as_ref().into();
+
+        let trait_ref: &TraitRef<I> = &impl_datum.trait_ref;
+
+        let source = trait_ref.self_type_parameter(interner);
+        let target = trait_ref
+            .substitution
+            .at(interner, 1)
+            .assert_ty_ref(interner)
+            .clone();
+
+        let mut place_in_environment = |goal| -> Goal<I> {
+            gb.forall(
+                &Binders::new(
+                    binders.clone(),
+                    (goal, trait_ref, &impl_datum.where_clauses),
+                ),
+                (),
+                |gb, _, (goal, trait_ref, where_clauses), ()| {
+                    let interner = gb.interner();
+                    gb.implies(
+                        impl_wf_environment(interner, where_clauses, trait_ref),
+                        |_| goal,
+                    )
+                },
+            )
+        };
+
+        match (source.kind(interner), target.kind(interner)) {
+            (TyKind::Ref(s_m, _, source), TyKind::Ref(t_m, _, target))
+            | (TyKind::Raw(s_m, source), TyKind::Raw(t_m, target)) => {
+                if s_m != t_m {
+                    return false;
+                }
+
+                let unsize_trait_id =
+                    if let Some(id) = db.well_known_trait_id(WellKnownTrait::Unsize) {
+                        id
+                    } else {
+                        return false;
+                    };
+
+                // Source: Unsize<Target>
+                let unsize_goal: Goal<I> = TraitRef {
+                    trait_id: unsize_trait_id,
+                    substitution: Substitution::from_iter(
+                        interner,
+                        [source.clone(), target.clone()].iter().cloned(),
+                    ),
+                }
+                .cast(interner);
+
+                // ImplEnv -> Source: Unsize<Target>
+                let unsize_goal = place_in_environment(unsize_goal);
+
+                solver.has_unique_solution(db, &unsize_goal.into_closed_goal(interner))
+            }
+            (TyKind::Adt(source_id, subst_a), TyKind::Adt(target_id, subst_b)) => {
+                let adt_datum = db.adt_datum(*source_id);
+
+                if source_id != target_id || adt_datum.kind != AdtKind::Struct {
+                    return false;
+                }
+
+                let repr = db.adt_repr(*source_id);
+                if repr.c || repr.packed {
+                    return false;
+                }
+
+                let fields = adt_datum
+                    .binders
+                    .map_ref(|bound| &bound.variants.last().unwrap().fields)
+                    .cloned();
+
+                let (source_fields, target_fields) = (
+                    fields.clone().substitute(interner, subst_a),
+                    fields.substitute(interner, subst_b),
+                );
+
+                // collect the fields that are not zero-sized
+                let sized_field_ids: Vec<usize> = (0..source_fields.len())
+                    .filter(|&i| !is_zst(db, &source_fields[i], &mut vec![]))
+                    .collect();
+
+                if sized_field_ids.len() != 1 {
+                    return false;
+                }
+
+                let field_id = sized_field_ids[0];
+
+                // Source.fields[i]: DispatchFromDyn<TargetFields[i]>
+                let dispatch_from_dyn_goal: Goal<I> = TraitRef {
+                    trait_id: trait_ref.trait_id,
+                    substitution: Substitution::from_iter(
+                        interner,
+                        [
+                            source_fields[field_id].clone(),
+                            target_fields[field_id].clone(),
+                        ]
+                        .iter()
+                        .cloned(),
+                    ),
+                }
+                .cast(interner);
+
+                // ImplEnv -> Source.fields[i]: DispatchFromDyn<TargetFields[i]>
+                let dispatch_from_dyn_goal = place_in_environment(dispatch_from_dyn_goal);
+
+                solver.has_unique_solution(db, &dispatch_from_dyn_goal.into_closed_goal(interner))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether `ty` is known to be zero-sized: `PhantomData`, and tuples and
+/// structs made only of zero-sized types. `visited` holds the structs that
+/// are being looked at, so that recursive types don't overflow the stack.
+fn is_zst<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    ty: &Ty<I>,
+    visited: &mut Vec<AdtId<I>>,
+) -> bool {
+    let interner = db.interner();
+    match ty.kind(interner) {
+        TyKind::Tuple(_, substitution) => substitution
+            .iter(interner)
+            .all(|ty| is_zst(db, ty.assert_ty_ref(interner), visited)),
+        TyKind::Adt(adt_id, substitution) => {
+            let adt_datum = db.adt_datum(*adt_id);
+            if adt_datum.flags.phantom_data {
+                return true;
+            }
+            if adt_datum.kind != AdtKind::Struct || visited.contains(adt_id) {
+                return false;
+            }
+            visited.push(*adt_id);
+            let fields = adt_datum
+                .binders
+                .map_ref(|bound| &bound.variants.last().unwrap().fields)
+                .cloned()
+                .substitute(interner, substitution);
+            let is_zst = fields.iter().all(|field| is_zst(db, field, visited));
+            visited.pop();
+            is_zst
+        }
+        _ => false,
+    }
 }
@@ -29,7 +29,10 @@ Options:
   --help              Show this screen.
   --program=PATH      Specifies the path to the `.chalk` file containing traits/impls.
   --goal=GOAL         Specifies a goal to evaluate (may be given more than once).
-  --overflow-depth=N  Specifies the overflow depth [default: 10].
+  --solver=S          Specifies the solver to use, `slg` or `recursive` [default: slg].
+  --max-size=N        Specifies the maximum size of goals and answers.
+  --overflow-depth=N  Specifies the overflow depth (the maximum size for the `slg` solver).
+  --no-cache          Disables caching of solved goals (recursive solver only).
   --multiple          Output multiple answers instead of ambiguous solution.
 ";
 
@@ -38,7 +41,10 @@ Options:
 struct Args {
     flag_program: Option<String>,
     flag_goal: Vec<String>,
-    flag_overflow_depth: usize,
+    flag_solver: String,
+    flag_max_size: Option<usize>,
+    flag_overflow_depth: Option<usize>,
+    flag_no_cache: bool,
     flag_multiple: bool,
 }
 
@@ -112,15 +118,18 @@ fn run() -> Result<()> {
         .unwrap_or_else(|e| e.exit());
 
     // Validate arguments.
-    if args.flag_overflow_depth == 0 {
-        eprintln!("error: overflow depth must be at least 1");
-        exit(1);
-    }
+    let mut solver_choice = match args.solver_choice() {
+        Ok(solver_choice) => solver_choice,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(1);
+        }
+    };
 
     // Load the .chalk file, if given.
     let mut prog = None;
     if let Some(program) = &args.flag_program {
-        match load_program(args, program) {
+        match load_program(solver_choice, program) {
             Ok(p) => prog = Some(p),
             Err(err) => {
                 eprintln!("error loading program: {}", err);
@@ -132,7 +141,7 @@ fn run() -> Result<()> {
     if args.flag_goal.is_empty() {
         // The user specified no goal. Enter interactive mode.
         readline_loop(&mut rustyline::Editor::new(), "?- ", |rl, line| {
-            if let Err(e) = process(args, line, rl, &mut prog) {
+            if let Err(e) = process(args, &mut solver_choice, line, rl, &mut prog) {
                 eprintln!("error: {}", e);
             }
         })
@@ -190,11 +199,11 @@ where
 }
 
 /// Process a single command. `args` is a struct containing the command-line
-/// arguments, and `prog` is a parsed `.chalk` file.
-// TODO: Could we pass in an Options struct or something? The Args struct
-// still has Strings where it should have Enums... (e.g. solver_choice)
+/// arguments, `solver_choice` is the solver used for programs loaded from now
+/// on, and `prog` is a parsed `.chalk` file.
 fn process(
     args: &Args,
+    solver_choice: &mut SolverChoice,
     command: &str,
     rl: &mut rustyline::Editor<()>,
     prog: &mut Option<LoadedProgram>,
@@ -207,14 +216,14 @@ fn process(
         help()
     } else if command == "program" {
         // Load a .chalk file via stdin, until EOF is found.
-        let chalk_prog = LoadedProgram::new(read_program(rl)?, args.solver_choice())?;
+        let chalk_prog = LoadedProgram::new(read_program(rl)?, *solver_choice)?;
         // Let's do a sanity check before going forward.
         let _ = chalk_prog.db.checked_program()?;
         *prog = Some(chalk_prog);
     } else if command.starts_with("load ") {
         // Load a .chalk file.
         let filename = &command["load ".len()..];
-        let chalk_prog = load_program(args, filename)?;
+        let chalk_prog = load_program(*solver_choice, filename)?;
         // Let's do a sanity check before going forward.
         let _ = chalk_prog.db.checked_program()?;
         *prog = Some(chalk_prog);
@@ -223,6 +232,18 @@ fn process(
             Some(level) => std::env::set_var("CHALK_DEBUG", level),
             None => println!("debug <level> set debug level to <level>"),
         }
+    } else if command == "solver" {
+        println!("{:?}", solver_choice);
+    } else if command.starts_with("solver ") {
+        // Switch solvers, keeping the currently loaded program (if any).
+        let mut words = command.split_whitespace().skip(1);
+        let name = words
+            .next()
+            .ok_or("solver <name> [params] expects a solver name")?;
+        *solver_choice = parse_solver_choice(name, words)?;
+        if let Some(prog) = prog {
+            prog.db.set_solver_choice(*solver_choice);
+        }
     } else {
         // The command is either "print", "lowered", or a goal.
 
@@ -253,12 +274,10 @@ fn process(
 }
 
 /// Load the file into a string, and parse it.
-// TODO: Could we pass in an Options struct or something? The Args struct
-// still has Strings where it should have Enums... (e.g. solver_choice)
-fn load_program(args: &Args, filename: &str) -> Result<LoadedProgram> {
+fn load_program(solver_choice: SolverChoice, filename: &str) -> Result<LoadedProgram> {
     let mut text = String::new();
     File::open(filename)?.read_to_string(&mut text)?;
-    Ok(LoadedProgram::new(text, args.solver_choice())?)
+    Ok(LoadedProgram::new(text, solver_choice)?)
 }
 
 /// Print out help for commands in interpreter mode.
@@ -272,6 +291,10 @@ fn help() {
     println!("  lowered       print the lowered program");
     println!("  <goal>        attempt to solve <goal>");
     println!("  debug <level> set debug level to <level>");
+    println!("  solver        print the current solver");
+    println!("  solver <name> [params]");
+    println!("                switch to the `slg` or `recursive` solver; params are");
+    println!("                `max_size=N`, `overflow_depth=N` and `caching_enabled=BOOL`");
 }
 
 /// Read a program from the command-line. Stop reading when EOF is read. If
@@ -287,12 +310,80 @@ fn read_program(rl: &mut rustyline::Editor<()>) -> Result<String> {
 }
 
 impl Args {
-    fn solver_choice(&self) -> SolverChoice {
-        SolverChoice::SLG {
-            max_size: self.flag_overflow_depth,
-            expected_answers: None,
+    fn solver_choice(&self) -> Result<SolverChoice> {
+        let mut params = vec![];
+        if let Some(max_size) = self.flag_max_size {
+            params.push(format!("max_size={}", max_size));
+        }
+        if let Some(overflow_depth) = self.flag_overflow_depth {
+            if overflow_depth == 0 {
+                return Err("overflow depth must be at least 1".into());
+            }
+            // The SLG solver has no overflow depth; as it always has, the flag
+            // sets its maximum size instead, unless that is given explicitly.
+            if self.flag_solver != "slg" {
+                params.push(format!("overflow_depth={}", overflow_depth));
+            } else if self.flag_max_size.is_none() {
+                params.push(format!("max_size={}", overflow_depth));
+            }
+        }
+        if self.flag_no_cache {
+            params.push("caching_enabled=false".to_string());
+        }
+        parse_solver_choice(&self.flag_solver, params.iter().map(|p| &p[..]))
+    }
+}
+
+/// Builds a [`SolverChoice`] from a solver name (`slg` or `recursive`) and a
+/// list of `key=value` parameters. Parameters that are not given keep the
+/// solver's default value.
+fn parse_solver_choice<'a>(
+    name: &str,
+    params: impl Iterator<Item = &'a str>,
+) -> Result<SolverChoice> {
+    let mut solver_choice = match name {
+        "slg" => SolverChoice::slg_default(),
+        "recursive" => SolverChoice::recursive_default(),
+        _ => {
+            return Err(format!("unknown solver `{}`; expected `slg` or `recursive`", name).into())
+        }
+    };
+
+    for param in params {
+        let (key, value) = match param.find('=') {
+            Some(index) => (&param[..index], &param[index + 1..]),
+            None => return Err(format!("expected `key=value`, found `{}`", param).into()),
+        };
+        match (&mut solver_choice, key) {
+            (SolverChoice::SLG { max_size, .. }, "max_size")
+            | (SolverChoice::Recursive { max_size, .. }, "max_size") => {
+                *max_size = value.parse()?;
+            }
+            (SolverChoice::Recursive { overflow_depth, .. }, "overflow_depth") => {
+                *overflow_depth = value.parse()?;
+                if *overflow_depth == 0 {
+                    return Err("overflow depth must be at least 1".into());
+                }
+            }
+            (
+                SolverChoice::Recursive {
+                    caching_enabled, ..
+                },
+                "caching_enabled",
+            ) => {
+                *caching_enabled = value.parse()?;
+            }
+            _ => {
+                return Err(format!(
+                    "parameter `{}` is not supported by the `{}` solver",
+                    key, name
+                )
+                .into())
+            }
         }
     }
+
+    Ok(solver_choice)
 }
 
 fn main() {
@@ -307,3 +398,159 @@ fn main() {
         })
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(solver: &str) -> Args {
+        Args {
+            flag_program: None,
+            flag_goal: vec![],
+            flag_solver: solver.to_string(),
+            flag_max_size: None,
+            flag_overflow_depth: None,
+            flag_no_cache: false,
+            flag_multiple: false,
+        }
+    }
+
+    #[test]
+    fn parse_solver_choice_defaults() {
+        assert_eq!(
+            parse_solver_choice("slg", std::iter::empty()).unwrap(),
+            SolverChoice::slg_default()
+        );
+        assert_eq!(
+            parse_solver_choice("recursive", std::iter::empty()).unwrap(),
+            SolverChoice::recursive_default()
+        );
+    }
+
+    #[test]
+    fn parse_solver_choice_params() {
+        assert_eq!(
+            parse_solver_choice("slg", vec!["max_size=7"].into_iter()).unwrap(),
+            SolverChoice::slg(7, None)
+        );
+        assert_eq!(
+            parse_solver_choice(
+                "recursive",
+                vec!["max_size=5", "overflow_depth=3", "caching_enabled=false"].into_iter()
+            )
+            .unwrap(),
+            SolverChoice::Recursive {
+                overflow_depth: 3,
+                caching_enabled: false,
+                max_size: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_solver_choice_errors() {
+        let error = |name, params: Vec<&'static str>| {
+            parse_solver_choice(name, params.into_iter())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("foo", vec![]),
+            "unknown solver `foo`; expected `slg` or `recursive`"
+        );
+        assert_eq!(
+            error("slg", vec!["max_size"]),
+            "expected `key=value`, found `max_size`"
+        );
+        assert_eq!(
+            error("slg", vec!["overflow_depth=3"]),
+            "parameter `overflow_depth` is not supported by the `slg` solver"
+        );
+        assert_eq!(
+            error("recursive", vec!["overflow_depth=0"]),
+            "overflow depth must be at least 1"
+        );
+        assert!(parse_solver_choice("slg", vec!["max_size=x"].into_iter()).is_err());
+    }
+
+    #[test]
+    fn args_solver_choice() {
+        assert_eq!(
+            args("slg").solver_choice().unwrap(),
+            SolverChoice::slg_default()
+        );
+
+        let mut recursive = args("recursive");
+        recursive.flag_max_size = Some(5);
+        recursive.flag_overflow_depth = Some(3);
+        recursive.flag_no_cache = true;
+        assert_eq!(
+            recursive.solver_choice().unwrap(),
+            SolverChoice::Recursive {
+                overflow_depth: 3,
+                caching_enabled: false,
+                max_size: 5,
+            }
+        );
+
+        let mut no_cache = args("slg");
+        no_cache.flag_no_cache = true;
+        assert!(no_cache.solver_choice().is_err());
+
+        let mut zero_depth = args("recursive");
+        zero_depth.flag_overflow_depth = Some(0);
+        assert!(zero_depth.solver_choice().is_err());
+    }
+
+    #[test]
+    fn args_overflow_depth_sets_slg_max_size() {
+        let mut slg = args("slg");
+        slg.flag_overflow_depth = Some(4);
+        assert_eq!(slg.solver_choice().unwrap(), SolverChoice::slg(4, None));
+
+        slg.flag_max_size = Some(6);
+        assert_eq!(slg.solver_choice().unwrap(), SolverChoice::slg(6, None));
+
+        slg.flag_overflow_depth = Some(0);
+        assert!(slg.solver_choice().is_err());
+    }
+
+    #[test]
+    fn repl_solver_command() {
+        let args = args("slg");
+        let mut solver_choice = args.solver_choice().unwrap();
+        let mut rl = rustyline::Editor::new();
+        let mut prog =
+            Some(LoadedProgram::new("struct Foo {}".to_string(), solver_choice).unwrap());
+
+        process(&args, &mut solver_choice, "solver", &mut rl, &mut prog).unwrap();
+        process(
+            &args,
+            &mut solver_choice,
+            "solver recursive overflow_depth=3",
+            &mut rl,
+            &mut prog,
+        )
+        .unwrap();
+        let expected = SolverChoice::Recursive {
+            overflow_depth: 3,
+            caching_enabled: true,
+            max_size: 30,
+        };
+        assert_eq!(solver_choice, expected);
+        assert_eq!(prog.as_ref().unwrap().db.solver_choice(), expected);
+
+        // Invalid commands keep the current solver.
+        assert!(process(&args, &mut solver_choice, "solver", &mut rl, &mut prog).is_ok());
+        assert!(process(&args, &mut solver_choice, "solver foo", &mut rl, &mut prog).is_err());
+        assert!(process(
+            &args,
+            &mut solver_choice,
+            "solver slg overflow_depth=3",
+            &mut rl,
+            &mut prog
+        )
+        .is_err());
+        assert_eq!(solver_choice, expected);
+    }
+}
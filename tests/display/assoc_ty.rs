@@ -15,6 +15,24 @@ fn test_trait_impl_assoc_type() {
     );
 }
 #[test]
+fn test_trait_impl_default_assoc_type() {
+    reparse_test!(
+        program {
+            struct Foo { }
+            struct Floo { }
+            trait Bar {
+                type Assoc;
+            }
+            impl<T> Bar for T {
+                default type Assoc = Floo;
+            }
+            impl Bar for Foo {
+                type Assoc = Foo;
+            }
+        }
+    );
+}
+#[test]
 fn test_trait_with_multiple_assoc_types() {
     // Test multiple associated types per trait
     reparse_test!(
@@ -179,6 +197,22 @@ fn test_simple_generic_assoc_type_with_where_clause() {
     );
 }
 
+#[test]
+fn test_assoc_type_defaults() {
+    // Test that defaults render before the where clauses, and can refer to
+    // `Self`, the trait's generics and the associated type's generics.
+    reparse_test!(
+        program {
+            trait Trait {}
+            struct Foo<A, B> { }
+            trait Bar<T> {
+                type Assoc = Self;
+                type Assoc2<Y>: Trait = Foo<T, Y> where Y: Trait;
+            }
+        }
+    );
+}
+
 #[test]
 fn test_assoc_type_in_generic_trait() {
     // Test traits with both generics and associated types render correctly.
@@ -61,3 +61,56 @@ fn test_basic_const_values_in_assoc_ty_values() {
         }
     );
 }
+
+#[test]
+fn test_assoc_consts() {
+    // Test we render associated const declarations, values and projections.
+    reparse_test!(
+        program {
+            struct Foo<const N> { }
+            struct Bar<T> { }
+            trait Baz<T> {
+                type Assoc;
+                const N: u32;
+            }
+            impl<const N> Baz<Foo<N>> for Foo<N> {
+                type Assoc = Foo<N>;
+                const N: u32 = N;
+            }
+            impl<T> Baz<T> for Bar<T> where T: Baz<T> {
+                type Assoc = Foo<<T as Baz<T>>::N>;
+                const N: u32 = <T as Baz<T>>::N;
+            }
+        }
+    );
+}
+
+#[test]
+fn test_typed_const_params() {
+    // Test we render the types of const parameters.
+    reparse_test!(
+        program {
+            struct Foo<const B: bool, const C: char, const N: i16> { }
+            trait Bar<const N: usize> { }
+            impl<const N: usize> Bar<N> for Foo<true, 'x', -7> { }
+        }
+    );
+}
+
+#[test]
+fn test_const_exprs() {
+    // Test we render const expressions so that they parse back the same.
+    reparse_test!(
+        program {
+            struct Matrix<const R, const C> { }
+            trait Flatten {
+                type Output;
+            }
+            impl<const R, const C> Flatten for Matrix<R, C> {
+                type Output = [u8; {{R * C} + {R % 2}}];
+            }
+            trait Halve<const N> { }
+            impl<const N> Halve<{N - 1}> for Matrix<{N / 2}, 1> { }
+        }
+    );
+}
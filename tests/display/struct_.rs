@@ -102,6 +102,19 @@ fn test_struct_repr() {
     );
 }
 
+#[test]
+fn test_struct_lang_item() {
+    // Test printing a `#[lang]` attribute between the flags and the repr.
+    reparse_test!(
+        program {
+            #[upstream]
+            #[lang(dyn_metadata)]
+            #[repr(C)]
+            struct UpstreamDynMetadata<Dyn> {}
+        }
+    );
+}
+
 #[test]
 fn test_struct_repr_with_flags() {
     // Test printing both a repr and a flag (to ensure we get the ordering between them right).
@@ -113,3 +126,16 @@ fn test_struct_repr_with_flags() {
         }
     );
 }
+
+#[test]
+fn test_struct_param_defaults() {
+    reparse_test!(
+        program {
+            struct RandomState {}
+            struct HashMap<K, V, S = RandomState> {}
+            struct Pair<A, B = A> {}
+            struct Buf<T, const N: usize = 16> {}
+            enum Either<L, R = L> {}
+        }
+    );
+}
@@ -81,7 +81,18 @@ fn test_trait_flags() {
 fn test_wellknown_traits() {
     // Test all possible `#[lang]` attributes on traits.
     let well_knowns = vec![
-        "sized", "copy", "clone", "drop", "fn_once", "fn_mut", "fn", "unsize",
+        "sized",
+        "copy",
+        "clone",
+        "drop",
+        "fn_once",
+        "fn_mut",
+        "fn",
+        "unsize",
+        "dispatch_from_dyn",
+        "deref",
+        "pointee",
+        "generator",
     ];
     for flag in well_knowns {
         reparse_test(&format!(
@@ -108,3 +119,39 @@ fn test_lang_with_flag() {
         }
     );
 }
+
+#[test]
+fn test_trait_alias() {
+    // Test printing trait aliases, whose bounds are printed as where clauses
+    reparse_test!(
+        program {
+            trait Foo<T> {}
+            trait Bar {}
+            trait Baz {}
+            trait Alias<T> = Foo<T> + Bar where T: Baz;
+        }
+        produces {
+            trait Foo<T> {}
+            trait Bar {}
+            trait Baz {}
+            trait Alias<T> = where Self: Foo<T>, Self: Bar, T: Baz;
+        }
+    );
+    reparse_test!(
+        program {
+            trait Foo {}
+            trait Alias = where Self: Foo;
+        }
+    );
+}
+
+#[test]
+fn test_trait_param_defaults() {
+    reparse_test!(
+        program {
+            trait Add<Rhs = Self> {}
+            trait Convert<T, U = Vec<T>> {}
+            struct Vec<T> {}
+        }
+    );
+}
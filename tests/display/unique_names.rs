@@ -49,6 +49,9 @@ where
     fn assoc_type_name(&self, _assoc_ty_id: chalk_ir::AssocTypeId<I>) -> String {
         "Foo".to_owned()
     }
+    fn assoc_const_name(&self, _assoc_const_id: chalk_ir::AssocConstId<I>) -> String {
+        "Foo".to_owned()
+    }
     fn opaque_type_name(&self, _opaque_ty_id: chalk_ir::OpaqueTyId<I>) -> String {
         "Foo".to_owned()
     }
@@ -97,6 +100,18 @@ where
     ) -> std::sync::Arc<chalk_solve::rust_ir::AssociatedTyValue<I>> {
         self.db.associated_ty_value(id)
     }
+    fn associated_const_data(
+        &self,
+        id: chalk_ir::AssocConstId<I>,
+    ) -> std::sync::Arc<chalk_solve::rust_ir::AssociatedConstDatum<I>> {
+        self.db.associated_const_data(id)
+    }
+    fn associated_const_value(
+        &self,
+        id: chalk_solve::rust_ir::AssociatedConstValueId<I>,
+    ) -> std::sync::Arc<chalk_solve::rust_ir::AssociatedConstValue<I>> {
+        self.db.associated_const_value(id)
+    }
     fn generator_datum(
         &self,
         generator_id: chalk_ir::GeneratorId<I>,
@@ -145,6 +160,12 @@ where
     ) -> Option<chalk_ir::TraitId<I>> {
         self.db.well_known_trait_id(well_known_trait)
     }
+    fn well_known_adt_id(
+        &self,
+        well_known_adt: chalk_solve::rust_ir::WellKnownAdt,
+    ) -> Option<chalk_ir::AdtId<I>> {
+        self.db.well_known_adt_id(well_known_adt)
+    }
     fn program_clauses_for_env(
         &self,
         environment: &chalk_ir::Environment<I>,
@@ -238,3 +238,16 @@ fn test_forall_in_where() {
         }
     );
 }
+
+#[test]
+fn test_negative_bound() {
+    reparse_test!(
+        program {
+            struct Foo { }
+            trait Bar { }
+            trait Baz { }
+            impl !Baz for Foo { }
+            impl<T> Bar for T where T: !Baz { }
+        }
+    );
+}
@@ -71,6 +71,7 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
                 VariableKinds::empty(&ChalkIr),
                 TraitDatumBound {
                     where_clauses: vec![],
+                    param_defaults: vec![],
                 },
             ),
             flags: TraitFlags {
@@ -80,8 +81,10 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
                 fundamental: false,
                 non_enumerable: false,
                 coinductive: false,
+                alias: false,
             },
             associated_ty_ids: vec![],
+            associated_const_ids: vec![],
             well_known: None,
         })
     }
@@ -115,6 +118,7 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
             binders,
             impl_type: ImplType::Local,
             associated_ty_value_ids: vec![],
+            associated_const_value_ids: vec![],
         })
     }
 
@@ -125,6 +129,20 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
         unimplemented!()
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        unimplemented!()
+    }
+
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        unimplemented!()
+    }
+
     fn opaque_ty_data(&self, id: OpaqueTyId<ChalkIr>) -> Arc<OpaqueTyDatum<ChalkIr>> {
         unimplemented!()
     }
@@ -141,6 +159,7 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
                 AdtDatumBound {
                     variants: vec![],
                     where_clauses: vec![],
+                    param_defaults: vec![],
                 },
             ),
             flags: AdtFlags {
@@ -199,6 +218,10 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
         unimplemented!()
     }
 
+    fn well_known_adt_id(&self, well_known_adt: WellKnownAdt) -> Option<AdtId<ChalkIr>> {
+        unimplemented!()
+    }
+
     fn program_clauses_for_env(
         &self,
         environment: &Environment<ChalkIr>,
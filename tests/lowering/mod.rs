@@ -195,6 +195,7 @@ fn atc_accounting() {
     value: for<lifetime, type> AssociatedTyValueBound {
         ty: Iter<'^0.0, ^0.1>
     },
+    default: false,
 }"#
             .replace(",\n", "\n"),
         );
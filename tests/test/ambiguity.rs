@@ -0,0 +1,134 @@
+//! Tests that ambiguous solutions report why they are ambiguous.
+
+use super::*;
+use chalk_solve::AmbiguityCause;
+
+fn solve(
+    program_text: &str,
+    goal_text: &str,
+    solver_choice: SolverChoice,
+) -> Option<Solution<ChalkIr>> {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        solver_choice.into_solver().solve(db, peeled_goal)
+    })
+}
+
+fn display(solution: &Solution<ChalkIr>) -> String {
+    format!("{}", solution.display(&ChalkIr))
+}
+
+const OVERFLOW: &str = stringify!({
+    trait Q {}
+    struct Z {}
+    struct G<X> {}
+    struct S<X> {}
+
+    impl Q for Z {}
+    impl<X> Q for G<X> where X: Q {}
+    impl<X> Q for S<X>
+    where
+        X: Q,
+        S<G<X>>: Q,
+    {
+    }
+});
+
+#[test]
+fn overflow_reports_goal_stack() {
+    let solution = solve(
+        OVERFLOW,
+        stringify!({ S<Z>: Q }),
+        SolverChoice::recursive(1000, 10),
+    )
+    .unwrap();
+    assert_eq!(
+        display(&solution),
+        "Ambiguous; no inference guidance (overflow)"
+    );
+    let stack = match solution.ambiguity_cause() {
+        Some(AmbiguityCause::Overflow(stack)) => stack,
+        cause => panic!("expected overflow, got {:?}", cause),
+    };
+    assert_eq!(stack.len(), 11);
+    let overflowed = solution
+        .ambiguity_cause()
+        .unwrap()
+        .overflowed_goal()
+        .unwrap();
+    assert_eq!(overflowed, stack.last().unwrap());
+    // The stack is a chain of distinct goals, not a cycle.
+    for (i, goal) in stack.iter().enumerate() {
+        assert!(!stack[..i].contains(goal));
+    }
+}
+
+#[test]
+fn truncation_is_not_ambiguity() {
+    for &solver_choice in &[
+        SolverChoice::slg(10, None),
+        SolverChoice::recursive(10, 100),
+    ] {
+        let solution = solve(OVERFLOW, stringify!({ S<Z>: Q }), solver_choice).unwrap();
+        assert_eq!(
+            display(&solution),
+            "Ambiguous; no inference guidance (truncated)",
+            "{:?}",
+            solver_choice
+        );
+        assert_eq!(
+            solution.ambiguity_cause(),
+            Some(&AmbiguityCause::Truncation)
+        );
+    }
+}
+
+#[test]
+fn floundering_is_not_ambiguity() {
+    let program = stringify!({
+        trait A {}
+        struct Vec<T> {}
+        impl<T> A for Vec<T> {}
+    });
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let solution = solve(
+            program,
+            stringify!({ exists<T> { not { T: A } } }),
+            solver_choice,
+        )
+        .unwrap();
+        assert_eq!(
+            solution.ambiguity_cause(),
+            Some(&AmbiguityCause::Floundered),
+            "{:?}",
+            solver_choice
+        );
+        assert!(display(&solution).ends_with("(floundered)"));
+    }
+}
+
+#[test]
+fn genuine_ambiguity() {
+    let program = stringify!({
+        trait Foo {}
+        struct A {}
+        struct B {}
+        impl Foo for A {}
+        impl Foo for B {}
+    });
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let solution = solve(program, stringify!({ exists<T> { T: Foo } }), solver_choice).unwrap();
+        assert_eq!(
+            display(&solution),
+            "Ambiguous; no inference guidance",
+            "{:?}",
+            solver_choice
+        );
+        assert_eq!(solution.ambiguity_cause(), Some(&AmbiguityCause::Ambiguity));
+    }
+}
@@ -0,0 +1,264 @@
+//! Tests related to associated consts and const projections.
+
+use super::*;
+
+#[test]
+fn normalize_assoc_const() {
+    test! {
+        program {
+            struct S {}
+            struct U {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl Trait for S {
+                const N: u32 = 3;
+            }
+        }
+
+        goal {
+            Normalize(<S as Trait>::N -> 3)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<S as Trait>::N -> 5)
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<const N> {
+                Normalize(<S as Trait>::N -> N)
+            }
+        } yields {
+            "Unique; substitution [?0 := 3], lifetime constraints []"
+        }
+
+        goal {
+            exists<const N> {
+                Normalize(<U as Trait>::N -> N)
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_in_array_len() {
+    test! {
+        program {
+            struct S {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl Trait for S {
+                const N: u32 = 3;
+            }
+        }
+
+        goal {
+            [u8; <S as Trait>::N] = [u8; 3]
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [u8; <S as Trait>::N] = [u8; 4]
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<const M> {
+                [u8; <S as Trait>::N] = [u8; M]
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_consts_normalizing_to_the_same_value() {
+    test! {
+        program {
+            struct A {}
+            struct B {}
+            struct C {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl Trait for A {
+                const N: u32 = 3;
+            }
+
+            impl Trait for B {
+                const N: u32 = 3;
+            }
+
+            impl Trait for C {
+                const N: u32 = 4;
+            }
+        }
+
+        goal {
+            [u8; <A as Trait>::N] = [u8; <B as Trait>::N]
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [u8; <A as Trait>::N] = [u8; <C as Trait>::N]
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_as_generic_arg() {
+    test! {
+        program {
+            struct S {}
+            struct Foo<const N> {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            trait Marker {}
+
+            impl Trait for S {
+                const N: u32 = 3;
+            }
+
+            impl Marker for Foo<3> {}
+        }
+
+        goal {
+            Foo<<S as Trait>::N>: Marker
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_generic_impl() {
+    test! {
+        program {
+            struct S<const N> {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl<const M> Trait for S<M> {
+                const N: u32 = M;
+            }
+        }
+
+        goal {
+            Normalize(<S<5> as Trait>::N -> 5)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<const M> {
+                [u8; <S<M> as Trait>::N] = [u8; M]
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_rigid_in_generic_context() {
+    test! {
+        program {
+            trait Trait {
+                const N: u32;
+            }
+        }
+
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    [u8; <T as Trait>::N] = [u8; <T as Trait>::N]
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    exists<const N> {
+                        [u8; <T as Trait>::N] = [u8; N]
+                    }
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := <!1_0 as Trait>::N], lifetime constraints []"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    [u8; <T as Trait>::N] = [u8; 3]
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_const_lowering_errors() {
+    lowering_error! {
+        program {
+            struct S {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl Trait for S {
+                const M: u32 = 3;
+            }
+        }
+        error_msg {
+            "no associated const `M` defined in trait"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct S {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl Trait for S {
+                const N: u8 = 3;
+            }
+        }
+        error_msg {
+            "value of associated const `N` does not have the declared type"
+        }
+    }
+}
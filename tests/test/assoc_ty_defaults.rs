@@ -0,0 +1,230 @@
+//! Tests related to defaults of associated types in trait definitions.
+
+use super::*;
+
+#[test]
+fn assoc_ty_default_used_by_impl() {
+    test! {
+        program {
+            trait Add<Rhs = Self> {
+                type Output = Self;
+            }
+
+            struct Meters {}
+            struct Feet {}
+
+            impl Add for Meters {}
+
+            impl Add<Feet> for Meters {
+                type Output = Feet;
+            }
+        }
+
+        goal {
+            Normalize(<Meters as Add>::Output -> Meters)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<X> {
+                Normalize(<Meters as Add<Feet>>::Output -> X)
+            }
+        } yields {
+            "Unique; substitution [?0 := Feet]"
+        }
+
+        goal {
+            Normalize(<Meters as Add<Feet>>::Output -> Meters)
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn generic_assoc_ty_default() {
+    test! {
+        program {
+            trait Foo<T> {
+                type Out<U> = (Self, T, U);
+            }
+
+            struct Vec<T> {}
+
+            impl<X> Foo<u32> for Vec<X> {}
+        }
+
+        goal {
+            forall<X> {
+                Normalize(<Vec<X> as Foo<u32>>::Out<u8> -> (Vec<X>, u32, u8))
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn assoc_ty_default_not_normalized_in_trait() {
+    test! {
+        program {
+            trait Foo {
+                type Out = u32;
+            }
+
+            impl Foo for u8 {}
+        }
+
+        // Impls may override the default, so it can't be assumed for an
+        // arbitrary implementation of the trait.
+        goal {
+            forall<T> {
+                if (T: Foo) {
+                    Normalize(<T as Foo>::Out -> u32)
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Normalize(<u8 as Foo>::Out -> u32)
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn assoc_ty_default_and_specialization() {
+    test! {
+        program {
+            trait Foo {
+                type Out = Self;
+            }
+
+            struct Vec<T> {}
+            struct A {}
+            struct B {}
+
+            impl<T> Foo for Vec<T> {}
+            impl Foo for Vec<A> {
+                type Out = B;
+            }
+            impl Foo for Vec<B> {}
+        }
+
+        // The default may be overridden by a specializing impl.
+        goal {
+            Normalize(<Vec<u32> as Foo>::Out -> Vec<u32>)
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            if (Reveal) {
+                Normalize(<Vec<u32> as Foo>::Out -> Vec<u32>)
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<Vec<A> as Foo>::Out -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := B]"
+        }
+
+        // A specializing impl without a value inherits the one of the impl
+        // it specializes.
+        goal {
+            if (Reveal) {
+                Normalize(<Vec<B> as Foo>::Out -> Vec<B>)
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn assoc_ty_default_with_specializing_impl_bounded_by_projection() {
+    // Whether the second impl specializes the first, and so inherits its
+    // value instead of the default, is found out by normalizing `<T as
+    // Foo>::Out`.
+    test! {
+        program {
+            trait Foo {
+                type Out = A;
+            }
+            trait Bar {}
+
+            struct A {}
+            struct Wrap<T> {}
+
+            impl Bar for A {}
+            impl<T> Foo for T {}
+            impl<T> Foo for Wrap<T> where <T as Foo>::Out: Bar {}
+        }
+
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<Wrap<A> as Foo>::Out -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+    }
+}
+
+#[test]
+fn assoc_ty_defaults_well_formed() {
+    lowering_success! {
+        program {
+            trait Clone {}
+            struct Set<T> where T: Clone {}
+
+            trait Foo where Self: Clone {
+                type Out: Clone = Self;
+            }
+
+            trait Bar {
+                type Out<T> = Set<T> where T: Clone;
+            }
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Clone {}
+
+            trait Foo {
+                type Out: Clone = Self;
+            }
+        }
+        error_msg {
+            "trait declaration `Foo` does not meet well-formedness requirements"
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Clone {}
+            struct Set<T> where T: Clone {}
+
+            trait Foo {
+                type Out = Set<Self>;
+            }
+        }
+        error_msg {
+            "trait declaration `Foo` does not meet well-formedness requirements"
+        }
+    }
+}
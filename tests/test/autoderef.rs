@@ -0,0 +1,219 @@
+//! Tests for the builtin `Deref` impls and `autoderef_steps`.
+
+use super::*;
+use chalk_ir::{DomainGoal, GoalData, WhereClause};
+use chalk_solve::autoderef::AUTODEREF_RECURSION_LIMIT;
+use chalk_solve::autoderef_steps;
+
+/// Computes the autoderef steps of the self type of the `Implemented` goal
+/// in `goal_text`, in the environment given by the `forall`s and `if`s around
+/// it. Returns the steps, formatted, and whether they overflowed.
+fn autoderef(
+    program_text: &str,
+    goal_text: &str,
+    solver_choice: SolverChoice,
+) -> (Vec<String>, bool) {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let interner = db.interner();
+        let peeled_goal = &peeled_goal.canonical.value;
+        let ty = match peeled_goal.goal.data(interner) {
+            GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(trait_ref))) => {
+                trait_ref.self_type_parameter(interner)
+            }
+            _ => panic!("expected an `Implemented` goal"),
+        };
+
+        let mut solver = solver_choice.into_solver();
+        let steps = autoderef_steps(db, &mut *solver, &peeled_goal.environment, ty);
+        (
+            steps.steps.iter().map(|ty| format!("{:?}", ty)).collect(),
+            steps.overflowed,
+        )
+    })
+}
+
+const PROGRAM: &str = stringify!({
+    #[lang(deref)]
+    trait Deref {
+        type Target;
+    }
+    trait Probe {}
+
+    struct S {}
+    struct Rc<T> {}
+    struct Wrapper<T> {}
+    struct Loop {}
+    struct Deep<T> {}
+
+    impl<T> Deref for Rc<T> {
+        type Target = T;
+    }
+    impl<T> Deref for Wrapper<T> {
+        type Target = Rc<T>;
+    }
+    impl Deref for Loop {
+        type Target = Loop;
+    }
+    impl<T> Deref for Deep<T> {
+        type Target = Deep<Deep<T>>;
+    }
+});
+
+#[test]
+fn builtin_deref() {
+    test! {
+        program {
+            #[lang(deref)]
+            trait Deref {
+                type Target;
+            }
+            struct S {}
+        }
+
+        goal {
+            forall<'a> {
+                &'a S: Deref
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                &'a mut S: Deref<Target = S>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                exists<T> {
+                    Normalize(<&'a S as Deref>::Target -> T)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := S]"
+        }
+
+        goal {
+            S: Deref
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            *const S: Deref
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn autoderef_chains() {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        // Builtin derefs of references
+        assert_eq!(
+            autoderef(
+                PROGRAM,
+                stringify!({ forall<'a> { &'a &'a S: Probe } }),
+                solver_choice
+            ),
+            (
+                vec![
+                    "(&'!1_0 (&'!1_0 S))".to_string(),
+                    "(&'!1_0 S)".to_string(),
+                    "S".to_string()
+                ],
+                false
+            ),
+            "{:?}",
+            solver_choice
+        );
+
+        // Overloaded derefs, mixed with builtin ones
+        assert_eq!(
+            autoderef(
+                PROGRAM,
+                stringify!({ forall<'a> { Wrapper<&'a S>: Probe } }),
+                solver_choice
+            ),
+            (
+                vec![
+                    "Wrapper<(&'!1_0 S)>".to_string(),
+                    "Rc<(&'!1_0 S)>".to_string(),
+                    "(&'!1_0 S)".to_string(),
+                    "S".to_string()
+                ],
+                false
+            ),
+            "{:?}",
+            solver_choice
+        );
+
+        // Types without a `Deref` impl end the chain
+        assert_eq!(
+            autoderef(
+                PROGRAM,
+                stringify!({ forall<T> { T: Probe } }),
+                solver_choice
+            ),
+            (vec!["!1_0".to_string()], false),
+            "{:?}",
+            solver_choice
+        );
+    }
+
+    // Deref bounds from the environment are used. The SLG solver can't
+    // tell whether `<T as Deref>::Target` normalizes to `S` or not.
+    assert_eq!(
+        autoderef(
+            PROGRAM,
+            stringify!({ forall<T> { if (T: Deref<Target = S>) { Rc<T>: Probe } } }),
+            SolverChoice::recursive_default()
+        ),
+        (
+            vec!["Rc<!1_0>".to_string(), "!1_0".to_string(), "S".to_string()],
+            false
+        )
+    );
+}
+
+#[test]
+fn autoderef_overflow() {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        // A type that derefs to itself
+        assert_eq!(
+            autoderef(PROGRAM, stringify!({ Loop: Probe }), solver_choice),
+            (vec!["Loop".to_string()], true),
+            "{:?}",
+            solver_choice
+        );
+
+        // A chain that never ends; the SLG solver truncates the types
+        // before we hit the limit
+        let (steps, overflowed) = autoderef(PROGRAM, stringify!({ Deep<S>: Probe }), solver_choice);
+        assert!(overflowed, "{:?}", solver_choice);
+        assert!(
+            steps.len() <= AUTODEREF_RECURSION_LIMIT + 1,
+            "{:?}",
+            solver_choice
+        );
+        assert_eq!(steps[1], "Deep<Deep<S>>", "{:?}", solver_choice);
+    }
+
+    let (steps, overflowed) = autoderef(
+        PROGRAM,
+        stringify!({ Deep<S>: Probe }),
+        SolverChoice::recursive_default(),
+    );
+    assert!(overflowed);
+    assert_eq!(steps.len(), AUTODEREF_RECURSION_LIMIT + 1);
+}
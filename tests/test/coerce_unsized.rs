@@ -0,0 +1,187 @@
+//! Tests targeting the builtin CoerceUnsized and DispatchFromDyn impls
+
+use super::*;
+
+#[test]
+fn coerce_unsized_pointers() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+        }
+
+        goal {
+            forall<'a> {
+                &'a [u32; 3]: CoerceUnsized<&'a [u32]>
+            }
+        } yields {
+            "Unique"
+        }
+
+        // The lifetime may be shortened
+        goal {
+            forall<'a, 'b> {
+                &'a [u32; 3]: CoerceUnsized<&'b [u32]>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_1 }]"
+        }
+
+        goal {
+            forall<'a> {
+                &'a mut [u32; 3]: CoerceUnsized<&'a [u32]>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                &'a mut [u32; 3]: CoerceUnsized<*const [u32]>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            *mut [u32; 3]: CoerceUnsized<*const [u32]>
+        } yields {
+            "Unique"
+        }
+
+        // Mutability can't be gained
+        goal {
+            forall<'a> {
+                &'a [u32; 3]: CoerceUnsized<&'a mut [u32]>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            *const [u32; 3]: CoerceUnsized<*mut [u32]>
+        } yields {
+            "No possible solution"
+        }
+
+        // A raw pointer can't become a reference
+        goal {
+            forall<'a> {
+                *const [u32; 3]: CoerceUnsized<&'a [u32]>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The pointee has to be unsizeable
+        goal {
+            *const [u32; 3]: CoerceUnsized<*const [u64]>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> {
+                *const [u32; 3]: CoerceUnsized<*const [T]>
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U32)]"
+        }
+    }
+}
+
+#[test]
+fn dispatch_from_dyn_pointers() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            #[lang(sized)]
+            trait Sized {}
+
+            #[object_safe]
+            trait Foo {}
+            struct S {}
+            impl Foo for S {}
+        }
+
+        goal {
+            forall<'a> {
+                &'a S: DispatchFromDyn<&'a (dyn Foo + 'a)>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                *mut S: DispatchFromDyn<*mut (dyn Foo + 'a)>
+            }
+        } yields {
+            "Unique"
+        }
+
+        // The kind of pointer can't change
+        goal {
+            forall<'a> {
+                &'a mut S: DispatchFromDyn<&'a (dyn Foo + 'a)>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                &'a S: DispatchFromDyn<*const (dyn Foo + 'a)>
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn dispatch_from_dyn_smart_pointer() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            #[lang(sized)]
+            trait Sized {}
+
+            #[phantom_data]
+            struct PhantomData<T> {}
+
+            struct Rc<T> {
+                ptr: *const T,
+                phantom: PhantomData<T>,
+            }
+
+            impl<T, U> DispatchFromDyn<Rc<U>> for Rc<T> where T: Unsize<U> {}
+
+            #[object_safe]
+            trait Foo {}
+            struct S {}
+            impl Foo for S {}
+        }
+
+        goal {
+            forall<'a> {
+                Rc<S>: DispatchFromDyn<Rc<dyn Foo + 'a>>
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
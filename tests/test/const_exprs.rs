@@ -0,0 +1,267 @@
+//! Tests related to generic const expressions.
+
+use super::*;
+
+#[test]
+fn closed_const_exprs() {
+    test! {
+        program {
+            struct S<const N> {}
+            struct Byte<const N: u8> {}
+
+            trait Trait {}
+
+            impl Trait for S<5> {}
+            impl Trait for Byte<255> {}
+        }
+
+        goal {
+            S<{2 + 3}>: Trait
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            S<{2 * 3 - 1}>: Trait
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            S<{2 * (3 - 1)}>: Trait
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<const N> {
+                S<N> = S<{17 % 6}>
+            }
+        } yields {
+            "Unique; substitution [?0 := 5], lifetime constraints []"
+        }
+
+        goal {
+            Byte<{254 + 1}>: Trait
+        } yields {
+            "Unique"
+        }
+
+        // A closed expression that overflows is an evaluation error.
+        goal {
+            Byte<{255 + 1}>: Trait
+        } yields {
+            "No possible solution"
+        }
+
+    }
+}
+
+#[test]
+fn symbolic_const_exprs() {
+    test! {
+        program {
+            struct S<const N> {}
+        }
+
+        goal {
+            forall<const N> {
+                S<{N + 1}> = S<{N + 1}>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<const N> {
+                exists<const M> {
+                    S<{N + 1}> = S<{M + 1}>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := !1_0], lifetime constraints []"
+        }
+
+        goal {
+            forall<const N> {
+                exists<const M> {
+                    S<M> = S<{N * 2}>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := {!1_0 * 2}], lifetime constraints []"
+        }
+
+        // Expressions that are not structurally equal may still be equal,
+        // so these are ambiguous rather than failing.
+        goal {
+            forall<const N> {
+                S<{N + 1}> = S<{1 + N}>
+            }
+        } yields {
+            "Ambiguous"
+        }
+
+        goal {
+            forall<const N, const M> {
+                S<{N * 0}> = S<{M * 0}>
+            }
+        } yields {
+            "Ambiguous"
+        }
+
+        goal {
+            exists<const N, const M> {
+                S<{N * 0}> = S<{M * 0}>
+            }
+        } yields {
+            "Ambiguous"
+        }
+
+        goal {
+            exists<const N, const M> {
+                S<{N + M}> = S<{M + N}>
+            }
+        } yields {
+            "Ambiguous"
+        }
+
+        // `N * 0` is `0` for every `N`, so `M` is not determined.
+        goal {
+            forall<const N> {
+                exists<const M> {
+                    S<{N * 0}> = S<{M * 0}>
+                }
+            }
+        } yields {
+            "Ambiguous"
+        }
+
+        goal {
+            exists<const N> {
+                S<{N * 0}> = S<{N * 0}>
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<const N> {
+                S<{N + 1}> = S<3>
+            }
+        } yields {
+            "Ambiguous"
+        }
+
+        // `N` occurs in the expression, but `{N * 1}` is `N` for every `N`.
+        goal {
+            exists<const N> {
+                S<N> = S<{N * 1}>
+            }
+        } yields {
+            "Ambiguous"
+        }
+    }
+}
+
+#[test]
+fn const_exprs_with_projections() {
+    test! {
+        program {
+            struct S {}
+            struct W<const N: u32> {}
+
+            trait Trait {
+                const N: u32;
+            }
+
+            impl Trait for S {
+                const N: u32 = 3;
+            }
+        }
+
+        goal {
+            W<{<S as Trait>::N + 1}> = W<4>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            W<{<S as Trait>::N + 1}> = W<5>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            W<{<S as Trait>::N * 2}> = W<{2 * 3}>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    W<{<T as Trait>::N + 1}> = W<{<T as Trait>::N + 1}>
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        // `<T as Trait>::N` cannot be normalized, but may still be `3`.
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    W<{<T as Trait>::N + 1}> = W<4>
+                }
+            }
+        } yields {
+            "Ambiguous"
+        }
+    }
+}
+
+#[test]
+fn const_exprs_in_impls() {
+    test! {
+        program {
+            struct Matrix<const R, const C> {}
+
+            trait Flatten {
+                type Output;
+            }
+
+            impl<const R, const C> Flatten for Matrix<R, C> {
+                type Output = [u8; {R * C}];
+            }
+        }
+
+        goal {
+            Normalize(<Matrix<2, 3> as Flatten>::Output -> [u8; 6])
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<const R, const C> {
+                Normalize(<Matrix<R, C> as Flatten>::Output -> [u8; {R * C}])
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn const_expr_lowering_errors() {
+    lowering_error! {
+        program {
+            struct Byte<const N: u8> {}
+            trait Trait {}
+            impl Trait for Byte<{1 + 256}> {}
+        }
+        error_msg {
+            "`256` is not a valid value of type `Uint(U8)`"
+        }
+    }
+}
@@ -149,3 +149,123 @@ fn placeholders_eq() {
         }
     }
 }
+
+#[test]
+fn typed_const_params() {
+    test! {
+        program {
+            struct Flag<const B: bool> {}
+            struct Letter<const C: char> {}
+            struct Signed<const N: i8> {}
+            struct Big<const N: u128> {}
+
+            trait Trait {}
+
+            impl Trait for Flag<true> {}
+            impl Trait for Letter<'a'> {}
+            impl Trait for Signed<-128> {}
+            impl Trait for Big<340282366920938463463374607431768211455> {}
+        }
+
+        goal {
+            Flag<true>: Trait
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Flag<false>: Trait
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<const B: bool> {
+                Flag<B>: Trait
+            }
+        } yields {
+            "Unique; substitution [?0 := true], lifetime constraints []"
+        }
+
+        goal {
+            exists<const C: char> {
+                Letter<C>: Trait
+            }
+        } yields {
+            "Unique; substitution [?0 := 'a'], lifetime constraints []"
+        }
+
+        goal {
+            Letter<'b'>: Trait
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<const N: i8> {
+                Signed<N>: Trait
+            }
+        } yields {
+            "Unique; substitution [?0 := -128], lifetime constraints []"
+        }
+
+        goal {
+            Big<340282366920938463463374607431768211455>: Trait
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Big<0>: Trait
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn typed_const_lowering_errors() {
+    lowering_error! {
+        program {
+            struct Flag<const B: bool> {}
+            trait Trait {}
+            impl Trait for Flag<1> {}
+        }
+        error_msg {
+            "`1` is not a valid value of type `Bool`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Signed<const N: i8> {}
+            trait Trait {}
+            impl Trait for Signed<128> {}
+        }
+        error_msg {
+            "`128` is not a valid value of type `Int(I8)`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Unsigned<const N: u64> {}
+            trait Trait {}
+            impl Trait for Unsigned<-1> {}
+        }
+        error_msg {
+            "`-1` is not a valid value of type `Uint(U64)`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Letter<const C: char> {}
+            trait Trait {}
+            impl<const N> Trait for Letter<N> {}
+        }
+        error_msg {
+            "incorrect const type: expected `Char`, found `Uint(U32)`"
+        }
+    }
+}
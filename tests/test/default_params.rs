@@ -0,0 +1,285 @@
+//! Tests related to defaults of type and const parameters.
+
+use super::*;
+
+#[test]
+fn adt_param_defaults() {
+    test! {
+        program {
+            struct RandomState {}
+            struct HashMap<K, V, S = RandomState> {}
+            struct Pair<A, B = A> {}
+
+            trait Foo {}
+
+            impl<K, V> Foo for HashMap<K, V> {}
+            impl<T> Foo for Pair<T> {}
+        }
+
+        goal {
+            HashMap<u32, u32, RandomState>: Foo
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            HashMap<u32, u32>: Foo
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<S> {
+                HashMap<u32, u32, S>: Foo
+            }
+        } yields {
+            "Unique; substitution [?0 := RandomState]"
+        }
+
+        goal {
+            Pair<u32, u32>: Foo
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Pair<u32, u8>: Foo
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                Pair<T> = Pair<T, T>
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn const_param_defaults() {
+    test! {
+        program {
+            struct Buf<const N: usize = 16> {}
+
+            trait Foo {}
+
+            impl Foo for Buf {}
+        }
+
+        goal {
+            Buf<16>: Foo
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Buf<8>: Foo
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Buf: Foo
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn trait_param_defaults() {
+    test! {
+        program {
+            trait Add<Rhs = Self> {
+                type Output;
+            }
+
+            struct Meters {}
+            struct Feet {}
+
+            impl Add for Meters {
+                type Output = Meters;
+            }
+
+            impl Add<Feet> for Meters {
+                type Output = Feet;
+            }
+        }
+
+        goal {
+            Meters: Add<Meters>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Meters: Add
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Feet: Add
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Normalize(<Meters as Add>::Output -> Meters)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<Meters as Add<Feet>>::Output -> Feet)
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn nested_param_defaults() {
+    test! {
+        program {
+            struct Inner<T = u32> {}
+            struct Outer<T = Inner> {}
+
+            trait Foo {}
+
+            impl Foo for Outer<Inner<u32>> {}
+        }
+
+        goal {
+            Outer: Foo
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn param_default_lowering_errors() {
+    lowering_error! {
+        program {
+            struct Foo<T = u32, U> {}
+        }
+        error_msg {
+            "parameters with a default must come last in the parameters of `Foo`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<T = U, U = u32> {}
+        }
+        error_msg {
+            "invalid parameter name `U`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<'a, T = 'a> {}
+        }
+        error_msg {
+            "incorrect parameter kind for `Foo`: expected type, found lifetime"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<T = Foo> {}
+        }
+        error_msg {
+            "cycle detected when computing the parameter defaults of `Foo`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<T = Bar> {}
+            struct Bar<T = Foo> {}
+        }
+        error_msg {
+            "cycle detected when computing the parameter defaults of `Foo`"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo<T, U = u32> {}
+            struct Bar {
+                field: Foo
+            }
+        }
+        error_msg {
+            "`Foo` takes 2 type parameters, not 0"
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Add<Rhs = Self> {}
+            struct Foo {
+                field: dyn Add + 'static
+            }
+        }
+        error_msg {
+            "the parameters of trait `Add` whose defaults refer to `Self` must be specified explicitly here"
+        }
+    }
+}
+
+#[test]
+fn param_defaults_well_formed() {
+    lowering_success! {
+        program {
+            trait Eq {}
+            impl Eq for u32 {}
+
+            struct Foo<T = u32> where T: Eq {}
+            trait Bar<T = u32> where T: Eq {}
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Eq {}
+            struct NotEq {}
+
+            struct Foo<T = NotEq> where T: Eq {}
+        }
+        error_msg {
+            "type declaration `Foo` does not meet well-formedness requirements"
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Eq {}
+            struct NotEq {}
+            struct Set<T> where T: Eq {}
+
+            struct Foo<T = Set<NotEq>> {}
+        }
+        error_msg {
+            "type declaration `Foo` does not meet well-formedness requirements"
+        }
+    }
+
+    lowering_error! {
+        program {
+            trait Eq {}
+            struct NotEq {}
+
+            trait Bar<T = NotEq> where T: Eq {}
+        }
+        error_msg {
+            "trait declaration `Bar` does not meet well-formedness requirements"
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Tests for the explanations of failed goals.
+
+use super::*;
+use chalk_recursive::RecursiveSolver;
+use chalk_solve::explain::explain_failure;
+
+macro_rules! explain_test {
+    (program $program:tt $(goal $goal:tt explanation { $expected:expr })*) => {
+        $(assert_explanation(stringify!($program), stringify!($goal), $expected);)*
+    };
+}
+
+fn assert_explanation(program_text: &str, goal_text: &str, expected: &str) {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let mut solver = RecursiveSolver::new(100, 30, true);
+        let explanation = explain_failure(db, &mut solver, peeled_goal, 10);
+        let explanation = match &explanation {
+            Some(explanation) => format!("{}", explanation),
+            None => "holds".to_string(),
+        };
+        assert_same(&explanation, expected);
+    })
+}
+
+#[test]
+fn missing_impl() {
+    explain_test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct S { }
+            struct U { }
+            struct Vec<T> { }
+            impl Bar for S { }
+            impl<T> Foo for Vec<T> where T: Bar { }
+        }
+
+        goal {
+            Vec<S>: Foo
+        } explanation {
+            "holds"
+        }
+
+        goal {
+            U: Foo
+        } explanation {
+            "Implemented(U: Foo): every clause failed
+              clause `for<type> Implemented(^0.0: Foo) :- FromEnv(^0.0: Foo)`
+                FromEnv(U: Foo): no clause applies"
+        }
+
+        goal {
+            Vec<U>: Foo
+        } explanation {
+            "Implemented(Vec<U>: Foo): every clause failed
+              clause `for<type> Implemented(^0.0: Foo) :- FromEnv(^0.0: Foo)`
+                FromEnv(Vec<U>: Foo): no clause applies
+              clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`
+                ForAll<> { Implemented(U: Bar) }
+                  Implemented(U: Bar): every clause failed
+                    clause `for<type> Implemented(^0.0: Bar) :- FromEnv(^0.0: Bar)`
+                      FromEnv(U: Bar): no clause applies"
+        }
+
+        goal {
+            forall<T> { if (T: Bar) { Vec<T>: Foo } }
+        } explanation {
+            "holds"
+        }
+
+        goal {
+            forall<T> { Vec<T>: Foo }
+        } explanation {
+            "Implemented(Vec<!1_0>: Foo): every clause failed
+              clause `for<type> Implemented(^0.0: Foo) :- FromEnv(^0.0: Foo)`
+                FromEnv(Vec<!1_0>: Foo): no clause applies
+              clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`
+                ForAll<> { Implemented(!1_0: Bar) }
+                  Implemented(!1_0: Bar): every clause failed
+                    clause `for<type> Implemented(^0.0: Bar) :- FromEnv(^0.0: Bar)`
+                      FromEnv(!1_0: Bar): no clause applies"
+        }
+    }
+}
+
+#[test]
+fn negative_impl() {
+    explain_test! {
+        program {
+            #[auto] trait Send { }
+            struct Rc<T> { }
+            struct Vec<T> { elem: T }
+            struct S { }
+            impl<T> !Send for Rc<T> { }
+        }
+
+        goal {
+            Vec<Rc<S>>: Send
+        } explanation {
+            "Implemented(Vec<Rc<S>>: Send): every clause failed
+              clause `for<type> Implemented(^0.0: Send) :- FromEnv(^0.0: Send)`
+                FromEnv(Vec<Rc<S>>: Send): no clause applies
+              clause `for<> Implemented(Vec<Rc<S>>: Send) :- Implemented(Rc<S>: Send)`
+                Implemented(Rc<S>: Send): every clause failed, ruled out by negative impl ImplId(#4)
+                  clause `for<type> Implemented(^0.0: Send) :- FromEnv(^0.0: Send)`
+                    FromEnv(Rc<S>: Send): no clause applies"
+        }
+    }
+}
+
+#[test]
+fn compound_goals() {
+    explain_test! {
+        program {
+            trait Foo { }
+            struct S { }
+            struct U { }
+            impl Foo for S { }
+        }
+
+        goal {
+            S: Foo, U: Foo
+        } explanation {
+            "all(Implemented(S: Foo), Implemented(U: Foo))
+              Implemented(U: Foo): every clause failed
+                clause `for<type> Implemented(^0.0: Foo) :- FromEnv(^0.0: Foo)`
+                  FromEnv(U: Foo): no clause applies"
+        }
+
+        goal {
+            not { S: Foo }
+        } explanation {
+            "not { Implemented(S: Foo) }: negated goal holds"
+        }
+
+        goal {
+            S = U
+        } explanation {
+            "(S = U): does not unify"
+        }
+    }
+}
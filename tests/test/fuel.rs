@@ -0,0 +1,196 @@
+//! Tests for solving with a fuel budget.
+
+use super::*;
+use chalk_ir::{Canonical, ConstrainedSubst};
+use chalk_solve::{FuelExhausted, Solver, SubstitutionResult};
+
+/// Solves `goal_text` with each of the given fuel budgets in turn, using
+/// the same solver, and returns the results formatted like `assert_result`.
+fn solve_with_fuel(
+    program_text: &str,
+    goal_text: &str,
+    solver_choice: SolverChoice,
+    budgets: &[usize],
+) -> Vec<String> {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let mut solver = solver_choice.into_solver();
+        budgets
+            .iter()
+            .map(
+                |&fuel| match solver.solve_with_fuel(db, peeled_goal, fuel) {
+                    Ok(Some(solution)) => format!("{}", solution.display(&ChalkIr)),
+                    Ok(None) => "No possible solution".to_string(),
+                    Err(FuelExhausted) => "Fuel exhausted".to_string(),
+                },
+            )
+            .collect()
+    })
+}
+
+const PROGRAM: &str = stringify!({
+    trait Foo {}
+    struct S {}
+    struct Vec<T> {}
+    impl Foo for S {}
+    impl<T> Foo for Vec<T> where T: Foo {}
+});
+
+#[test]
+fn fuel_runs_out() {
+    let goal = stringify!({ Vec<Vec<Vec<Vec<S>>>>: Foo });
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        assert_eq!(
+            solve_with_fuel(PROGRAM, goal, solver_choice, &[1]),
+            vec!["Fuel exhausted"],
+            "{:?}",
+            solver_choice
+        );
+        assert_eq!(
+            solve_with_fuel(PROGRAM, goal, solver_choice, &[1000]),
+            vec!["Unique; substitution [], lifetime constraints []"],
+            "{:?}",
+            solver_choice
+        );
+    }
+}
+
+#[test]
+fn fuel_is_deterministic() {
+    let goal = stringify!({ Vec<Vec<Vec<Vec<S>>>>: Foo });
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        // Find the smallest budget that suffices; the same budget must
+        // always suffice, and one less must never.
+        let fuel = (0..1000)
+            .find(|&fuel| {
+                solve_with_fuel(PROGRAM, goal, solver_choice, &[fuel])[0] != "Fuel exhausted"
+            })
+            .unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                solve_with_fuel(PROGRAM, goal, solver_choice, &[fuel - 1, fuel]),
+                vec![
+                    "Fuel exhausted",
+                    "Unique; substitution [], lifetime constraints []"
+                ],
+                "{:?}",
+                solver_choice
+            );
+        }
+    }
+}
+
+#[test]
+fn fuel_exhaustion_is_not_cached() {
+    // The recursive solver gives up on goals once it is out of fuel; a
+    // later query must not see those goals as ambiguous.
+    let goal = stringify!({ Vec<Vec<Vec<Vec<S>>>>: Foo });
+    assert_eq!(
+        solve_with_fuel(
+            PROGRAM,
+            goal,
+            SolverChoice::recursive_default(),
+            &[2, 1000, 2]
+        ),
+        vec![
+            "Fuel exhausted",
+            "Unique; substitution [], lifetime constraints []",
+            "Unique; substitution [], lifetime constraints []",
+        ]
+    );
+}
+
+#[test]
+fn fuel_exhaustion_keeps_earlier_results_cached() {
+    with_program(PROGRAM, |db, program| {
+        let cached = lower_test_goal(stringify!({ Vec<Vec<S>>: Foo }), program)
+            .into_peeled_goal(db.interner());
+        let limited = lower_test_goal(stringify!({ Vec<Vec<Vec<Vec<Vec<S>>>>>: Foo }), program)
+            .into_peeled_goal(db.interner());
+        let mut solver = SolverChoice::recursive_default().into_solver();
+        assert!(solver.solve(db, &cached).unwrap().is_unique());
+        assert_eq!(solver.solve_with_fuel(db, &limited, 2), Err(FuelExhausted));
+
+        // Running out of fuel must not throw away the results of the first
+        // query, which finished normally.
+        assert!(solver.solve(db, &cached).unwrap().is_unique());
+        let stats = solver.stats().unwrap();
+        assert_eq!(
+            (stats.cache_hits, stats.cache_misses),
+            (1, 0),
+            "{:?}",
+            stats
+        );
+    });
+}
+
+#[test]
+fn slg_fuel_stops_the_strand_loop() {
+    let goal = stringify!({ Vec<Vec<Vec<Vec<S>>>>: Foo });
+    for fuel in 1..10 {
+        with_peeled_goal(PROGRAM, goal, |db, peeled_goal| {
+            let mut solver = SolverChoice::slg_default().into_solver();
+            assert_eq!(
+                solver.solve_with_fuel(db, peeled_goal, fuel),
+                Err(FuelExhausted)
+            );
+            let stats = solver.stats().unwrap();
+            assert!(stats.strands_pursued <= fuel, "{:?}", stats);
+        });
+    }
+}
+
+/// A solver that only implements the required methods of `Solver`.
+#[derive(Debug)]
+struct LimitedSolver(Box<dyn Solver<ChalkIr>>);
+
+impl Solver<ChalkIr> for LimitedSolver {
+    fn solve(
+        &mut self,
+        program: &dyn RustIrDatabase<ChalkIr>,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+    ) -> Option<Solution<ChalkIr>> {
+        self.0.solve(program, goal)
+    }
+
+    fn solve_limited(
+        &mut self,
+        program: &dyn RustIrDatabase<ChalkIr>,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+        should_continue: &dyn Fn() -> bool,
+    ) -> Option<Solution<ChalkIr>> {
+        self.0.solve_limited(program, goal, should_continue)
+    }
+
+    fn solve_multiple(
+        &mut self,
+        program: &dyn RustIrDatabase<ChalkIr>,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+        f: &mut dyn FnMut(SubstitutionResult<Canonical<ConstrainedSubst<ChalkIr>>>, bool) -> bool,
+    ) -> bool {
+        self.0.solve_multiple(program, goal, f)
+    }
+}
+
+#[test]
+fn default_solve_with_fuel() {
+    let goal = stringify!({ Vec<Vec<Vec<Vec<S>>>>: Foo });
+    with_peeled_goal(PROGRAM, goal, |db, peeled_goal| {
+        let mut solver = LimitedSolver(SolverChoice::slg_default().into_solver());
+        assert_eq!(
+            solver.solve_with_fuel(db, peeled_goal, 0),
+            Err(FuelExhausted)
+        );
+        assert_eq!(
+            solver
+                .solve_with_fuel(db, peeled_goal, 1000)
+                .map(|solution| solution.map(|solution| solution.is_unique())),
+            Ok(Some(true))
+        );
+    });
+}
@@ -0,0 +1,150 @@
+//! Tests for solving goals with inference variables through a
+//! `FulfillmentContext`.
+
+use super::*;
+use chalk_ir::cast::Cast;
+use chalk_ir::{
+    Environment, GenericArg, GoalData, InEnvironment, NoSolution, QuantifierKind, UniverseIndex,
+};
+use chalk_solve::infer::InferenceTable;
+use chalk_solve::FulfillmentContext;
+
+/// Turns the variables of the outermost `exists` in `goal_text` into
+/// inference variables, registers each of its conjuncts as a separate
+/// obligation, and solves them all. Returns the values inferred for the
+/// variables, and the causes of the obligations that stay ambiguous.
+fn fulfill(
+    program_text: &str,
+    goal_text: &str,
+    solver_choice: SolverChoice,
+) -> Result<(Vec<String>, Vec<String>), NoSolution> {
+    with_program(program_text, |db, program| {
+        let interner = db.interner();
+        let goal = lower_test_goal(goal_text, program);
+        let binders = match goal.data(interner) {
+            GoalData::Quantified(QuantifierKind::Exists, binders) => binders.clone(),
+            _ => panic!("expected an `exists` goal"),
+        };
+
+        let mut infer = InferenceTable::new();
+        let vars: Vec<GenericArg<_>> = binders
+            .binders
+            .iter(interner)
+            .map(|_| {
+                infer
+                    .new_variable(UniverseIndex::root())
+                    .to_ty(interner)
+                    .cast(interner)
+            })
+            .collect();
+        let goal = binders.substitute(interner, &vars);
+        let goals = match goal.data(interner) {
+            GoalData::All(goals) => goals.as_slice(interner).to_vec(),
+            _ => vec![goal],
+        };
+
+        let mut fulfill = FulfillmentContext::new();
+        for goal in goals {
+            fulfill.register(InEnvironment::new(&Environment::new(interner), goal));
+        }
+        let mut solver = solver_choice.into_solver();
+        let pending = fulfill.select_all(db, &mut *solver, &mut infer)?;
+
+        let values = vars
+            .into_iter()
+            .map(|var| format!("{:?}", infer.canonicalize(interner, var).quantified.value))
+            .collect();
+        let causes = pending
+            .into_iter()
+            .map(|obligation| obligation.cause.to_string())
+            .collect();
+        Ok((values, causes))
+    })
+}
+
+const PROGRAM: &str = stringify!({
+    trait Foo {}
+    trait Bar<T> {}
+    struct S {}
+    struct U {}
+    struct Vec<T> {}
+    impl Foo for S {}
+    impl Foo for U {}
+    impl Bar<S> for Vec<U> {}
+});
+
+#[test]
+fn fulfill_unifies_variables() {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        // Learning `?0` from the first goal makes the second one unique.
+        assert_eq!(
+            fulfill(
+                PROGRAM,
+                stringify!({ exists<T, X> { X: Bar<T>, T: Foo } }),
+                solver_choice
+            ),
+            Ok((vec!["S".to_string(), "Vec<U>".to_string()], vec![])),
+            "{:?}",
+            solver_choice
+        );
+
+        // The order of the goals doesn't matter.
+        assert_eq!(
+            fulfill(
+                PROGRAM,
+                stringify!({ exists<T, X> { T: Foo, X: Bar<T> } }),
+                solver_choice
+            ),
+            Ok((vec!["S".to_string(), "Vec<U>".to_string()], vec![])),
+            "{:?}",
+            solver_choice
+        );
+    }
+}
+
+#[test]
+fn fulfill_keeps_ambiguous_obligations() {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        assert_eq!(
+            fulfill(PROGRAM, stringify!({ exists<T> { T: Foo } }), solver_choice),
+            Ok((vec!["^0.0".to_string()], vec!["ambiguity".to_string()])),
+            "{:?}",
+            solver_choice
+        );
+        assert_eq!(
+            fulfill(
+                PROGRAM,
+                stringify!({ exists<T> { T: Foo, T = U } }),
+                solver_choice
+            ),
+            Ok((vec!["U".to_string()], vec![])),
+            "{:?}",
+            solver_choice
+        );
+    }
+}
+
+#[test]
+fn fulfill_reports_errors() {
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        assert_eq!(
+            fulfill(
+                PROGRAM,
+                stringify!({ exists<T> { T: Foo, Vec<T>: Foo } }),
+                solver_choice
+            ),
+            Err(NoSolution),
+            "{:?}",
+            solver_choice
+        );
+    }
+}
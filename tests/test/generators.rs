@@ -87,3 +87,168 @@ fn generator_test() {
         }
     }
 }
+
+#[test]
+fn generator_trait() {
+    test! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {
+                type Yield;
+                type Return;
+            }
+
+            struct Resume {}
+            struct Yielded {}
+            struct Returned {}
+
+            generator gen<>[resume = Resume, yield = Yielded] -> Returned {
+                upvars []
+                witnesses []
+            }
+
+            generator gen_generic<T>[resume = (), yield = T] -> u32 {
+                upvars [T]
+                witnesses []
+            }
+        }
+
+        goal {
+            gen: Generator<Resume>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            gen: Generator<()>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<R> {
+                gen: Generator<R>
+            }
+        } yields {
+            "Unique; substitution [?0 := Resume]"
+        }
+
+        goal {
+            Normalize(<gen as Generator<Resume>>::Yield -> Yielded)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<T> {
+                Normalize(<gen as Generator<Resume>>::Return -> T)
+            }
+        } yields {
+            "Unique; substitution [?0 := Returned]"
+        }
+
+        goal {
+            forall<T> {
+                Normalize(<gen_generic<T> as Generator<()>>::Yield -> T)
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<T> {
+                Normalize(<gen_generic<T> as Generator<()>>::Return -> u32)
+            }
+        } yields {
+            "Unique"
+        }
+
+        // The generator trait is non-enumerable
+        goal {
+            exists<G> {
+                G: Generator<()>
+            }
+        } yields {
+            "Ambiguous"
+        }
+    }
+}
+
+#[test]
+fn generator_unpin() {
+    test! {
+        program {
+            #[auto] #[lang(unpin)] trait Unpin { }
+
+            struct NotUnpin {}
+            impl !Unpin for NotUnpin {}
+
+            generator movable_gen<>[resume = (), yield = ()] {
+                upvars [NotUnpin]
+                witnesses []
+            }
+
+            static generator static_gen<>[resume = (), yield = ()] {
+                upvars []
+                witnesses []
+            }
+        }
+
+        // Movable generators are always `Unpin`, whatever they hold
+        goal {
+            movable_gen: Unpin
+        } yields {
+            "Unique"
+        }
+
+        // Immovable generators are never `Unpin`
+        goal {
+            static_gen: Unpin
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn generator_trait_without_associated_types() {
+    test! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {}
+
+            generator gen<>[resume = (), yield = ()] -> u32 {
+                upvars []
+                witnesses []
+            }
+        }
+
+        goal {
+            gen: Generator<()>
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn no_generator_impls() {
+    lowering_error! {
+        program {
+            #[lang(generator)]
+            trait Generator<R> {
+                type Yield;
+                type Return;
+            }
+
+            struct S {}
+
+            impl Generator<()> for S {
+                type Yield = ();
+                type Return = ();
+            }
+        } error_msg {
+            "trait impl for `Generator` does not meet well-formedness requirements"
+        }
+    }
+}
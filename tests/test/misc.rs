@@ -56,6 +56,82 @@ fn basic() {
         } yields_all[SolverChoice::slg(10, None)] {
             "substitution [], lifetime constraints []"
         }
+
+        goal {
+            forall<T> { if (T: Sized) { T: Sized } }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn recursive_multiple_answers() {
+    test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct S { }
+            struct U { }
+            struct Vec<T> { }
+            impl Bar for S { }
+            impl Bar for U { }
+            impl<T> Foo for Vec<T> where T: Bar { }
+        }
+
+        goal {
+            exists<T> { T: Bar }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := S], lifetime constraints []",
+            "substitution [?0 := U], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> { T: Bar }
+        } yields_first[SolverChoice::recursive_default()] {
+            "substitution [?0 := S], lifetime constraints []"
+        }
+
+        // The recursive solver only splits the answers of the goal itself,
+        // not those of its subgoals.
+        goal {
+            exists<T> { Vec<T>: Foo }
+        } yields_all[SolverChoice::recursive_default()] {
+            "Ambiguous(for<?U0> { substitution [?0 := ^0.0], lifetime constraints [] })"
+        }
+
+        goal {
+            exists<T> { Vec<T>: Bar }
+        } yields_all[SolverChoice::recursive_default()] {
+        }
+
+        goal {
+            exists<T> { T: Bar, T = S }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := S], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn recursive_multiple_answers_cycle() {
+    test! {
+        program {
+            trait Foo { }
+            struct S { }
+            struct Box<T> { }
+            impl Foo for S { }
+            impl<T> Foo for Box<T> where T: Foo { }
+        }
+
+        // The subgoal `?T: Foo` of the second impl cycles back to the goal
+        // itself and gets its (ambiguous) solution.
+        goal {
+            exists<T> { T: Foo }
+        } yields_all[SolverChoice::recursive_default()] {
+            "substitution [?0 := S], lifetime constraints []",
+            "Ambiguous(for<?U0> { substitution [?0 := Box<^0.0>], lifetime constraints [] })"
+        }
     }
 }
 
@@ -4,9 +4,10 @@ use crate::test_util::assert_same;
 use chalk_integration::db::ChalkDatabase;
 use chalk_integration::interner::ChalkIr;
 use chalk_integration::lowering::lower_goal;
+use chalk_integration::program::Program;
 use chalk_integration::query::LoweringDatabase;
 use chalk_integration::SolverChoice;
-use chalk_ir::Constraints;
+use chalk_ir::{Constraints, Goal, InEnvironment, UCanonical};
 use chalk_solve::ext::*;
 use chalk_solve::logging::with_tracing_logs;
 use chalk_solve::RustIrDatabase;
@@ -83,8 +84,8 @@ macro_rules! parse_test_data {
     };
 
     // goal { G } yields_all { "Y1", "Y2", ... , "YN" } -- test that the SLG
-    // solver gets exactly N answers in this order (the recursive solver can't
-    // return multiple answers)
+    // solver gets exactly N answers in this order (the recursive solver only
+    // gives one answer per clause, so its answers usually differ)
     (@program[$program:tt] @parsed_goals[$($parsed_goals:tt)*] @unparsed_goals[
         goal $goal:tt yields_all { $($expected:expr),* }
         $($unparsed_goals:tt)*
@@ -237,14 +238,6 @@ fn solve_goal(program_text: &str, goals: Vec<(&str, SolverChoice, TestGoal)>, co
         };
 
         for (goal_text, solver_choice, expected) in goals {
-            match (&solver_choice, &expected) {
-                (SolverChoice::Recursive { .. }, TestGoal::All(_))
-                | (SolverChoice::Recursive { .. }, TestGoal::First(_)) => {
-                    panic!("cannot test the recursive solver with yields_first or yields_all");
-                }
-                _ => {}
-            };
-
             if db.solver_choice() != solver_choice {
                 db.set_solver_choice(solver_choice);
             }
@@ -321,33 +314,91 @@ fn solve_goal(program_text: &str, goals: Vec<(&str, SolverChoice, TestGoal)>, co
     })
 }
 
+/// Lowers and checks the program in `program_text`, given as `stringify!`
+/// gives it (that is, surrounded by braces), and runs `f` with its database
+/// and the lowered program, which is the current program while `f` runs.
+fn with_program<R>(program_text: &str, f: impl FnOnce(&ChalkDatabase, &Program) -> R) -> R {
+    with_tracing_logs(|| {
+        assert!(program_text.starts_with("{"));
+        assert!(program_text.ends_with("}"));
+        let db = ChalkDatabase::with(
+            &program_text[1..program_text.len() - 1],
+            SolverChoice::default(),
+        );
+        let program = db.checked_program().unwrap();
+        chalk_integration::tls::set_current_program(&program, || f(&db, &program))
+    })
+}
+
+/// Lowers the goal in `goal_text`, given as `stringify!` gives it, against
+/// `program`.
+fn lower_test_goal(goal_text: &str, program: &Program) -> Goal<ChalkIr> {
+    assert!(goal_text.starts_with("{"));
+    assert!(goal_text.ends_with("}"));
+    lower_goal(
+        &chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1]).unwrap(),
+        program,
+    )
+    .unwrap()
+}
+
+/// Like `with_program`, but also lowers the goal in `goal_text` and passes
+/// it to `f` in the peeled form that solvers take.
+fn with_peeled_goal<R>(
+    program_text: &str,
+    goal_text: &str,
+    f: impl FnOnce(&ChalkDatabase, &UCanonical<InEnvironment<Goal<ChalkIr>>>) -> R,
+) -> R {
+    with_program(program_text, |db, program| {
+        let goal = lower_test_goal(goal_text, program);
+        f(db, &goal.into_peeled_goal(db.interner()))
+    })
+}
+
+mod ambiguity;
 mod arrays;
+mod assoc_consts;
+mod assoc_ty_defaults;
 mod auto_traits;
+mod autoderef;
 mod closures;
+mod coerce_unsized;
 mod coherence_goals;
 mod coinduction;
+mod const_exprs;
 mod constants;
 mod cycle;
+mod default_params;
 mod discriminant_kind;
 mod existential_types;
+mod explain;
 mod fn_def;
 mod foreign_types;
+mod fuel;
+mod fulfill;
 mod generators;
 mod implied_bounds;
 mod impls;
 mod lifetimes;
 mod misc;
 mod negation;
+mod negative_bounds;
 mod never;
 mod numerics;
 mod object_safe;
 mod opaque_types;
+mod pointee;
 mod projection;
+mod proofs;
 mod refs;
+mod regions;
 mod scalars;
 mod slices;
+mod specialization;
+mod stats;
 mod string;
 mod subtype;
+mod trait_aliases;
 mod tuples;
 mod type_flags;
 mod unify;
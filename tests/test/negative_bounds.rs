@@ -0,0 +1,121 @@
+//! Tests for negative bounds (`T: !Trait`), which are proven by negative
+//! impls.
+
+use super::*;
+
+#[test]
+fn negative_impls_prove_negative_bounds() {
+    test! {
+        program {
+            trait Foo { }
+            struct A { }
+            struct B { }
+            struct Vec<T> { }
+            impl !Foo for A { }
+            impl<T> !Foo for Vec<T> where T: !Foo { }
+        }
+
+        goal {
+            A: !Foo
+        } yields {
+            "Unique; substitution []"
+        }
+
+        // Not implementing `Foo` isn't enough: there has to be a negative impl.
+        goal {
+            B: !Foo
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            not { B: Foo }
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            Vec<A>: !Foo
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            Vec<B>: !Foo
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: !Foo) {
+                    Vec<T>: !Foo
+                }
+            }
+        } yields {
+            "Unique; substitution []"
+        }
+    }
+}
+
+#[test]
+fn negative_bounds_in_where_clauses() {
+    test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct A { }
+            struct B { }
+            impl !Foo for A { }
+            impl<T> Bar for T where T: !Foo { }
+        }
+
+        goal {
+            A: Bar
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            B: Bar
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> {
+                T: Bar
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+    }
+}
+
+#[test]
+fn negative_impls_make_impls_disjoint() {
+    // An upstream crate could add `impl Foo for A`, so these impls overlap...
+    lowering_error! {
+        program {
+            #[upstream] trait Foo { }
+            #[upstream] struct A { }
+            trait Bar { }
+            impl Bar for A { }
+            impl<T> Bar for T where T: Foo { }
+        } error_msg {
+            "overlapping impls of trait `Bar`"
+        }
+    }
+
+    // ...unless it has promised not to, with a negative impl.
+    lowering_success! {
+        program {
+            #[upstream] trait Foo { }
+            #[upstream] struct A { }
+            #[upstream] impl !Foo for A { }
+            trait Bar { }
+            impl Bar for A { }
+            impl<T> Bar for T where T: Foo { }
+        }
+    }
+}
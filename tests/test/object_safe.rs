@@ -1,15 +1,147 @@
 use super::*;
+use chalk_solve::object_safety::{object_safety_violations, ObjectSafetyViolation};
+
+/// Lowers `program_text` and returns the object safety violations of each of
+/// the traits named in `trait_names`, formatted with `Display`.
+fn violations(program_text: &str, trait_names: &[&str]) -> Vec<Vec<String>> {
+    with_program(program_text, |db, program| {
+        trait_names
+            .iter()
+            .map(|name| {
+                let (_, &trait_id) = program
+                    .trait_ids
+                    .iter()
+                    .find(|(trait_name, _)| **trait_name == **name)
+                    .unwrap();
+                object_safety_violations(db, trait_id)
+                    .iter()
+                    .map(ObjectSafetyViolation::to_string)
+                    .collect()
+            })
+            .collect()
+    })
+}
 
 #[test]
-fn object_safe_flag() {
+fn object_safe_computed() {
     test! {
         program {
+            #[lang(sized)]
+            trait Sized {}
+
             #[object_safe]
             trait Foo {}
-            trait Bar {}
+            #[object_safe]
+            trait Bar where Self: Sized {}
+            #[object_safe]
+            trait Baz where Self: Bar {}
         }
 
         goal { ObjectSafe(Foo) } yields { "Unique" }
         goal { not { ObjectSafe(Bar) } } yields { "Unique" }
+        goal { not { ObjectSafe(Baz) } } yields { "Unique" }
+        goal { not { ObjectSafe(Sized) } } yields { "Unique" }
+    }
+}
+
+#[test]
+fn object_safe_attribute() {
+    test! {
+        program {
+            #[lang(sized)]
+            trait Sized {}
+
+            #[object_safe]
+            trait Bar where Self: Sized {}
+            #[object_safe]
+            trait Foo {}
+            trait Baz {}
+        }
+
+        // The attribute cannot make up for a violation chalk finds...
+        goal { ObjectSafe(Bar) } yields { "No possible solution" }
+        goal { ObjectSafe(Foo) } yields { "Unique" }
+        // ...and a trait without it is not object safe.
+        goal { ObjectSafe(Baz) } yields { "No possible solution" }
     }
 }
+
+#[test]
+fn object_safety_supertrait_self() {
+    test! {
+        program {
+            trait Eq<T> {}
+            trait Iterator {
+                type Item;
+            }
+
+            #[object_safe]
+            trait SelfParam where Self: Eq<Self> {}
+            #[object_safe]
+            trait NestedSelfParam where Self: Eq<Box<Self>> {}
+            #[object_safe]
+            trait InheritedSelfParam where Self: SelfParam {}
+            #[object_safe]
+            trait OtherParam<T> where Self: Eq<T> {}
+            #[object_safe]
+            trait SelfProjection where Self: Iterator, Self: Eq<<Self as Iterator>::Item> {}
+
+            struct Box<T> {}
+        }
+
+        goal { not { ObjectSafe(SelfParam) } } yields { "Unique" }
+        goal { not { ObjectSafe(NestedSelfParam) } } yields { "Unique" }
+        goal { not { ObjectSafe(InheritedSelfParam) } } yields { "Unique" }
+        goal { ObjectSafe(OtherParam) } yields { "Unique" }
+        goal { ObjectSafe(SelfProjection) } yields { "Unique" }
+    }
+}
+
+#[test]
+fn object_safety_generic_assoc_ty() {
+    test! {
+        program {
+            #[object_safe]
+            trait Foo {
+                type Assoc<T>;
+            }
+            #[object_safe]
+            trait Bar {
+                type Assoc;
+            }
+        }
+
+        goal { not { ObjectSafe(Foo) } } yields { "Unique" }
+        goal { ObjectSafe(Bar) } yields { "Unique" }
+    }
+}
+
+#[test]
+fn object_safety_violations_reasons() {
+    let violations = violations(
+        stringify!({
+            #[lang(sized)]
+            trait Sized {}
+            trait Eq<T> {}
+
+            trait Fine {}
+            trait Unsafe
+            where
+                Self: Sized,
+                Self: Eq<Self>,
+            {
+                type Assoc<'a>;
+            }
+        }),
+        &["Fine", "Unsafe"],
+    );
+    assert_eq!(violations[0], Vec::<String>::new());
+    assert_eq!(
+        violations[1],
+        vec![
+            "it requires `Self: Sized`",
+            "its supertrait `Eq` uses `Self` as a type parameter",
+            "its associated type `(Unsafe::Assoc)` has generic parameters",
+        ]
+    );
+}
@@ -0,0 +1,230 @@
+use super::*;
+
+// Test that user-provided impls of `Pointee` are prohibited
+#[test]
+fn no_pointee_impls() {
+    lowering_error! {
+        program {
+            #[lang(pointee)]
+            trait Pointee {
+                type Metadata;
+            }
+
+            impl Pointee for u32 {
+                type Metadata = ();
+            }
+        } error_msg {
+            "trait impl for `Pointee` does not meet well-formedness requirements"
+        }
+    }
+}
+
+// Test that all types are implementing Pointee
+#[test]
+fn pointee_impl() {
+    test! {
+        program {
+            #[lang(pointee)]
+            trait Pointee {
+                type Metadata;
+            }
+
+            #[object_safe]
+            trait Principal {}
+
+            struct A { }
+        }
+
+        goal {
+            A: Pointee
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            [i32]: Pointee
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Principal + 'a: Pointee
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                T: Pointee
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn pointee_metadata() {
+    test! {
+        program {
+            #[lang(pointee)]
+            trait Pointee {
+                type Metadata;
+            }
+
+            #[lang(sized)]
+            trait Sized {}
+
+            #[object_safe]
+            trait Principal {}
+
+            #[lang(dyn_metadata)]
+            struct DynMetadata<Dyn> {}
+
+            struct A {}
+            enum E {}
+            struct Wrapper<T> {
+                header: u8,
+                value: T
+            }
+        }
+
+        // Sized types have no metadata
+        goal {
+            Normalize(<u32 as Pointee>::Metadata -> ())
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<A as Pointee>::Metadata -> ())
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<E as Pointee>::Metadata -> ())
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                Normalize(<&'a str as Pointee>::Metadata -> ())
+            }
+        } yields {
+            "Unique"
+        }
+
+        // Slices and `str` have their length as metadata
+        goal {
+            exists<M> {
+                Normalize(<[u8] as Pointee>::Metadata -> M)
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(Usize)]"
+        }
+
+        goal {
+            Normalize(<str as Pointee>::Metadata -> usize)
+        } yields {
+            "Unique"
+        }
+
+        // Trait objects have a `DynMetadata` of themselves
+        goal {
+            forall<'a> {
+                Normalize(<dyn Principal + 'a as Pointee>::Metadata -> DynMetadata<dyn Principal + 'a>)
+            }
+        } yields {
+            "Unique"
+        }
+
+        // Structs and tuples have the metadata of their last field
+        goal {
+            Normalize(<Wrapper<[u8]> as Pointee>::Metadata -> usize)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<Wrapper<Wrapper<str>> as Pointee>::Metadata -> usize)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<Wrapper<u8> as Pointee>::Metadata -> ())
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<(u8, [u8]) as Pointee>::Metadata -> usize)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Normalize(<() as Pointee>::Metadata -> ())
+        } yields {
+            "Unique"
+        }
+
+        // Placeholders have no metadata only if they are `Sized`
+        goal {
+            forall<T> {
+                if (T: Sized) {
+                    Normalize(<T as Pointee>::Metadata -> ())
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                Normalize(<Wrapper<T> as Pointee>::Metadata -> ())
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // We can't enumerate the metadata of all types
+        goal {
+            exists<T> {
+                Normalize(<T as Pointee>::Metadata -> usize)
+            }
+        } yields {
+            "Ambiguous"
+        }
+    }
+}
+
+// Without a `DynMetadata` lang item, trait objects have no known metadata
+#[test]
+fn pointee_metadata_without_dyn_metadata() {
+    test! {
+        program {
+            #[lang(pointee)]
+            trait Pointee {
+                type Metadata;
+            }
+
+            #[object_safe]
+            trait Principal {}
+        }
+
+        goal {
+            forall<'a> {
+                exists<M> {
+                    Normalize(<dyn Principal + 'a as Pointee>::Metadata -> M)
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
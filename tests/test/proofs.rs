@@ -0,0 +1,328 @@
+//! Tests for the proof trees recorded by the solvers.
+
+use super::*;
+use chalk_engine::solve::SLGSolver;
+use chalk_recursive::RecursiveSolver;
+use chalk_solve::proof::check::{check_proof, ProofError};
+use chalk_solve::proof::{ProofStep, ProofTree};
+use chalk_solve::Solver;
+
+macro_rules! proof_test {
+    (program $program:tt $(goal $goal:tt proof { $expected:expr })*) => {
+        $(assert_proof(stringify!($program), stringify!($goal), $expected);)*
+    };
+}
+
+/// Like `proof_test!`, but for the SLG solver, which gives one proof per
+/// answer.
+macro_rules! slg_proof_test {
+    (program $program:tt $(goal $goal:tt proofs { $($expected:expr),* })*) => {
+        $(assert_slg_proofs(stringify!($program), stringify!($goal), &[$($expected),*]);)*
+    };
+}
+
+fn assert_proof(program_text: &str, goal_text: &str, expected: &str) {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let mut solver = RecursiveSolver::new(100, 30, true);
+        let (solution, proof) = solver.solve_with_proof(db, peeled_goal);
+        assert_eq!(solution, proof.solution);
+        assert_same(&format!("{}", proof.display(db.interner())), expected);
+        if let Some(Solution::Unique(_)) = proof.solution {
+            check_proof(db, &mut SLGSolver::new(10, None), peeled_goal, &proof).unwrap();
+        }
+    })
+}
+
+fn assert_slg_proofs(program_text: &str, goal_text: &str, expected: &[&str]) {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let mut solver = SLGSolver::new(10, None);
+        solver.record_proofs();
+        let mut answers = 0;
+        solver.solve_multiple(db, peeled_goal, &mut |_, _| {
+            answers += 1;
+            true
+        });
+        let proofs = solver.answer_proofs(db, peeled_goal);
+        assert_eq!(proofs.len(), answers);
+        for proof in &proofs {
+            if let Some(Solution::Unique(_)) = proof.solution {
+                check_proof(
+                    db,
+                    &mut RecursiveSolver::new(100, 30, true),
+                    peeled_goal,
+                    proof,
+                )
+                .unwrap();
+            }
+        }
+        let proofs: Vec<_> = proofs
+            .iter()
+            .map(|proof| format!("{}", proof.display(db.interner())))
+            .collect();
+        assert_eq!(proofs.len(), expected.len());
+        for (proof, expected) in proofs.iter().zip(expected) {
+            assert_same(proof, expected);
+        }
+    })
+}
+
+/// Checks the proof of the recursive solver for `goal_text`, after letting
+/// `tamper` modify it, and returns the error if it was rejected.
+fn check_tampered_proof(
+    program_text: &str,
+    goal_text: &str,
+    tamper: impl FnOnce(&mut ProofTree<ChalkIr>),
+) -> Option<ProofError<ChalkIr>> {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let mut solver = RecursiveSolver::new(100, 30, true);
+        let (_, mut proof) = solver.solve_with_proof(db, peeled_goal);
+        tamper(&mut proof);
+        check_proof(db, &mut SLGSolver::new(10, None), peeled_goal, &proof).err()
+    })
+}
+
+#[test]
+fn impl_with_where_clause() {
+    proof_test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct S { }
+            struct U { }
+            struct Vec<T> { }
+            impl Bar for S { }
+            impl Bar for U { }
+            impl<T> Foo for Vec<T> where T: Bar { }
+        }
+
+        goal {
+            Vec<S>: Foo
+        } proof {
+            "Implemented(Vec<S>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: Unique; substitution [], lifetime constraints []
+               Implemented(S: Bar) by clause `for<> Implemented(S: Bar)`: Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> { Vec<T>: Foo }
+        } proof {
+            "Implemented(Vec<^0.0>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: Ambiguous; no inference guidance
+               Implemented(^0.0: Bar) by alternatives: Ambiguous; no inference guidance
+                 Implemented(^0.0: Bar) by clause `for<> Implemented(S: Bar)`: Unique; substitution [?0 := S], lifetime constraints []
+                 Implemented(^0.0: Bar) by clause `for<> Implemented(U: Bar)`: Unique; substitution [?0 := U], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn failed_attempts() {
+    proof_test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct S { }
+            struct Vec<T> { }
+            impl Bar for S { }
+            impl<T> Foo for Vec<T> where T: Bar { }
+        }
+
+        goal {
+            Vec<Vec<S>>: Foo
+        } proof {
+            "Implemented(Vec<Vec<S>>: Foo) by alternatives: No possible solution
+               Implemented(Vec<Vec<S>>: Foo) by clause `for<type> Implemented(^0.0: Foo) :- FromEnv(^0.0: Foo)`: No possible solution
+                 FromEnv(Vec<Vec<S>>: Foo) by alternatives: No possible solution
+               Implemented(Vec<Vec<S>>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: No possible solution
+                 Implemented(Vec<S>: Bar) by alternatives: No possible solution
+                   Implemented(Vec<S>: Bar) by clause `for<type> Implemented(^0.0: Bar) :- FromEnv(^0.0: Bar)`: No possible solution
+                     FromEnv(Vec<S>: Bar) by alternatives: No possible solution"
+        }
+
+        goal {
+            not { Vec<Vec<S>>: Foo }
+        } proof {
+            "not { Implemented(Vec<Vec<S>>: Foo) }: Unique; substitution [], lifetime constraints []
+               not { Implemented(Vec<Vec<S>>: Foo) } by negation: Unique; substitution [], lifetime constraints []
+                 Implemented(Vec<Vec<S>>: Foo) by alternatives: No possible solution"
+        }
+    }
+}
+
+#[test]
+fn coinductive_cycle() {
+    proof_test! {
+        program {
+            #[auto] trait Send { }
+            struct List { next: List }
+        }
+
+        goal {
+            List: Send
+        } proof {
+            "Implemented(List: Send) by clause `for<> Implemented(List: Send) :- Implemented(List: Send)`: Unique; substitution [], lifetime constraints []
+               Implemented(List: Send) by cycle: Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn proof_of_cached_goal() {
+    let program = stringify!({
+        trait Foo {}
+        trait Bar {}
+        struct S {}
+        struct Vec<T> {}
+        impl Bar for S {}
+        impl<T> Foo for Vec<T> where T: Bar {}
+    });
+    with_peeled_goal(program, "{ Vec<S>: Foo }", |db, peeled_goal| {
+        let mut solver = RecursiveSolver::new(100, 30, true);
+
+        // The results cached before recording proofs are solved again, so
+        // that the proof is complete.
+        let solution = solver.solve(db, peeled_goal);
+        let (proof_solution, proof) = solver.solve_with_proof(db, peeled_goal);
+        assert_eq!(solution, proof_solution);
+        assert_same(
+            &format!("{}", proof.display(db.interner())),
+            "Implemented(Vec<S>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: Unique; substitution [], lifetime constraints []
+               Implemented(S: Bar) by clause `for<> Implemented(S: Bar)`: Unique; substitution [], lifetime constraints []",
+        );
+        check_proof(db, &mut SLGSolver::new(10, None), peeled_goal, &proof).unwrap();
+
+        // Once recorded, the proofs are reused along with the cache.
+        let (_, cached_proof) = solver.solve_with_proof(db, peeled_goal);
+        assert_eq!(cached_proof, proof);
+        assert!(solver.stats().unwrap().cache_hits > 0);
+    });
+}
+
+#[test]
+fn slg_impl_with_where_clause() {
+    slg_proof_test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct S { }
+            struct U { }
+            struct Vec<T> { }
+            impl Bar for S { }
+            impl Bar for U { }
+            impl<T> Foo for Vec<T> where T: Bar { }
+        }
+
+        goal {
+            Vec<S>: Foo
+        } proofs {
+            "Implemented(Vec<S>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: Unique; substitution [], lifetime constraints []
+               ForAll<> { Implemented(S: Bar) }: Unique; substitution [], lifetime constraints []
+                 Implemented(S: Bar) by clause `for<> Implemented(S: Bar)`: Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> { Vec<T>: Foo }
+        } proofs {
+            "Implemented(Vec<^0.0>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: Unique; substitution [?0 := S], lifetime constraints []
+               ForAll<> { Implemented(^1.0: Bar) }: Unique; substitution [?0 := S], lifetime constraints []
+                 Implemented(^0.0: Bar) by clause `for<> Implemented(S: Bar)`: Unique; substitution [?0 := S], lifetime constraints []",
+            "Implemented(Vec<^0.0>: Foo) by clause `for<type> Implemented(Vec<^0.0>: Foo) :- ForAll<> { Implemented(^1.0: Bar) }`: Unique; substitution [?0 := U], lifetime constraints []
+               ForAll<> { Implemented(^1.0: Bar) }: Unique; substitution [?0 := U], lifetime constraints []
+                 Implemented(^0.0: Bar) by clause `for<> Implemented(U: Bar)`: Unique; substitution [?0 := U], lifetime constraints []"
+        }
+
+        goal {
+            not { Vec<Vec<S>>: Foo }
+        } proofs {
+            "not { Implemented(Vec<Vec<S>>: Foo) }: Unique; substitution [], lifetime constraints []
+               not { Implemented(Vec<Vec<S>>: Foo) } by negation: Unique; substitution [], lifetime constraints []
+                 Implemented(Vec<Vec<S>>: Foo) by alternatives: No possible solution"
+        }
+    }
+}
+
+#[test]
+fn slg_coinductive_cycle() {
+    slg_proof_test! {
+        program {
+            #[auto] trait Send { }
+            struct List { next: List }
+        }
+
+        goal {
+            List: Send
+        } proofs {
+            "Implemented(List: Send) by clause `for<> Implemented(List: Send) :- Implemented(List: Send)`: Unique; substitution [], lifetime constraints []
+               Implemented(List: Send) by cycle: Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn check_rejects_tampered_proofs() {
+    let program = stringify!({
+        trait Foo {}
+        trait Bar {}
+        struct S {}
+        struct Vec<T> {}
+        impl Bar for S {}
+        impl<T> Foo for Vec<T> where T: Bar {}
+    });
+    let goal = "{ Vec<S>: Foo }";
+    assert!(check_tampered_proof(program, goal, |_| {}).is_none());
+
+    let result = check_tampered_proof(program, goal, |proof| proof.subgoals.clear());
+    assert!(matches!(result, Some(ProofError::UnprovenSubgoal(..))));
+
+    let result = check_tampered_proof(program, goal, |proof| {
+        proof.subgoals[0].step = proof.step.clone();
+    });
+    assert!(matches!(result, Some(ProofError::UnknownClause(..))));
+
+    let result = check_tampered_proof(program, goal, |proof| {
+        proof.subgoals[0].step = ProofStep::Cycle;
+    });
+    assert!(matches!(result, Some(ProofError::InvalidCycle(..))));
+
+    let result = check_tampered_proof(program, goal, |proof| {
+        proof.subgoals[0].goal = proof.goal.clone();
+    });
+    assert!(matches!(result, Some(ProofError::UnexpectedSubproof(..))));
+}
+
+#[test]
+fn check_rejects_unsound_coinduction() {
+    // The recursive solver accepts this goal (see `coinductive_unsound1`),
+    // but its proof relies on `X: C3`, which does not hold.
+    let program = stringify!({
+        trait C1orC2 { }
+        #[coinductive] trait C1 { }
+        #[coinductive] trait C2 { }
+        #[coinductive] trait C3 { }
+        forall<T> { T: C1 if T: C2, T: C3 }
+        forall<T> { T: C2 if T: C1 }
+        forall<T> { T: C1orC2 if T: C1 }
+        forall<T> { T: C1orC2 if T: C2 }
+    });
+    let result = check_tampered_proof(program, "{ forall<X> { X: C1orC2 } }", |_| {});
+    assert!(matches!(result, Some(ProofError::UnprovenSubgoal(..))));
+}
+
+#[test]
+fn check_rejects_forged_negation() {
+    let program = stringify!({
+        trait Foo {}
+        struct S {}
+        impl Foo for S {}
+    });
+    assert!(check_tampered_proof(program, "{ not { S: Foo } }", |_| {}).is_some());
+
+    // Claim that `not { S: Foo }` holds because `S: Foo` fails.
+    let result = check_tampered_proof(program, "{ not { S: Foo } }", |proof| {
+        let holds = proof.subgoals[0].subgoals[0].solution.take();
+        proof.solution = holds.clone();
+        proof.subgoals[0].solution = holds;
+    });
+    assert!(matches!(
+        result,
+        Some(ProofError::NegatedGoalNotRefuted(..))
+    ));
+}
@@ -0,0 +1,146 @@
+//! Tests for checking the lifetime constraints of solutions.
+
+use super::*;
+use chalk_solve::solve::regions::resolve_regions;
+
+/// Solves `goal_text`, then checks and simplifies the lifetime constraints
+/// of the solution, formatting the result like `assert_result`.
+fn solve_and_resolve_regions(program_text: &str, goal_text: &str) -> String {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let solution = SolverChoice::default()
+            .into_solver()
+            .solve(db, peeled_goal)
+            .unwrap();
+        match resolve_regions(db, solution) {
+            Ok(solution) => format!("{}", solution.display(&ChalkIr)),
+            Err(_) => "No possible solution".to_string(),
+        }
+    })
+}
+
+const PROGRAM: &str = stringify!({
+    trait Foo<'a> {}
+    trait Outlives<'a, 'b> {}
+    trait TypeOutlives<'a, T> {}
+    struct Ref<'a, T> {}
+    struct S {}
+
+    impl<'a, T> Foo<'static> for Ref<'a, T> where 'a: 'static {}
+    impl<'a, 'b> Outlives<'a, 'b> for S where 'a: 'b {}
+    impl<'a, T> TypeOutlives<'a, T> for S where T: 'a {}
+});
+
+#[test]
+fn unsatisfiable_constraints() {
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a> { Ref<'a, S>: Foo<'static> } }),
+        ),
+        "No possible solution",
+    );
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a, 'b> { S: Outlives<'a, 'b> } }),
+        ),
+        "No possible solution",
+    );
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a, T> { S: TypeOutlives<'a, T> } }),
+        ),
+        "No possible solution",
+    );
+}
+
+#[test]
+fn constraints_satisfied_by_environment() {
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a> { if ('a: 'static) { Ref<'a, S>: Foo<'static> } } }),
+        ),
+        "Unique; substitution [], lifetime constraints []",
+    );
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a, 'b, 'c> { if ('a: 'b) { if ('b: 'c) { S: Outlives<'a, 'c> } } } }),
+        ),
+        "Unique; substitution [], lifetime constraints []",
+    );
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a, T> { if (T: 'static) { S: TypeOutlives<'a, Ref<'a, T>> } } }),
+        ),
+        "Unique; substitution [], lifetime constraints []",
+    );
+}
+
+#[test]
+fn constraints_on_variables() {
+    // Whether these hold depends on the caller's choice for `'b`.
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a> { exists<'b> { S: Outlives<'a, 'b> } } }),
+        ),
+        "Unique; for<?U1> { substitution [?0 := '^0.0], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '^0.0 }] }",
+    );
+
+    // `'b` would have to be outlived by `'a`, and outlive `'static`.
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ forall<'a> { exists<'b> { S: Outlives<'a, 'b>, S: Outlives<'b, 'static> } } }),
+        ),
+        "No possible solution",
+    );
+
+    // `'b` can't name `'a`, but can be `'static`.
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ exists<'b> { forall<'a> { S: Outlives<'b, 'a> } } }),
+        ),
+        "Unique; for<?U0> { substitution [?0 := '^0.0], lifetime constraints [InEnvironment { environment: Env([]), goal: '^0.0: '!1_0 }] }",
+    );
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ exists<'b> { forall<'a> { S: Outlives<'b, 'a>, S: Outlives<'a, 'b> } } }),
+        ),
+        "No possible solution",
+    );
+}
+
+#[test]
+fn constraints_are_minimized() {
+    // `'a: 'c` follows from the other two, and `'static: 'b` always holds.
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({
+                exists<'a, 'b, 'c> {
+                    S: Outlives<'a, 'b>,
+                    S: Outlives<'b, 'c>,
+                    S: Outlives<'a, 'c>,
+                    S: Outlives<'static, 'b>
+                }
+            }),
+        ),
+        "Unique; for<?U0,?U0,?U0> { substitution [?0 := '^0.0, ?1 := '^0.1, ?2 := '^0.2], lifetime constraints [InEnvironment { environment: Env([]), goal: '^0.0: '^0.1 }, InEnvironment { environment: Env([]), goal: '^0.1: '^0.2 }] }",
+    );
+
+    // Type constraints are broken down into their components.
+    assert_same(
+        &solve_and_resolve_regions(
+            PROGRAM,
+            stringify!({ exists<'a, 'b, T> { S: TypeOutlives<'a, Ref<'b, T>> } }),
+        ),
+        "Unique; for<?U0,?U0,?U0> { substitution [?0 := '^0.0, ?1 := '^0.1, ?2 := ^0.2], lifetime constraints [InEnvironment { environment: Env([]), goal: '^0.1: '^0.0 }, InEnvironment { environment: Env([]), goal: ^0.2: '^0.0 }] }",
+    );
+}
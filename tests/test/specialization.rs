@@ -0,0 +1,222 @@
+//! Tests for specialization: which impl provides the value of an
+//! associated type.
+
+use super::*;
+
+#[test]
+fn default_value_of_final_impl() {
+    test! {
+        program {
+            trait Foo { type Assoc; }
+            struct A { }
+            struct S { }
+            impl<T> Foo for T { default type Assoc = A; }
+        }
+
+        // Nothing can override the value, so it is normalized as usual.
+        goal {
+            Normalize(<S as Foo>::Assoc -> A)
+        } yields {
+            "Unique; substitution []"
+        }
+    }
+}
+
+#[test]
+fn default_value_requires_reveal() {
+    test! {
+        program {
+            trait Foo { type Assoc; }
+            struct A { }
+            struct B { }
+            struct S { }
+            struct U { }
+            impl<T> Foo for T { default type Assoc = A; }
+            impl Foo for U { type Assoc = B; }
+        }
+
+        goal {
+            Normalize(<S as Foo>::Assoc -> A)
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            if (Reveal) {
+                Normalize(<S as Foo>::Assoc -> A)
+            }
+        } yields {
+            "Unique; substitution []"
+        }
+
+        // The value of a final impl needs no `Reveal`.
+        goal {
+            exists<X> {
+                Normalize(<U as Foo>::Assoc -> X)
+            }
+        } yields {
+            "Unique; substitution [?0 := B]"
+        }
+
+        // The more specialized impl overrides the default value.
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<U as Foo>::Assoc -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := B]"
+        }
+
+        goal {
+            if (Reveal) {
+                Normalize(<U as Foo>::Assoc -> A)
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // Both impls are usable for the trait itself.
+        goal {
+            forall<T> {
+                T: Foo
+            }
+        } yields {
+            "Unique; substitution []"
+        }
+    }
+}
+
+#[test]
+fn default_value_is_inherited() {
+    test! {
+        program {
+            trait Foo { type Assoc; type Other; }
+            struct A { }
+            struct B { }
+            struct C { }
+            struct S { }
+            struct Vec<T> { }
+            impl<T> Foo for T {
+                default type Assoc = A;
+                default type Other = A;
+            }
+            impl<T> Foo for Vec<T> {
+                default type Other = B;
+            }
+            impl Foo for Vec<S> {
+                type Assoc = C;
+            }
+        }
+
+        // `impl Foo for Vec<T>` doesn't override `Assoc`, so it is
+        // inherited from the blanket impl, except for `Vec<S>`.
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<Vec<A> as Foo>::Assoc -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<Vec<S> as Foo>::Assoc -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := C]"
+        }
+
+        // `Other` is overridden for all `Vec`s, and inherited by `Vec<S>`.
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<Vec<S> as Foo>::Other -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := B]"
+        }
+
+        goal {
+            exists<X> {
+                if (Reveal) {
+                    Normalize(<S as Foo>::Other -> X)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+    }
+}
+
+#[test]
+fn specializing_impl_with_projection_in_where_clause() {
+    // Finding out whether the second impl specializes the first requires
+    // normalizing `<T as Foo>::Item`, whose clauses depend on which impls
+    // specialize one another.
+    test! {
+        program {
+            trait Foo { type Item; }
+            trait Bar { }
+            struct A { }
+            struct Wrap<T> { }
+            impl Bar for A { }
+            impl<T> Foo for T { default type Item = A; }
+            impl<T> Foo for Wrap<T> where <T as Foo>::Item: Bar { type Item = A; }
+        }
+
+        goal {
+            exists<T> {
+                Normalize(<A as Foo>::Item -> T)
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> {
+                if (Reveal) {
+                    Normalize(<A as Foo>::Item -> T)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+
+        goal {
+            exists<T> {
+                if (Reveal) {
+                    Normalize(<Wrap<A> as Foo>::Item -> T)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+    }
+}
+
+#[test]
+fn specialized_impls_are_tried_first() {
+    test! {
+        program {
+            trait Foo { }
+            struct A { }
+            struct Vec<T> { }
+            impl<T> Foo for Vec<T> { }
+            impl Foo for Vec<A> { }
+        }
+
+        goal {
+            exists<T> { T: Foo }
+        } yields_all[SolverChoice::slg_default()] {
+            "substitution [?0 := Vec<A>], lifetime constraints []",
+            "for<?U0> { substitution [?0 := Vec<^0.0>], lifetime constraints [] }"
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Tests for the statistics solvers collect about their queries.
+
+use super::*;
+use chalk_solve::SolverStats;
+
+fn solve_with_stats(
+    program_text: &str,
+    goal_text: &str,
+    solver_choice: SolverChoice,
+) -> (Option<Solution<ChalkIr>>, SolverStats) {
+    with_peeled_goal(program_text, goal_text, |db, peeled_goal| {
+        let mut solver = solver_choice.into_solver();
+        let solution = solver.solve(db, peeled_goal);
+        (solution, solver.stats().unwrap().clone())
+    })
+}
+
+#[test]
+fn stats_count_work() {
+    let program = stringify!({
+        trait Foo {}
+        trait Bar {}
+        struct S {}
+        struct Vec<T> {}
+        impl Bar for S {}
+        impl<T> Foo for Vec<T> where T: Bar {}
+    });
+    let goal = stringify!({ Vec<Vec<S>>: Foo });
+
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let (solution, stats) = solve_with_stats(program, goal, solver_choice);
+        assert!(solution.is_none());
+        assert!(stats.tables_created >= 2, "{:?}", stats);
+        assert_eq!(stats.cache_misses, stats.tables_created, "{:?}", stats);
+        assert_eq!(stats.answers_produced, 0, "{:?}", stats);
+        assert_eq!(stats.truncations, 0, "{:?}", stats);
+        assert!(stats.program_clauses["Implemented"] > 0, "{:?}", stats);
+    }
+
+    let goal = stringify!({ Vec<S>: Foo });
+    for &solver_choice in &[
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        let (solution, stats) = solve_with_stats(program, goal, solver_choice);
+        assert!(solution.is_some());
+        assert!(stats.answers_produced >= 2, "{:?}", stats);
+    }
+}
+
+#[test]
+fn stats_count_truncations() {
+    let program = stringify!({
+        trait Foo {}
+        struct S {}
+        struct Box<T> {}
+        impl<T> Foo for T where Box<T>: Foo {}
+    });
+    let goal = stringify!({ S: Foo });
+
+    for &solver_choice in &[
+        SolverChoice::slg(10, None),
+        SolverChoice::recursive(10, 100),
+    ] {
+        let (_, stats) = solve_with_stats(program, goal, solver_choice);
+        assert!(stats.truncations > 0, "{:?}", stats);
+    }
+}
@@ -0,0 +1,197 @@
+use super::*;
+
+#[test]
+fn trait_alias_implemented() {
+    test! {
+        program {
+            trait Foo<T> {}
+            trait Bar {}
+            trait Baz {}
+
+            trait Alias<T> = Foo<T> + Bar where T: Baz;
+
+            struct A {}
+            struct B {}
+            struct C {}
+
+            impl Foo<B> for A {}
+            impl Bar for A {}
+            impl Baz for B {}
+
+            impl Foo<C> for C {}
+            impl Bar for C {}
+        }
+
+        goal {
+            A: Alias<B>
+        } yields {
+            "Unique"
+        }
+
+        // `C: Baz` doesn't hold
+        goal {
+            C: Alias<C>
+        } yields {
+            "No possible solution"
+        }
+
+        // `B: Foo<B>` doesn't hold
+        goal {
+            B: Alias<B>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> {
+                T: Alias<B>
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+    }
+}
+
+#[test]
+fn trait_alias_in_where_clauses() {
+    test! {
+        program {
+            trait Foo {}
+            trait Bar {}
+            trait Baz {}
+
+            trait Alias = Foo + Bar;
+
+            struct S<T> where T: Alias {}
+        }
+
+        goal {
+            forall<T> {
+                if (T: Alias) {
+                    T: Foo
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Alias) {
+                    T: Baz
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Foo; T: Bar) {
+                    T: Alias
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (FromEnv(S<T>)) {
+                    T: Bar
+                }
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn trait_alias_dyn() {
+    test! {
+        program {
+            trait Foo {}
+            trait Bar {}
+
+            trait Alias = Foo + Bar;
+        }
+
+        goal {
+            forall<'a> {
+                dyn Alias + 'a: Alias
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Alias + 'a: Foo
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Alias + 'a: Bar
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn trait_alias_assoc_ty_bound() {
+    test! {
+        program {
+            trait Iterator {
+                type Item;
+            }
+
+            trait U32Iterator = Iterator<Item = u32>;
+
+            struct Foo {}
+            struct Bar {}
+
+            impl Iterator for Foo {
+                type Item = u32;
+            }
+
+            impl Iterator for Bar {
+                type Item = i32;
+            }
+        }
+
+        goal {
+            Foo: U32Iterator
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Bar: U32Iterator
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+// Test that trait aliases cannot be implemented directly
+#[test]
+fn no_trait_alias_impls() {
+    lowering_error! {
+        program {
+            trait Foo {}
+            trait Alias = Foo;
+
+            struct S {}
+
+            impl Alias for S {}
+        } error_msg {
+            "trait impl for `Alias` does not meet well-formedness requirements"
+        }
+    }
+}
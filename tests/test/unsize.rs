@@ -586,3 +586,178 @@ fn struct_unsizing() {
         }
     }
 }
+
+#[test]
+fn dyn_upcasting() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[object_safe]
+            trait Base {}
+            #[object_safe]
+            trait Middle where Self: Base {}
+            #[object_safe]
+            trait Sub where Self: Middle {}
+            #[object_safe]
+            trait Unrelated {}
+
+            #[object_safe]
+            trait GenericBase<T> {}
+            #[object_safe]
+            trait GenericSub<T> where Self: GenericBase<Vec<T>> {}
+
+            #[object_safe]
+            trait AssocBase {
+                type Item;
+            }
+            #[object_safe]
+            trait AssocSub where Self: AssocBase {}
+            #[object_safe]
+            trait FixedSub where Self: AssocBase<Item = u32> {}
+
+            trait GenericAssocBase<T> {
+                type Item;
+            }
+            trait TwoBases
+            where
+                Self: GenericAssocBase<u32, Item = u8>,
+                Self: GenericAssocBase<u64, Item = u16>
+            {}
+
+            #[auto]
+            #[object_safe]
+            trait Auto1 {}
+
+            #[auto]
+            #[object_safe]
+            trait Auto2 {}
+
+            struct Vec<T> {}
+        }
+
+        // Upcasting to a direct supertrait
+        goal {
+            forall<'a> {
+                dyn Middle + 'a: Unsize<dyn Base + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Upcasting through several levels of supertraits, while dropping
+        // auto traits and shortening the lifetime
+        goal {
+            forall<'a> {
+                forall<'b> {
+                    dyn Sub + Auto1 + Auto2 + 'a: Unsize<dyn Base + Auto1 + 'b>
+                }
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!2_0 }]"
+        }
+
+        // Auto traits can't be added while upcasting
+        goal {
+            forall<'a> {
+                dyn Sub + Auto1 + 'a: Unsize<dyn Base + Auto1 + Auto2 + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // No downcasting, or casting to unrelated traits
+        goal {
+            forall<'a> {
+                dyn Base + 'a: Unsize<dyn Middle + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Unrelated + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The generics of the supertrait are substituted
+        goal {
+            forall<'a> {
+                dyn GenericSub<u32> + 'a: Unsize<dyn GenericBase<Vec<u32>> + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn GenericSub<u32> + 'a: Unsize<dyn GenericBase<u32> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                exists<T> {
+                    dyn GenericSub<u32> + 'a: Unsize<dyn GenericBase<T> + 'a>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := Vec<Uint(U32)>], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Associated type bindings of the source are carried over (chalk
+        // only resolves `Item` on the trait it's written on)
+        goal {
+            forall<'a> {
+                dyn AssocSub + AssocBase<Item = u32> + 'a: Unsize<dyn AssocBase<Item = u32> + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn AssocSub + AssocBase<Item = u32> + 'a: Unsize<dyn AssocBase<Item = u64> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                dyn AssocSub + 'a: Unsize<dyn AssocBase<Item = u32> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // Associated type bindings from supertrait where clauses
+        goal {
+            forall<'a> {
+                exists<T> {
+                    dyn FixedSub + 'a: Unsize<dyn AssocBase<Item = T> + 'a>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U32)], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Each binding is for the supertrait with the parameters it was
+        // written with
+        goal {
+            forall<'a> {
+                exists<T> {
+                    dyn TwoBases + 'a: Unsize<dyn GenericAssocBase<u64, Item = T> + 'a>
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U16)], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+    }
+}
@@ -1438,3 +1438,177 @@ fn coerce_unsized_struct() {
         }
     }
 }
+
+#[test]
+fn coerce_unsized_struct_builtin_pointers() {
+    // The pointer impls are builtin
+    lowering_success! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(coerce_unsized)]
+            trait CoerceUnsized<T> {}
+
+            struct Foo<T> {
+                ptr: *mut T,
+            }
+
+            impl<T, U> CoerceUnsized<Foo<U>> for Foo<T> where T: Unsize<U> {}
+        }
+    }
+}
+
+#[test]
+fn dispatch_from_dyn_pointer() {
+    lowering_success! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            impl<'a, T, U> DispatchFromDyn<&'a mut U> for &'a mut T where T: Unsize<U> {}
+            impl<T, U> DispatchFromDyn<*const U> for *const T where T: Unsize<U> {}
+        }
+    }
+
+    // T: Unsize<U> is not in the environment
+    lowering_error! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            impl<'a, T, U> DispatchFromDyn<&'a U> for &'a T {}
+        } error_msg {
+            "trait impl for `DispatchFromDyn` does not meet well-formedness requirements"
+        }
+    }
+
+    // Mutability can't change
+    lowering_error! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            impl<'a, T, U> DispatchFromDyn<&'a U> for &'a mut T where T: Unsize<U> {}
+        } error_msg {
+            "trait impl for `DispatchFromDyn` does not meet well-formedness requirements"
+        }
+    }
+
+    // Neither can the kind of pointer
+    lowering_error! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            impl<'a, T, U> DispatchFromDyn<*const U> for &'a T where T: Unsize<U> {}
+        } error_msg {
+            "trait impl for `DispatchFromDyn` does not meet well-formedness requirements"
+        }
+    }
+}
+
+#[test]
+fn dispatch_from_dyn_struct() {
+    // Zero-sized fields are ignored
+    lowering_success! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            #[phantom_data]
+            struct PhantomData<T> {}
+
+            struct Marker<T> {
+                phantom: PhantomData<T>,
+            }
+
+            struct Rc<T> {
+                ptr: *const T,
+                phantom: PhantomData<T>,
+                zst: (Marker<T>, ()),
+            }
+
+            struct Wrapper<T> {
+                rc: Rc<T>,
+            }
+
+            impl<T, U> DispatchFromDyn<Rc<U>> for Rc<T> where T: Unsize<U> {}
+            impl<T, U> DispatchFromDyn<Wrapper<U>> for Wrapper<T> where T: Unsize<U> {}
+        }
+    }
+
+    // Only one field may not be zero-sized
+    lowering_error! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            struct Rc<T> {
+                ptr: *const T,
+                count: u32,
+            }
+
+            impl<T, U> DispatchFromDyn<Rc<U>> for Rc<T> where T: Unsize<U> {}
+        } error_msg {
+            "trait impl for `DispatchFromDyn` does not meet well-formedness requirements"
+        }
+    }
+
+    // The field has to implement DispatchFromDyn
+    lowering_error! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            struct Foo<T> {
+                value: T,
+            }
+
+            impl<T, U> DispatchFromDyn<Foo<U>> for Foo<T> where T: Unsize<U> {}
+        } error_msg {
+            "trait impl for `DispatchFromDyn` does not meet well-formedness requirements"
+        }
+    }
+
+    // The struct can't change its representation
+    lowering_error! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[lang(dispatch_from_dyn)]
+            trait DispatchFromDyn<T> {}
+
+            #[repr(C)]
+            struct Rc<T> {
+                ptr: *const T,
+            }
+
+            impl<T, U> DispatchFromDyn<Rc<U>> for Rc<T> where T: Unsize<U> {}
+        } error_msg {
+            "trait impl for `DispatchFromDyn` does not meet well-formedness requirements"
+        }
+    }
+}